@@ -0,0 +1,34 @@
+-- On-chain slashing history for bonded oracles, indexed from OracleAdapter `orc_slash` events.
+CREATE TABLE IF NOT EXISTS oracle_slashes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id TEXT UNIQUE NOT NULL,  -- Soroban event id, keeps re-indexing idempotent
+    oracle_address VARCHAR(56) NOT NULL,
+    escrow_id TEXT NOT NULL,        -- hex of the on-chain escrow id bytes
+    event_type INTEGER NOT NULL,
+    amount BIGINT NOT NULL,
+    bond_before BIGINT NOT NULL,
+    harmed_address VARCHAR(56) NOT NULL,
+    ledger BIGINT NOT NULL,
+    slashed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_slash_amount CHECK (amount > 0 AND bond_before >= amount)
+);
+
+CREATE INDEX idx_oracle_slashes_oracle ON oracle_slashes(oracle_address);
+
+-- Reputation is derived from slashing history: every slash costs 10 points plus
+-- up to 40 more in proportion to the share of the bond it took.
+CREATE OR REPLACE FUNCTION oracle_reputation_from_slashes(addr VARCHAR(56))
+RETURNS DECIMAL(5,2) AS $$
+    SELECT GREATEST(
+        0,
+        100 - COALESCE(SUM(10 + 40 * amount::NUMERIC / NULLIF(bond_before, 0)), 0)
+    )::DECIMAL(5,2)
+    FROM oracle_slashes
+    WHERE oracle_address = addr;
+$$ LANGUAGE sql STABLE;
+
+UPDATE oracles SET reputation_score = oracle_reputation_from_slashes(address);
+
+COMMENT ON TABLE oracle_slashes IS 'Bond slashes applied to oracles for confirmations proven wrong';
+COMMENT ON COLUMN oracles.reputation_score IS 'Oracle reliability score (0-100), derived from on-chain slashing history';
@@ -0,0 +1,38 @@
+-- Trade documents backing a collateral, and their on-chain anchors.
+
+CREATE TYPE document_type AS ENUM (
+    'invoice', 'bill_of_lading', 'packing_list', 'insurance', 'certificate_of_origin', 'other'
+);
+
+-- Uploaded files; content_hash is the hex SHA-256 anchored on-chain
+CREATE TABLE IF NOT EXISTS collateral_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    collateral_id BIGINT NOT NULL,  -- Soroban contract collateral ID
+    doc_type document_type NOT NULL,
+    file_name TEXT NOT NULL,
+    content BYTEA NOT NULL,
+    content_hash TEXT NOT NULL,
+    uploaded_by UUID REFERENCES users(id),
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_collateral_document UNIQUE (collateral_id, content_hash)
+);
+
+CREATE INDEX idx_collateral_documents_collateral ON collateral_documents(collateral_id);
+
+-- Document set indexed from CollateralRegistry `doc_anch` events (append-only on-chain)
+CREATE TABLE IF NOT EXISTS collateral_document_anchors (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    collateral_id BIGINT NOT NULL,
+    doc_index INTEGER NOT NULL,
+    doc_type document_type NOT NULL,
+    content_hash TEXT NOT NULL,
+    attester_address VARCHAR(56) NOT NULL,
+    anchored_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_document_anchor UNIQUE (collateral_id, doc_index)
+);
+
+CREATE INDEX idx_document_anchors_hash ON collateral_document_anchors(collateral_id, content_hash);
+
+COMMENT ON TABLE collateral_document_anchors IS 'Document hashes anchored to collateral on-chain, with the attesting address';
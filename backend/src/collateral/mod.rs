@@ -4,4 +4,4 @@ pub mod service;
 
 pub use indexer::CollateralIndexer;
 pub use model::*;
-pub use service::CollateralService;
+pub use service::{CollateralService, MAX_DOCUMENT_BYTES};
@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub use crate::models::{Collateral, CollateralStatus, PaginatedResponse};
@@ -19,3 +20,50 @@ pub struct CollateralFilter {
     pub page: Option<i32>,
     pub limit: Option<i32>,
 }
+
+/// Kind of trade document, mirroring the CollateralRegistry `DocumentType`
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "document_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    Invoice,
+    BillOfLading,
+    PackingList,
+    Insurance,
+    CertificateOfOrigin,
+    Other,
+}
+
+/// Uploaded collateral document (file contents are not returned)
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CollateralDocument {
+    pub id: Uuid,
+    pub collateral_id: i64,
+    pub doc_type: DocumentType,
+    pub file_name: String,
+    pub content_hash: String,
+    pub uploaded_by: Option<Uuid>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadDocumentParams {
+    pub doc_type: DocumentType,
+    pub file_name: String,
+}
+
+/// Result of checking a stored document against the on-chain document set
+#[derive(Debug, Serialize)]
+pub struct DocumentVerification {
+    pub document_id: Uuid,
+    pub doc_type: DocumentType,
+    pub file_name: String,
+    pub content_hash: String,
+    /// Stored file still hashes to the recorded content hash
+    pub intact: bool,
+    /// Hash is anchored on-chain under the same document type
+    pub anchored: bool,
+    pub doc_index: Option<i32>,
+    pub attester_address: Option<String>,
+    pub anchored_at: Option<DateTime<Utc>>,
+}
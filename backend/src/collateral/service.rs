@@ -1,11 +1,18 @@
-use chrono::Utc;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
-use crate::collateral::model::{Collateral, CollateralFilter, CreateCollateralRequest, PaginatedResponse};
+use crate::collateral::model::{
+    Collateral, CollateralDocument, CollateralFilter, CreateCollateralRequest, DocumentType,
+    DocumentVerification, PaginatedResponse, UploadDocumentParams,
+};
 use crate::error::ApiError;
 use crate::models::CollateralStatus;
 
+/// Largest trade document accepted by the upload route (10 MiB)
+pub const MAX_DOCUMENT_BYTES: usize = 10 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct CollateralService {
     db_pool: PgPool,
@@ -183,4 +190,120 @@ impl CollateralService {
 
         Ok(())
     }
+
+    /// Store a trade document for a collateral and return its content hash
+    ///
+    /// The hash is what the owner or an attester anchors on-chain via
+    /// `CollateralRegistry::anchor_document`. Only the collateral's owner or
+    /// an admin may upload.
+    pub async fn upload_document(
+        &self,
+        id: Uuid,
+        uploaded_by: Uuid,
+        is_admin: bool,
+        params: UploadDocumentParams,
+        content: Vec<u8>,
+    ) -> Result<CollateralDocument, ApiError> {
+        if content.is_empty() {
+            return Err(ApiError::BadRequest("Document is empty".to_string()));
+        }
+        if params.file_name.trim().is_empty() {
+            return Err(ApiError::BadRequest("File name is required".to_string()));
+        }
+
+        let collateral = self.get_collateral(id).await?;
+        if collateral.owner_id != uploaded_by && !is_admin {
+            return Err(ApiError::Forbidden(
+                "Only the collateral owner can upload documents".to_string(),
+            ));
+        }
+
+        let collateral_id = onchain_id(&collateral)?;
+        let content_hash = hash_document(&content);
+
+        let document = sqlx::query_as::<_, CollateralDocument>(
+            r#"
+            INSERT INTO collateral_documents (collateral_id, doc_type, file_name, content, content_hash, uploaded_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (collateral_id, content_hash) DO NOTHING
+            RETURNING id, collateral_id, doc_type, file_name, content_hash, uploaded_by, uploaded_at
+            "#,
+        )
+        .bind(collateral_id)
+        .bind(params.doc_type)
+        .bind(params.file_name.trim())
+        .bind(&content)
+        .bind(&content_hash)
+        .bind(uploaded_by)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| ApiError::Conflict(format!("Document {} already uploaded", content_hash)))?;
+
+        Ok(document)
+    }
+
+    /// Re-hash every stored document of a collateral and match it against the
+    /// document set anchored on-chain
+    pub async fn verify_documents(&self, id: Uuid) -> Result<Vec<DocumentVerification>, ApiError> {
+        let collateral_id = self.onchain_collateral_id(id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT d.id, d.doc_type, d.file_name, d.content, d.content_hash,
+                   a.doc_index, a.attester_address, a.anchored_at
+            FROM collateral_documents d
+            LEFT JOIN collateral_document_anchors a
+                ON a.collateral_id = d.collateral_id
+               AND a.content_hash = d.content_hash
+               AND a.doc_type = d.doc_type
+            WHERE d.collateral_id = $1
+            ORDER BY d.uploaded_at
+            "#,
+        )
+        .bind(collateral_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+        let verifications = rows
+            .into_iter()
+            .map(|row| {
+                let content: Vec<u8> = row.get("content");
+                let content_hash: String = row.get("content_hash");
+                let doc_index: Option<i32> = row.get("doc_index");
+                DocumentVerification {
+                    document_id: row.get("id"),
+                    doc_type: row.get::<DocumentType, _>("doc_type"),
+                    file_name: row.get("file_name"),
+                    intact: hash_document(&content) == content_hash,
+                    content_hash,
+                    anchored: doc_index.is_some(),
+                    doc_index,
+                    attester_address: row.get("attester_address"),
+                    anchored_at: row.get::<Option<DateTime<Utc>>, _>("anchored_at"),
+                }
+            })
+            .collect();
+
+        Ok(verifications)
+    }
+
+    async fn onchain_collateral_id(&self, id: Uuid) -> Result<i64, ApiError> {
+        let collateral = self.get_collateral(id).await?;
+        onchain_id(&collateral)
+    }
+}
+
+/// On-chain CollateralRegistry ID of an indexed collateral
+fn onchain_id(collateral: &Collateral) -> Result<i64, ApiError> {
+    collateral
+        .collateral_id
+        .parse::<i64>()
+        .map_err(|_| ApiError::InternalError(format!("Invalid collateral id {}", collateral.collateral_id)))
+}
+
+/// Hex SHA-256 of a document, as anchored on-chain
+fn hash_document(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
 }
@@ -1,12 +1,17 @@
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
     Json,
 };
 use uuid::Uuid;
 use std::sync::Arc;
 
-use crate::collateral::{CollateralFilter, CreateCollateralRequest, CollateralService};
-use crate::models::{ApiResponse, Collateral, PaginatedResponse};
+use super::AuthenticatedUser;
+use crate::collateral::{
+    CollateralDocument, CollateralFilter, CollateralService, CreateCollateralRequest,
+    DocumentVerification, UploadDocumentParams,
+};
+use crate::models::{ApiResponse, Collateral, PaginatedResponse, UserRole};
 use crate::error::ApiError;
 
 pub async fn create_collateral(
@@ -60,3 +65,40 @@ pub async fn list_collateral(
         error: None,
     }))
 }
+
+pub async fn upload_collateral_document(
+    State(service): State<Arc<CollateralService>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(params): Query<UploadDocumentParams>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<CollateralDocument>>, ApiError> {
+    let document = service
+        .upload_document(
+            id,
+            user.user_id,
+            matches!(user.role, UserRole::Admin),
+            params,
+            body.to_vec(),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(document),
+        error: None,
+    }))
+}
+
+pub async fn verify_collateral_documents(
+    State(service): State<Arc<CollateralService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<DocumentVerification>>>, ApiError> {
+    let verifications = service.verify_documents(id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(verifications),
+        error: None,
+    }))
+}
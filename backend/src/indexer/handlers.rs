@@ -38,6 +38,7 @@ impl EventHandler {
             "collateral" => self.handle_collateral_event(&event_name, &data).await?,
             "escrow" => self.handle_escrow_event(&event_name, &data).await?,
             "loan" => self.handle_loan_event(&event_name, &data).await?,
+            "oracle" => self.handle_oracle_event(&event_name, &data, event).await?,
             _ => info!("Unknown contract type: {}", contract_type),
         }
 
@@ -76,7 +77,7 @@ impl EventHandler {
                     if args.is_empty() { return Err(anyhow!("Invalid args length for coll_lock")); }
                     let id = scval_to_u64(&args[0])?;
                     sqlx::query(
-                        "UPDATE collateral SET locked = true, status = 'locked' WHERE collateral_id = $1"
+                        "UPDATE collateral SET locked = true, status = CASE WHEN status = 'active' THEN 'locked' ELSE status END WHERE collateral_id = $1"
                     )
                     .bind(id as i64)
                     .execute(&self.pool)
@@ -87,10 +88,98 @@ impl EventHandler {
                  if let ScVal::Vec(Some(args)) = data {
                     if args.is_empty() { return Err(anyhow!("Invalid args length for coll_unlk")); }
                     let id = scval_to_u64(&args[0])?;
+                    // (id, escrow_id, still_locked): other escrows' liens may keep it locked
+                    let still_locked = matches!(args.get(2), Some(ScVal::Bool(true)));
                     sqlx::query(
-                        "UPDATE collateral SET locked = false, status = 'active' WHERE collateral_id = $1"
+                        r#"
+                        UPDATE collateral
+                        SET locked = $2,
+                            status = CASE
+                                WHEN status NOT IN ('active', 'locked') THEN status
+                                WHEN $2 THEN 'locked'
+                                ELSE 'active'
+                            END
+                        WHERE collateral_id = $1
+                        "#
+                    )
+                    .bind(id as i64)
+                    .bind(still_locked)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            },
+            "coll_xfer" | "coll_seiz" => {
+                // Assignment and liquidation seizure both carry (id, previous_owner, new_owner)
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.len() < 3 { return Err(anyhow!("Invalid args length for {}", name)); }
+                    let id = scval_to_u64(&args[0])?;
+                    let new_owner = scval_to_address(&args[2])?;
+
+                    let Some(owner_id) = self.get_user_id_by_address(&new_owner).await? else {
+                        info!("Collateral {} moved to unknown user {}", id, new_owner);
+                        return Ok(());
+                    };
+
+                    // Seizure also releases the lock
+                    let unlock = name == "coll_seiz";
+                    sqlx::query(
+                        r#"
+                        UPDATE collateral
+                        SET owner_id = $2,
+                            locked = CASE WHEN $3 THEN false ELSE locked END,
+                            status = CASE WHEN $3 THEN 'active' ELSE status END
+                        WHERE collateral_id = $1
+                        "#
                     )
                     .bind(id as i64)
+                    .bind(owner_id)
+                    .bind(unlock)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+            "doc_anch" => {
+                // (id, index, doc_type, content_hash, attester)
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.len() < 5 { return Err(anyhow!("Invalid args length for doc_anch")); }
+                    let id = scval_to_u64(&args[0])?;
+                    let index = scval_to_u64(&args[1])?;
+                    let doc_type = document_type_name(scval_to_u64(&args[2])?)?;
+                    let content_hash = scval_to_hex(&args[3])?;
+                    let attester = scval_to_address(&args[4])?;
+
+                    sqlx::query(
+                        r#"
+                        INSERT INTO collateral_document_anchors (collateral_id, doc_index, doc_type, content_hash, attester_address)
+                        VALUES ($1, $2, $3::document_type, $4, $5)
+                        ON CONFLICT (collateral_id, doc_index) DO NOTHING
+                        "#
+                    )
+                    .bind(id as i64)
+                    .bind(index as i32)
+                    .bind(doc_type)
+                    .bind(content_hash)
+                    .bind(attester)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+            "coll_exp" | "coll_burn" | "coll_rdm" | "coll_rlzd" => {
+                // Lifecycle transitions; redeemed and realized collateral is
+                // retired just like burned collateral
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.is_empty() { return Err(anyhow!("Invalid args length for {}", name)); }
+                    let id = scval_to_u64(&args[0])?;
+                    let status = if name == "coll_exp" { "expired" } else { "burned" };
+                    if name == "coll_rlzd" && args.len() > 1 {
+                        info!("Collateral {} realized at {}", id, scval_to_i128(&args[1])?);
+                    }
+
+                    sqlx::query(
+                        "UPDATE collateral SET status = $2::collateral_status WHERE collateral_id = $1"
+                    )
+                    .bind(id as i64)
+                    .bind(status)
                     .execute(&self.pool)
                     .await?;
                 }
@@ -189,6 +278,96 @@ impl EventHandler {
         Ok(())
     }
 
+    async fn handle_oracle_event(&self, name: &str, data: &ScVal, event: &SorobanEvent) -> Result<()> {
+        match name {
+            "orc_slash" => {
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.len() < 6 { return Err(anyhow!("Invalid args length for orc_slash")); }
+                    let oracle = scval_to_address(&args[0])?;
+                    let escrow_id = scval_to_hex(&args[1])?;
+                    let event_type = scval_to_u64(&args[2])?;
+                    let amount = scval_to_i128(&args[3])?;
+                    let bond_before = scval_to_i128(&args[4])?;
+                    let harmed = scval_to_address(&args[5])?;
+
+                    let mut tx = self.pool.begin().await?;
+                    sqlx::query(
+                        r#"
+                        INSERT INTO oracle_slashes (event_id, oracle_address, escrow_id, event_type, amount, bond_before, harmed_address, ledger)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                        ON CONFLICT (event_id) DO NOTHING
+                        "#
+                    )
+                    .bind(&event.id)
+                    .bind(&oracle)
+                    .bind(&escrow_id)
+                    .bind(event_type as i32)
+                    .bind(i64::try_from(amount).map_err(|_| anyhow!("amount too large"))?)
+                    .bind(i64::try_from(bond_before).map_err(|_| anyhow!("bond_before too large"))?)
+                    .bind(harmed)
+                    .bind(event.ledger as i64)
+                    .execute(&mut *tx)
+                    .await?;
+
+                    // Reputation is derived from the full slashing history
+                    sqlx::query(
+                        "UPDATE oracles SET reputation_score = oracle_reputation_from_slashes(address) WHERE address = $1"
+                    )
+                    .bind(&oracle)
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+
+                    info!("Oracle {} slashed {} on escrow {}", oracle, amount, escrow_id);
+                }
+            },
+            "conf_rev" => {
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.len() < 3 { return Err(anyhow!("Invalid args length for conf_rev")); }
+                    let escrow_id = scval_to_escrow_id(&args[0])?;
+                    let oracle = scval_to_address(&args[1])?;
+                    let event_type = scval_to_u64(&args[2])?;
+
+                    let mut tx = self.pool.begin().await?;
+                    let revoked = sqlx::query(
+                        r#"
+                        UPDATE oracle_events
+                        SET status = 'rejected', updated_at = NOW()
+                        WHERE escrow_id = $1 AND oracle_address = $2
+                        RETURNING id
+                        "#
+                    )
+                    .bind(escrow_id as i64)
+                    .bind(&oracle)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+                    sqlx::query(
+                        r#"
+                        INSERT INTO oracle_audit_logs (id, oracle_event_id, action, actor_address, details, created_at)
+                        VALUES ($1, $2, 'revoke', $3, $4, NOW())
+                        "#
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(revoked.map(|r| r.get::<Uuid, _>("id")))
+                    .bind(&oracle)
+                    .bind(serde_json::json!({
+                        "escrow_id": escrow_id,
+                        "event_type": event_type,
+                        "ledger": event.ledger,
+                    }))
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+
+                    info!("Oracle {} revoked confirmation on escrow {}", oracle, escrow_id);
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn get_user_id_by_address(&self, address: &str) -> Result<Option<Uuid>> {
         let rec = sqlx::query("SELECT id FROM users WHERE stellar_address = $1")
             .bind(address)
@@ -229,6 +408,37 @@ fn scval_to_i128(val: &ScVal) -> Result<i128> {
     }
 }
 
+fn scval_to_hex(val: &ScVal) -> Result<String> {
+    match val {
+        ScVal::Bytes(b) => Ok(b.iter().map(|byte| format!("{:02x}", byte)).collect()),
+        _ => Err(anyhow!("Expected Bytes")),
+    }
+}
+
+/// Escrow ids reach the oracle adapter as the 8-byte big-endian escrow number
+fn scval_to_escrow_id(val: &ScVal) -> Result<u64> {
+    match val {
+        ScVal::Bytes(b) => {
+            let bytes: [u8; 8] = b.as_slice().try_into().map_err(|_| anyhow!("Expected 8-byte escrow id"))?;
+            Ok(u64::from_be_bytes(bytes))
+        }
+        _ => Err(anyhow!("Expected Bytes")),
+    }
+}
+
+/// Map a CollateralRegistry `DocumentType` discriminant onto the `document_type` enum
+fn document_type_name(value: u64) -> Result<&'static str> {
+    match value {
+        0 => Ok("invoice"),
+        1 => Ok("bill_of_lading"),
+        2 => Ok("packing_list"),
+        3 => Ok("insurance"),
+        4 => Ok("certificate_of_origin"),
+        5 => Ok("other"),
+        _ => Err(anyhow!("Unknown document type {}", value)),
+    }
+}
+
 fn scval_to_address(val: &ScVal) -> Result<String> {
     match val {
         ScVal::Address(addr) => Ok(addr.to_string()),
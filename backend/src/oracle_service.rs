@@ -209,7 +209,10 @@ impl OracleService {
         Ok(!signature.is_empty() && !public_key.is_empty())
     }
 
-    /// Update oracle statistics after confirmation
+    /// Update oracle statistics after confirmation.
+    ///
+    /// `reputation_score` is not touched here: it is derived from on-chain
+    /// slashing history by the indexer.
     async fn update_oracle_stats(&self, oracle_address: &str) -> Result<(), Error> {
         sqlx::query(
             r#"
@@ -217,7 +220,6 @@ impl OracleService {
             SET
                 total_confirmations = total_confirmations + 1,
                 successful_confirmations = successful_confirmations + 1,
-                reputation_score = LEAST(100.0, reputation_score + 1.0),
                 updated_at = NOW()
             WHERE address = $1
             "#
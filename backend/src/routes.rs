@@ -1,8 +1,9 @@
 //! Route definitions for StelloVault API
 
-use axum::{routing::get, Router};
+use axum::{extract::DefaultBodyLimit, routing::get, Router};
 
 use crate::app_state::AppState;
+use crate::collateral::MAX_DOCUMENT_BYTES;
 use crate::handlers::*;
 
 // User routes
@@ -28,6 +29,11 @@ pub fn collateral_routes() -> Router<AppState> {
         .route("/api/collateral", get(list_collateral))
         .route("/api/collateral/:id", get(get_collateral))
         .route("/api/collateral/metadata/:hash", get(get_collateral_by_metadata))
+        .route(
+            "/api/collateral/:id/documents",
+            axum::routing::post(upload_collateral_document).layer(DefaultBodyLimit::max(MAX_DOCUMENT_BYTES)),
+        )
+        .route("/api/collateral/:id/documents/verify", get(verify_collateral_documents))
 }
 
 // Oracle routes
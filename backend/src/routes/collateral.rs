@@ -1,7 +1,8 @@
 //! Collateral route definitions
 
-use axum::{routing::get, Router};
+use axum::{extract::DefaultBodyLimit, routing::get, Router};
 
+use crate::collateral::MAX_DOCUMENT_BYTES;
 use crate::handlers::*;
 use crate::state::AppState;
 
@@ -14,4 +15,13 @@ pub fn collateral_routes() -> Router<AppState> {
             "/api/collateral/metadata/:hash",
             get(get_collateral_by_metadata),
         )
+        .route(
+            "/api/collateral/:id/documents",
+            axum::routing::post(upload_collateral_document)
+                .layer(DefaultBodyLimit::max(MAX_DOCUMENT_BYTES)),
+        )
+        .route(
+            "/api/collateral/:id/documents/verify",
+            get(verify_collateral_documents),
+        )
 }
@@ -5,7 +5,16 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, IntoVal,
+    Symbol, Val, Vec,
+};
+
+/// Maximum number of documents anchored to a single collateral
+const MAX_DOCUMENTS: u32 = 32;
+
+/// Highest storage layout this registry code understands
+const CONTRACT_VERSION: u32 = 1;
 
 /// Contract errors
 #[contracttype]
@@ -18,6 +27,17 @@ pub enum ContractError {
     CollateralNotFound = 5,
     CollateralLocked = 6,
     DuplicateMetadata = 7,
+    StaleValuation = 8,
+    NoPriceFeed = 9,
+    AlreadyFractionalized = 10,
+    NotFractionalized = 11,
+    InsufficientShares = 12,
+    CollateralClosed = 13,
+    InsufficientValue = 14,
+    NotExpired = 15,
+    TooManyDocuments = 16,
+    NoShareMajority = 17,
+    InvalidVersion = 18,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -45,6 +65,83 @@ pub struct Collateral {
     pub registered_at: u64,
     pub last_valuation_ts: u64,
     pub locked: bool,
+    /// Asset class (e.g. invoice, commodity) used for per-type LTV limits
+    pub collateral_type: u32,
+}
+
+/// Lifecycle status of a collateral, stored alongside its record
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CollateralStatus {
+    Active = 0,
+    /// All fractional shares were burned; the collateral is closed
+    Redeemed = 1,
+    /// Past its expiry timestamp; can no longer be locked
+    Expired = 2,
+    /// Retired by its owner
+    Burned = 3,
+    /// Paid out; `realized_value` holds the final amount
+    Realized = 4,
+}
+
+/// Share allowance granted by a holder to a spender
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ShareAllowance {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+/// Encumbrance of part of a collateral's value by one escrow
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Lien {
+    pub escrow_id: u64,
+    /// Units of realized value encumbered
+    pub amount: i128,
+    pub created_at: u64,
+    /// Set when a revaluation left this lien without full cover
+    pub under_covered: bool,
+}
+
+/// Kind of trade document anchored to a collateral
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocumentType {
+    Invoice = 0,
+    BillOfLading = 1,
+    PackingList = 2,
+    Insurance = 3,
+    CertificateOfOrigin = 4,
+    Other = 5,
+}
+
+/// Hash of an off-chain document vouched for by an attester
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub doc_type: DocumentType,
+    pub content_hash: BytesN<32>,
+    pub attester: Address,
+    pub anchored_at: u64,
+}
+
+/// A change of collateral ownership, e.g. an invoice assigned to a financier
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    pub from: Address,
+    pub to: Address,
+    pub timestamp: u64,
+}
+
+/// `PriceData` exactly as returned by `OracleAdapter::get_price`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PriceData {
+    pub value: i128,
+    pub updated_at: u64,
+    pub sources: u32,
 }
 
 /// Main contract for collateral registry operations
@@ -84,6 +181,7 @@ impl CollateralRegistry {
     /// * `face_value` - Face value of the collateral (must be > 0)
     /// * `expiry_ts` - Expiry timestamp (must be in future)
     /// * `metadata_hash` - SHA-256 hash of off-chain metadata
+    /// * `collateral_type` - Asset class of the collateral
     ///
     /// # Returns
     /// The sequential collateral ID
@@ -96,6 +194,7 @@ impl CollateralRegistry {
         face_value: i128,
         expiry_ts: u64,
         metadata_hash: BytesN<32>,
+        collateral_type: u32,
     ) -> Result<u64, ContractError> {
         owner.require_auth();
 
@@ -133,6 +232,7 @@ impl CollateralRegistry {
             registered_at: current_ts,
             last_valuation_ts: current_ts,
             locked: false,
+            collateral_type,
         };
 
         // Store collateral
@@ -155,14 +255,28 @@ impl CollateralRegistry {
         Ok(collateral_id)
     }
 
-    /// Lock collateral (only callable by EscrowManager contract)
+    /// Place a lien on part of a collateral's value for an escrow (only
+    /// callable by EscrowManager contract)
+    ///
+    /// A collateral can back several escrows as long as their liens fit in
+    /// its current valuation (see `get_valuation`), so a stale price feed
+    /// blocks new liens. It counts as locked while any lien is held.
+    /// Fractionalized collateral can only be pledged while its owner holds
+    /// a majority of the shares.
     ///
     /// # Arguments
     /// * `id` - Collateral ID to lock
+    /// * `escrow_id` - Escrow the lien secures
+    /// * `amount` - Units of realized value to encumber
     ///
     /// # Events
     /// Emits `CollateralLocked` event
-    pub fn lock_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+    pub fn lock_collateral(
+        env: Env,
+        id: u64,
+        escrow_id: u64,
+        amount: i128,
+    ) -> Result<(), ContractError> {
         // Only escrow manager can lock collateral
         let escrow_manager: Address = env
             .storage()
@@ -178,29 +292,61 @@ impl CollateralRegistry {
             .get(&id)
             .ok_or(ContractError::CollateralNotFound)?;
 
-        if collateral.locked {
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if Self::get_status(env.clone(), id) != CollateralStatus::Active {
+            return Err(ContractError::CollateralClosed);
+        }
+        if env.ledger().timestamp() >= collateral.expiry_ts {
+            return Err(ContractError::CollateralExpired);
+        }
+        if !Self::holds_share_majority(&env, id, &collateral.owner) {
+            return Err(ContractError::NoShareMajority);
+        }
+
+        let mut liens = Self::get_liens(env.clone(), id);
+        if liens.iter().any(|lien| lien.escrow_id == escrow_id) {
             return Err(ContractError::CollateralLocked);
         }
+        if amount > Self::get_available_value(env.clone(), id)? {
+            return Err(ContractError::InsufficientValue);
+        }
+
+        liens.push_back(Lien {
+            escrow_id,
+            amount,
+            created_at: env.ledger().timestamp(),
+            under_covered: false,
+        });
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("liens"), id), &liens);
 
         collateral.locked = true;
         env.storage().persistent().set(&id, &collateral);
 
         env.events().publish(
             (symbol_short!("coll_lock"),),
-            (id,),
+            (id, escrow_id, amount),
         );
 
         Ok(())
     }
 
-    /// Unlock collateral (only callable by EscrowManager contract)
+    /// Release an escrow's lien on a collateral (only callable by
+    /// EscrowManager contract)
+    ///
+    /// Other escrows' liens stay in place; the collateral is unlocked once
+    /// none remain.
     ///
     /// # Arguments
     /// * `id` - Collateral ID to unlock
+    /// * `escrow_id` - Escrow whose lien is released
     ///
     /// # Events
     /// Emits `CollateralUnlocked` event
-    pub fn unlock_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+    pub fn unlock_collateral(env: Env, id: u64, escrow_id: u64) -> Result<(), ContractError> {
         // Only escrow manager can unlock collateral
         let escrow_manager: Address = env
             .storage()
@@ -216,21 +362,295 @@ impl CollateralRegistry {
             .get(&id)
             .ok_or(ContractError::CollateralNotFound)?;
 
-        if !collateral.locked {
-            return Ok(()); // Already unlocked
+        let liens = Self::get_liens(env.clone(), id);
+        let mut remaining = Vec::new(&env);
+        for lien in liens.iter() {
+            if lien.escrow_id != escrow_id {
+                remaining.push_back(lien);
+            }
         }
 
-        collateral.locked = false;
+        if remaining.len() == liens.len() {
+            return Ok(()); // No lien held for this escrow
+        }
+
+        if remaining.is_empty() {
+            env.storage().persistent().remove(&(symbol_short!("liens"), id));
+        } else {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("liens"), id), &remaining);
+        }
+
+        collateral.locked = !remaining.is_empty();
         env.storage().persistent().set(&id, &collateral);
 
         env.events().publish(
             (symbol_short!("coll_unlk"),),
-            (id,),
+            (id, escrow_id, collateral.locked),
+        );
+
+        Ok(())
+    }
+
+    /// Get the liens held on a collateral, oldest (most senior) first
+    pub fn get_liens(env: Env, id: u64) -> Vec<Lien> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("liens"), id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the value of a collateral not yet encumbered by liens
+    ///
+    /// Measured against `get_valuation`, the same value EscrowManager checks
+    /// LTV against, so it fails with `StaleValuation` while the collateral's
+    /// price feed is stale.
+    pub fn get_available_value(env: Env, id: u64) -> Result<i128, ContractError> {
+        let value = Self::get_valuation(env.clone(), id)?;
+        let encumbered: i128 = Self::get_liens(env, id).iter().map(|lien| lien.amount).sum();
+        Ok((value - encumbered).max(0))
+    }
+
+    /// Transfer collateral to the winner of a liquidation (only callable by
+    /// the registered liquidation engine)
+    ///
+    /// The liquidated escrow's lien is dropped, the collateral unlocked and
+    /// its ownership reassigned in one step. Seizure is refused while other
+    /// escrows still hold liens on it. If it is fractionalized, only the
+    /// previous owner's shares pass to `new_owner`; other holders keep
+    /// theirs.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID being seized
+    /// * `escrow_id` - Escrow whose liquidation the seizure settles
+    /// * `new_owner` - Address receiving the collateral
+    ///
+    /// # Events
+    /// Emits `CollateralSeized` event
+    pub fn seize_collateral(
+        env: Env,
+        id: u64,
+        escrow_id: u64,
+        new_owner: Address,
+    ) -> Result<(), ContractError> {
+        let liquidation_engine: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "liq_engine"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        liquidation_engine.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if Self::get_liens(env.clone(), id)
+            .iter()
+            .any(|lien| lien.escrow_id != escrow_id)
+        {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        let previous_owner = collateral.owner.clone();
+        collateral.owner = new_owner.clone();
+        collateral.locked = false;
+        env.storage().persistent().set(&id, &collateral);
+        env.storage().persistent().remove(&(symbol_short!("liens"), id));
+        Self::hand_over_shares(&env, id, &previous_owner, &new_owner);
+        Self::record_assignment(&env, id, &previous_owner, &new_owner);
+
+        env.events().publish(
+            (symbol_short!("coll_seiz"),),
+            (id, previous_owner, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Assign collateral to a new owner, e.g. factoring an invoice
+    ///
+    /// Both the current owner and the assignee must authorize the transfer,
+    /// and the collateral must be unlocked and active. Once fractionalized,
+    /// the owner needs a majority of the shares, which move to the assignee
+    /// with the collateral.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to transfer
+    /// * `from` - Current owner
+    /// * `to` - New owner
+    ///
+    /// # Events
+    /// Emits `CollateralTransferred` event
+    pub fn transfer_collateral(
+        env: Env,
+        id: u64,
+        from: Address,
+        to: Address,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+        to.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.owner != from {
+            return Err(ContractError::Unauthorized);
+        }
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+        if Self::get_status(env.clone(), id) != CollateralStatus::Active {
+            return Err(ContractError::CollateralClosed);
+        }
+        if !Self::holds_share_majority(&env, id, &from) {
+            return Err(ContractError::NoShareMajority);
+        }
+
+        collateral.owner = to.clone();
+        env.storage().persistent().set(&id, &collateral);
+        Self::hand_over_shares(&env, id, &from, &to);
+        Self::record_assignment(&env, id, &from, &to);
+
+        env.events().publish(
+            (symbol_short!("coll_xfer"),),
+            (id, from, to),
+        );
+
+        Ok(())
+    }
+
+    /// Get every ownership change of a collateral, oldest first
+    ///
+    /// Covers assignments via `transfer_collateral` and liquidation seizures.
+    pub fn get_assignment_history(env: Env, id: u64) -> Vec<Assignment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("assign"), id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Anchor a document's hash to a collateral
+    ///
+    /// The document set is append-only. Attesters are the collateral owner
+    /// and addresses approved by the admin (e.g. carriers or insurers).
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID the document belongs to
+    /// * `attester` - Address vouching for the document (must authorize)
+    /// * `doc_type` - Kind of document
+    /// * `content_hash` - SHA-256 of the document contents
+    ///
+    /// # Returns
+    /// Index of the document in the collateral's document set
+    ///
+    /// # Events
+    /// Emits `DocumentAnchored` event
+    pub fn anchor_document(
+        env: Env,
+        id: u64,
+        attester: Address,
+        doc_type: DocumentType,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, ContractError> {
+        attester.require_auth();
+
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.owner != attester
+            && !Self::is_document_attester(env.clone(), attester.clone())
+        {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::get_status(env.clone(), id) == CollateralStatus::Burned {
+            return Err(ContractError::CollateralClosed);
+        }
+
+        let mut documents = Self::get_documents(env.clone(), id);
+        if documents.len() >= MAX_DOCUMENTS {
+            return Err(ContractError::TooManyDocuments);
+        }
+        if documents.iter().any(|doc| doc.content_hash == content_hash) {
+            return Err(ContractError::DuplicateMetadata);
+        }
+
+        let index = documents.len();
+        documents.push_back(Document {
+            doc_type,
+            content_hash: content_hash.clone(),
+            attester: attester.clone(),
+            anchored_at: env.ledger().timestamp(),
+        });
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("docs"), id), &documents);
+
+        env.events().publish(
+            (symbol_short!("doc_anch"),),
+            (id, index, doc_type, content_hash, attester),
+        );
+
+        Ok(index)
+    }
+
+    /// Get the documents anchored to a collateral, in anchoring order
+    pub fn get_documents(env: Env, id: u64) -> Vec<Document> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("docs"), id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Approve or revoke a third-party document attester (admin only)
+    pub fn set_document_attester(
+        env: Env,
+        attester: Address,
+        approved: bool,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        if approved {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("attester"), attester.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("attester"), attester.clone()));
+        }
+
+        env.events().publish(
+            (symbol_short!("att_set"),),
+            (attester, approved),
         );
 
         Ok(())
     }
 
+    /// Check if an address is an approved document attester
+    pub fn is_document_attester(env: Env, attester: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("attester"), attester))
+            .unwrap_or(false)
+    }
+
     /// Update collateral valuation (only callable by registered Valuation Oracle)
     ///
     /// # Arguments
@@ -265,12 +685,17 @@ impl CollateralRegistry {
             .get(&collateral_id)
             .ok_or(ContractError::CollateralNotFound)?;
 
+        if Self::is_final(&env, collateral_id) {
+            return Err(ContractError::CollateralClosed);
+        }
+
         // Update values
         collateral.realized_value = new_value;
         collateral.last_valuation_ts = env.ledger().timestamp();
 
         // Store updated collateral
         env.storage().persistent().set(&collateral_id, &collateral);
+        Self::flag_liens(&env, collateral_id, new_value);
 
         // Emit event
         env.events().publish(
@@ -307,69 +732,834 @@ impl CollateralRegistry {
             .unwrap_or(false)
     }
 
-    /// Get admin address
-    pub fn admin(env: Env) -> Address {
+    /// Get the lifecycle status of a collateral
+    pub fn get_status(env: Env, id: u64) -> CollateralStatus {
         env.storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .unwrap()
+            .persistent()
+            .get(&(symbol_short!("coll_stat"), id))
+            .unwrap_or(CollateralStatus::Active)
     }
 
-    /// Set escrow manager address (admin only)
+    /// Mark a collateral as expired once its expiry timestamp has passed
     ///
-    /// # Arguments
-    /// * `escrow_manager` - Address of the escrow manager contract
-    pub fn set_escrow_manager(env: Env, escrow_manager: Address) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Permissionless, so keepers can sweep expired collateral. Existing liens
+    /// stay in place until their escrows settle, but no new ones are accepted.
+    ///
+    /// # Events
+    /// Emits `CollateralExpired` event
+    pub fn expire_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+        let collateral: Collateral = env
             .storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .unwrap();
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
 
-        admin.require_auth();
+        if Self::get_status(env.clone(), id) != CollateralStatus::Active {
+            return Err(ContractError::CollateralClosed);
+        }
+        if env.ledger().timestamp() < collateral.expiry_ts {
+            return Err(ContractError::NotExpired);
+        }
 
         env.storage()
-            .instance()
-            .set(&Symbol::new(&env, "escrow_mgr"), &escrow_manager);
+            .persistent()
+            .set(&(symbol_short!("coll_stat"), id), &CollateralStatus::Expired);
+
+        env.events().publish(
+            (symbol_short!("coll_exp"),),
+            (id, collateral.expiry_ts),
+        );
 
         Ok(())
     }
 
-    /// Set valuation oracle address (admin only)
+    /// Retire a collateral (owner only, while unlocked)
     ///
-    /// # Arguments
-    /// * `valuation_oracle` - Address of the valuation oracle
-    pub fn set_valuation_oracle(env: Env, valuation_oracle: Address) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Active or expired collateral can be burned. Fractionalized collateral
+    /// is retired through `burn_shares` instead, as its holders own it.
+    ///
+    /// # Events
+    /// Emits `CollateralBurned` event
+    pub fn burn_collateral(env: Env, id: u64, owner: Address) -> Result<(), ContractError> {
+        owner.require_auth();
+
+        let collateral: Collateral = env
             .storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .unwrap();
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
 
-        admin.require_auth();
+        if collateral.owner != owner {
+            return Err(ContractError::Unauthorized);
+        }
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+        match Self::get_status(env.clone(), id) {
+            CollateralStatus::Active | CollateralStatus::Expired => {}
+            _ => return Err(ContractError::CollateralClosed),
+        }
+        if Self::share_supply(env.clone(), id) > 0 {
+            return Err(ContractError::AlreadyFractionalized);
+        }
 
         env.storage()
-            .instance()
-            .set(&Symbol::new(&env, "val_oracle"), &valuation_oracle);
+            .persistent()
+            .set(&(symbol_short!("coll_stat"), id), &CollateralStatus::Burned);
+
+        env.events().publish(
+            (symbol_short!("coll_burn"),),
+            (id, owner),
+        );
 
         Ok(())
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
 
-    #[test]
-    fn test_initialize() {
-        let env = Env::default();
-        let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+    /// Record the final amount a collateral paid out (only callable by
+    /// registered Valuation Oracle)
+    ///
+    /// Used once an invoice is settled. The amount becomes the collateral's
+    /// fixed value: later revaluations are refused, and liens are re-checked
+    /// against it so escrows can settle.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to realize
+    /// * `amount` - Final realized amount
+    ///
+    /// # Events
+    /// Emits `CollateralRealized` event
+    pub fn realize_collateral(env: Env, id: u64, amount: i128) -> Result<(), ContractError> {
+        let valuation_oracle: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "val_oracle"))
+            .ok_or(ContractError::Unauthorized)?;
 
-        env.as_contract(&contract_id, || {
-            let result = CollateralRegistry::initialize(env.clone(), admin.clone());
-            assert!(result.is_ok());
+        valuation_oracle.require_auth();
+
+        if amount < 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        match Self::get_status(env.clone(), id) {
+            CollateralStatus::Active | CollateralStatus::Expired => {}
+            _ => return Err(ContractError::CollateralClosed),
+        }
+
+        collateral.realized_value = amount;
+        collateral.last_valuation_ts = env.ledger().timestamp();
+        env.storage().persistent().set(&id, &collateral);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("coll_stat"), id), &CollateralStatus::Realized);
+        Self::flag_liens(&env, id, amount);
+
+        env.events().publish(
+            (symbol_short!("coll_rlzd"),),
+            (id, amount),
+        );
+
+        Ok(())
+    }
+
+    /// Split collateral into fractional shares held by its owner
+    ///
+    /// Shares can then be transferred to investors co-funding the
+    /// collateral. Opt-in and one-off per collateral. From then on the owner
+    /// can only transfer or pledge the collateral while holding a majority
+    /// of the shares.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to fractionalize
+    /// * `total_shares` - Number of shares minted to the owner (must be > 0)
+    ///
+    /// # Events
+    /// Emits `SharesMinted` event
+    pub fn fractionalize(env: Env, id: u64, total_shares: i128) -> Result<(), ContractError> {
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        collateral.owner.require_auth();
+
+        if total_shares <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+        if Self::get_status(env.clone(), id) != CollateralStatus::Active {
+            return Err(ContractError::CollateralClosed);
+        }
+        if env.storage().persistent().has(&(symbol_short!("shares"), id)) {
+            return Err(ContractError::AlreadyFractionalized);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("shares"), id), &total_shares);
+        Self::set_share_balance(&env, id, &collateral.owner, total_shares);
+
+        env.events().publish(
+            (symbol_short!("frac_mint"),),
+            (id, collateral.owner, total_shares),
+        );
+
+        Ok(())
+    }
+
+    /// Get the outstanding shares of a collateral (0 if not fractionalized)
+    pub fn share_supply(env: Env, id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("shares"), id))
+            .unwrap_or(0)
+    }
+
+    /// Get the shares of a collateral held by an address
+    pub fn share_balance(env: Env, id: u64, holder: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("share_bal"), id, holder))
+            .unwrap_or(0)
+    }
+
+    /// Transfer shares of a collateral (not while it is locked)
+    ///
+    /// # Events
+    /// Emits `SharesTransferred` event
+    pub fn transfer_shares(
+        env: Env,
+        id: u64,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+        Self::move_shares(&env, id, &from, &to, amount)
+    }
+
+    /// Allow `spender` to transfer up to `amount` of `from`'s shares until
+    /// `expiration_ledger`
+    ///
+    /// # Events
+    /// Emits `SharesApproved` event
+    pub fn approve_shares(
+        env: Env,
+        id: u64,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+
+        if amount < 0 || (amount > 0 && expiration_ledger < env.ledger().sequence()) {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        env.storage().persistent().set(
+            &(symbol_short!("share_alw"), id, from.clone(), spender.clone()),
+            &ShareAllowance {
+                amount,
+                expiration_ledger,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("frac_appr"),),
+            (id, from, spender, amount, expiration_ledger),
+        );
+
+        Ok(())
+    }
+
+    /// Get the shares `spender` may still transfer on behalf of `from`
+    pub fn share_allowance(env: Env, id: u64, from: Address, spender: Address) -> i128 {
+        let allowance: Option<ShareAllowance> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("share_alw"), id, from, spender));
+        match allowance {
+            Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => {
+                allowance.amount
+            }
+            _ => 0,
+        }
+    }
+
+    /// Transfer shares on behalf of a holder using an allowance
+    ///
+    /// # Events
+    /// Emits `SharesTransferred` event
+    pub fn transfer_shares_from(
+        env: Env,
+        id: u64,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        spender.require_auth();
+
+        let allowance = Self::share_allowance(env.clone(), id, from.clone(), spender.clone());
+        if amount > allowance {
+            return Err(ContractError::InsufficientShares);
+        }
+
+        Self::move_shares(&env, id, &from, &to, amount)?;
+
+        let key = (symbol_short!("share_alw"), id, from, spender);
+        let mut stored: ShareAllowance = env.storage().persistent().get(&key).unwrap();
+        stored.amount -= amount;
+        env.storage().persistent().set(&key, &stored);
+
+        Ok(())
+    }
+
+    /// Burn shares of a collateral (not while it is locked)
+    ///
+    /// Burning the last outstanding share redeems the collateral, closing it
+    /// for further locking.
+    ///
+    /// # Events
+    /// Emits `SharesBurned` event, and `CollateralRedeemed` when the last
+    /// share is burned
+    pub fn burn_shares(
+        env: Env,
+        id: u64,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+
+        let supply = Self::tradable_supply(&env, id, amount)?;
+        let balance = Self::share_balance(env.clone(), id, from.clone());
+        if amount > balance {
+            return Err(ContractError::InsufficientShares);
+        }
+
+        Self::set_share_balance(&env, id, &from, balance - amount);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("shares"), id), &(supply - amount));
+
+        env.events().publish(
+            (symbol_short!("frac_burn"),),
+            (id, from, amount),
+        );
+
+        if supply == amount {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("coll_stat"), id), &CollateralStatus::Redeemed);
+
+            env.events().publish(
+                (symbol_short!("coll_rdm"),),
+                (id,),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap()
+    }
+
+    /// Set escrow manager address (admin only)
+    ///
+    /// # Arguments
+    /// * `escrow_manager` - Address of the escrow manager contract
+    pub fn set_escrow_manager(env: Env, escrow_manager: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "escrow_mgr"), &escrow_manager);
+
+        Ok(())
+    }
+
+    /// Set liquidation engine address (admin only)
+    ///
+    /// # Arguments
+    /// * `liquidation_engine` - Address of the risk assessment contract
+    pub fn set_liquidation_engine(
+        env: Env,
+        liquidation_engine: Address,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "liq_engine"), &liquidation_engine);
+
+        Ok(())
+    }
+
+    /// Set valuation oracle address (admin only)
+    ///
+    /// # Arguments
+    /// * `valuation_oracle` - Address of the valuation oracle
+    pub fn set_valuation_oracle(env: Env, valuation_oracle: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "val_oracle"), &valuation_oracle);
+
+        Ok(())
+    }
+
+    /// Set governance contract address
+    ///
+    /// The admin sets it once; afterwards only the current governance can
+    /// replace it.
+    ///
+    /// # Arguments
+    /// * `governance` - Address of the governance contract allowed to upgrade the registry
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&symbol_short!("gov"));
+        match current {
+            Some(current) => current.require_auth(),
+            None => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("admin"))
+                    .ok_or(ContractError::Unauthorized)?;
+                admin.require_auth();
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
+        Ok(())
+    }
+
+    /// Swap the registry's code for an uploaded Wasm (governance only)
+    /// Collateral records, liens and fractional shares stay in storage and carry
+    /// over to the new code.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    /// Record that stored collateral has been brought up to `version` (governance only)
+    /// Run after `upgrade` once records, liens and shares match the new layout
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("version"), &version);
+
+        env.events()
+            .publish((symbol_short!("migrated"),), (current, version));
+
+        Ok(())
+    }
+
+    /// Storage layout version the registry's records are in
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
+
+    /// Set the oracle adapter publishing aggregated price feeds (admin only)
+    ///
+    /// # Arguments
+    /// * `price_oracle` - Address of the OracleAdapter contract
+    pub fn set_price_oracle(env: Env, price_oracle: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "price_orc"), &price_oracle);
+
+        Ok(())
+    }
+
+    /// Bind collateral to a price feed of the oracle adapter (admin only)
+    ///
+    /// Several collaterals can share a feed, e.g. lots of the same commodity.
+    ///
+    /// # Arguments
+    /// * `collateral_id` - ID of the collateral to bind
+    /// * `feed_id` - Feed identifier in the oracle adapter
+    ///
+    /// # Events
+    /// Emits `CollateralFeedSet` event
+    pub fn set_price_feed(
+        env: Env,
+        collateral_id: u64,
+        feed_id: Bytes,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&collateral_id) {
+            return Err(ContractError::CollateralNotFound);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("coll_feed"), collateral_id), &feed_id);
+
+        env.events().publish(
+            (symbol_short!("coll_feed"),),
+            (collateral_id, feed_id),
+        );
+
+        Ok(())
+    }
+
+    /// Get the price feed a collateral is bound to, if any
+    pub fn get_price_feed(env: Env, collateral_id: u64) -> Option<Bytes> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("coll_feed"), collateral_id))
+    }
+
+    /// Pull the aggregated price of a collateral's feed into its realized value
+    ///
+    /// Callable by anyone; the value comes from the oracle adapter's median.
+    ///
+    /// # Arguments
+    /// * `collateral_id` - ID of the collateral to revalue
+    ///
+    /// # Returns
+    /// The new realized value
+    ///
+    /// # Events
+    /// Emits `CollateralValued` event
+    pub fn sync_valuation(env: Env, collateral_id: u64) -> Result<i128, ContractError> {
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&collateral_id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if Self::is_final(&env, collateral_id) {
+            return Err(ContractError::CollateralClosed);
+        }
+
+        let price =
+            Self::fetch_feed_price(&env, collateral_id)?.ok_or(ContractError::NoPriceFeed)?;
+
+        collateral.realized_value = price.value;
+        collateral.last_valuation_ts = price.updated_at;
+        env.storage().persistent().set(&collateral_id, &collateral);
+        Self::flag_liens(&env, collateral_id, price.value);
+
+        env.events().publish(
+            (symbol_short!("coll_val"),),
+            (collateral_id, price.value),
+        );
+
+        Ok(price.value)
+    }
+
+    /// Get the current value of a collateral
+    ///
+    /// Collateral bound to a price feed is valued at the feed's aggregated
+    /// price, and stale prices are refused. Other collateral, and collateral
+    /// that has been realized, is valued at its stored realized value.
+    ///
+    /// # Arguments
+    /// * `collateral_id` - ID of the collateral to value
+    pub fn get_valuation(env: Env, collateral_id: u64) -> Result<i128, ContractError> {
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&collateral_id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if Self::get_status(env.clone(), collateral_id) == CollateralStatus::Realized {
+            return Ok(collateral.realized_value);
+        }
+
+        match Self::fetch_feed_price(&env, collateral_id)? {
+            Some(price) => Ok(price.value),
+            None => Ok(collateral.realized_value),
+        }
+    }
+
+    /// Fetch the fresh aggregated price of a collateral's feed
+    ///
+    /// Returns None when the collateral is not bound to a feed.
+    fn fetch_feed_price(env: &Env, collateral_id: u64) -> Result<Option<PriceData>, ContractError> {
+        let feed_id: Bytes = match Self::get_price_feed(env.clone(), collateral_id) {
+            Some(feed_id) => feed_id,
+            None => return Ok(None),
+        };
+        let price_oracle: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(env, "price_orc"))
+            .ok_or(ContractError::NoPriceFeed)?;
+
+        let price_args: Vec<Val> = Vec::from_array(env, [feed_id.into_val(env)]);
+        match env.try_invoke_contract::<PriceData, soroban_sdk::Error>(
+            &price_oracle,
+            &Symbol::new(env, "get_price"),
+            price_args,
+        ) {
+            Ok(Ok(price)) if price.value > 0 => Ok(Some(price)),
+            _ => Err(ContractError::StaleValuation),
+        }
+    }
+
+    /// Check a share operation of `amount` on a fractionalized, unlocked
+    /// collateral and return its outstanding supply
+    fn tradable_supply(env: &Env, id: u64, amount: i128) -> Result<i128, ContractError> {
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("shares"), id))
+            .ok_or(ContractError::NotFractionalized)
+    }
+
+    fn move_shares(
+        env: &Env,
+        id: u64,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        Self::tradable_supply(env, id, amount)?;
+
+        let from_balance = Self::share_balance(env.clone(), id, from.clone());
+        if amount > from_balance {
+            return Err(ContractError::InsufficientShares);
+        }
+
+        Self::set_share_balance(env, id, from, from_balance - amount);
+        let to_balance = Self::share_balance(env.clone(), id, to.clone());
+        Self::set_share_balance(env, id, to, to_balance + amount);
+
+        env.events().publish(
+            (symbol_short!("frac_xfer"),),
+            (id, from.clone(), to.clone(), amount),
+        );
+
+        Ok(())
+    }
+
+    /// Whether a collateral's value is settled for good (burned or realized)
+    fn is_final(env: &Env, id: u64) -> bool {
+        matches!(
+            Self::get_status(env.clone(), id),
+            CollateralStatus::Burned | CollateralStatus::Realized
+        )
+    }
+
+    /// Re-check lien cover after a revaluation
+    ///
+    /// Liens are covered in order of seniority; those the new value no longer
+    /// covers in full are flagged, and flags are cleared once cover returns.
+    fn flag_liens(env: &Env, id: u64, realized_value: i128) {
+        let liens = Self::get_liens(env.clone(), id);
+        if liens.is_empty() {
+            return;
+        }
+
+        let mut covered: i128 = 0;
+        let mut updated = Vec::new(env);
+        for mut lien in liens.iter() {
+            covered += lien.amount;
+            let under_covered = covered > realized_value;
+            if under_covered && !lien.under_covered {
+                env.events().publish(
+                    (symbol_short!("lien_uncv"),),
+                    (id, lien.escrow_id, lien.amount, realized_value),
+                );
+            }
+            lien.under_covered = under_covered;
+            updated.push_back(lien);
+        }
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("liens"), id), &updated);
+    }
+
+    fn record_assignment(env: &Env, id: u64, from: &Address, to: &Address) {
+        let key = (symbol_short!("assign"), id);
+        let mut history: Vec<Assignment> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        history.push_back(Assignment {
+            from: from.clone(),
+            to: to.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&key, &history);
+    }
+
+    /// Whether `holder` may act for a collateral alone: always for whole
+    /// collateral, and only with more than half the outstanding shares once
+    /// fractionalized
+    fn holds_share_majority(env: &Env, id: u64, holder: &Address) -> bool {
+        let supply = Self::share_supply(env.clone(), id);
+        supply == 0 || Self::share_balance(env.clone(), id, holder.clone()) * 2 > supply
+    }
+
+    /// Move the outgoing owner's shares to the new owner; other holders keep
+    /// theirs
+    fn hand_over_shares(env: &Env, id: u64, from: &Address, to: &Address) {
+        let moved = Self::share_balance(env.clone(), id, from.clone());
+        if moved == 0 || from == to {
+            return;
+        }
+
+        Self::set_share_balance(env, id, from, 0);
+        let to_balance = Self::share_balance(env.clone(), id, to.clone());
+        Self::set_share_balance(env, id, to, to_balance + moved);
+
+        env.events().publish(
+            (symbol_short!("frac_xfer"),),
+            (id, from.clone(), to.clone(), moved),
+        );
+    }
+
+    fn set_share_balance(env: &Env, id: u64, holder: &Address, balance: i128) {
+        let key = (symbol_short!("share_bal"), id, holder.clone());
+        if balance == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &balance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contracterror, testutils::Address as _, testutils::Ledger as _, Env};
+
+    #[contracterror]
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum MockPriceError {
+        StalePrice = 21,
+    }
+
+    /// Oracle adapter stand-in publishing a single price that goes stale after an hour
+    #[contract]
+    pub struct MockPriceOracle;
+
+    #[contractimpl]
+    impl MockPriceOracle {
+        pub fn set_price(env: Env, value: i128) {
+            let price = PriceData {
+                value,
+                updated_at: env.ledger().timestamp(),
+                sources: 3,
+            };
+            env.storage().instance().set(&symbol_short!("price"), &price);
+        }
+
+        pub fn get_price(env: Env, _feed_id: Bytes) -> Result<PriceData, MockPriceError> {
+            let price: PriceData = env.storage().instance().get(&symbol_short!("price")).unwrap();
+            if env.ledger().timestamp() > price.updated_at + 3_600 {
+                return Err(MockPriceError::StalePrice);
+            }
+            Ok(price)
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::initialize(env.clone(), admin.clone());
+            assert!(result.is_ok());
 
             let admin_result = CollateralRegistry::admin(env.clone());
             assert_eq!(admin_result, admin);
@@ -397,6 +1587,7 @@ mod test {
                 1000,
                 future_ts,
                 metadata_hash,
+                0,
             );
 
             assert!(result.is_ok());
@@ -434,6 +1625,7 @@ mod test {
                 1000,
                 future_ts,
                 metadata_hash,
+                0,
             ).unwrap();
 
             // Update valuation
@@ -466,6 +1658,7 @@ mod test {
                 0, // Invalid amount
                 future_ts,
                 metadata_hash,
+                0,
             );
 
             assert_eq!(result, Err(ContractError::InvalidAmount));
@@ -491,6 +1684,7 @@ mod test {
                 1000,
                 past_ts,
                 metadata_hash,
+                0,
             );
 
             assert_eq!(result, Err(ContractError::CollateralExpired));
@@ -518,6 +1712,7 @@ mod test {
                 1000,
                 future_ts,
                 metadata_hash.clone(),
+                0,
             ).unwrap();
 
             // Try to register duplicate
@@ -527,6 +1722,7 @@ mod test {
                 2000,
                 future_ts,
                 metadata_hash, // Same hash
+                0,
             );
 
             assert_eq!(result, Err(ContractError::DuplicateMetadata));
@@ -555,15 +1751,16 @@ mod test {
                 1000,
                 future_ts,
                 metadata_hash,
+                0,
             ).unwrap();
 
             // Lock collateral
-            let lock_result = CollateralRegistry::lock_collateral(env.clone(), collateral_id);
+            let lock_result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, 1000);
             assert!(lock_result.is_ok());
             assert!(CollateralRegistry::is_locked(env.clone(), collateral_id));
 
             // Unlock collateral
-            let unlock_result = CollateralRegistry::unlock_collateral(env.clone(), collateral_id);
+            let unlock_result = CollateralRegistry::unlock_collateral(env.clone(), collateral_id, 1);
             assert!(unlock_result.is_ok());
             assert!(!CollateralRegistry::is_locked(env.clone(), collateral_id));
         });
@@ -580,7 +1777,7 @@ mod test {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
             CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager).unwrap();
 
-            let result = CollateralRegistry::lock_collateral(env.clone(), 999);
+            let result = CollateralRegistry::lock_collateral(env.clone(), 999, 1, 1000);
             assert_eq!(result, Err(ContractError::CollateralNotFound));
         });
     }
@@ -605,11 +1802,556 @@ mod test {
                 1000,
                 future_ts,
                 metadata_hash,
+                0,
             ).unwrap();
 
             // Try to lock with unauthorized address (no escrow manager set)
-            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id);
+            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, 1000);
+            assert_eq!(result, Err(ContractError::Unauthorized));
+        });
+    }
+
+    #[test]
+    fn test_seize_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let escrow_manager = Address::generate(&env);
+        let liquidation_engine = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let winner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&admin);
+        client.set_escrow_manager(&escrow_manager);
+        client.set_liquidation_engine(&liquidation_engine);
+
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.lock_collateral(&collateral_id, &1, &1000);
+
+        client.seize_collateral(&collateral_id, &1, &winner);
+
+        let collateral = client.get_collateral(&collateral_id).unwrap();
+        assert_eq!(collateral.owner, winner);
+        assert!(!collateral.locked);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_seize_collateral_refused_while_other_liens_held() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        client.set_liquidation_engine(&Address::generate(&env));
+
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.lock_collateral(&collateral_id, &1, &400);
+        client.lock_collateral(&collateral_id, &2, &400);
+
+        // Escrow 2 still relies on the collateral
+        client.seize_collateral(&collateral_id, &1, &Address::generate(&env));
+    }
+
+    #[test]
+    fn test_seize_collateral_no_engine() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let winner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin).unwrap();
+
+            let result = CollateralRegistry::seize_collateral(env.clone(), 1, 1, winner);
             assert_eq!(result, Err(ContractError::Unauthorized));
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_price_feed_valuation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let price_oracle = env.register_contract(None, MockPriceOracle);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+        MockPriceOracleClient::new(&env, &price_oracle).set_price(&1_500);
+
+        client.initialize(&admin);
+        client.set_price_oracle(&price_oracle);
+
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+
+        // Unbound collateral keeps its stored value
+        assert_eq!(client.get_valuation(&collateral_id), 1000);
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::sync_valuation(env.clone(), collateral_id),
+                Err(ContractError::NoPriceFeed)
+            );
+        });
+
+        let feed_id = Bytes::from_slice(&env, b"cocoa");
+        client.set_price_feed(&collateral_id, &feed_id);
+        assert_eq!(client.get_price_feed(&collateral_id), Some(feed_id));
+        assert_eq!(client.get_valuation(&collateral_id), 1_500);
+
+        assert_eq!(client.sync_valuation(&collateral_id), 1_500);
+        assert_eq!(client.get_collateral(&collateral_id).unwrap().realized_value, 1_500);
+
+        env.ledger().with_mut(|li| li.timestamp += 3_601);
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::get_valuation(env.clone(), collateral_id),
+                Err(ContractError::StaleValuation)
+            );
+            assert_eq!(
+                CollateralRegistry::sync_valuation(env.clone(), collateral_id),
+                Err(ContractError::StaleValuation)
+            );
+        });
+    }
+
+    #[test]
+    fn test_liens_use_feed_valuation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let price_oracle = env.register_contract(None, MockPriceOracle);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+        MockPriceOracleClient::new(&env, &price_oracle).set_price(&1_500);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        client.set_price_oracle(&price_oracle);
+
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.set_price_feed(&collateral_id, &Bytes::from_slice(&env, b"cocoa"));
+
+        // Headroom follows the feed price, not the stored value of 1000
+        client.lock_collateral(&collateral_id, &1, &1_200);
+        assert_eq!(client.get_available_value(&collateral_id), 300);
+
+        env.ledger().with_mut(|li| li.timestamp += 3_601);
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::get_available_value(env.clone(), collateral_id),
+                Err(ContractError::StaleValuation)
+            );
+            assert_eq!(
+                CollateralRegistry::lock_collateral(env.clone(), collateral_id, 2, 100),
+                Err(ContractError::StaleValuation)
+            );
+        });
+    }
+
+    fn setup_fractional(env: &Env) -> (CollateralRegistryClient<'_>, Address, u64) {
+        env.mock_all_auths();
+        let owner = Address::generate(env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(env, &contract_id);
+
+        client.initialize(&Address::generate(env));
+        client.set_escrow_manager(&Address::generate(env));
+
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.fractionalize(&collateral_id, &100);
+
+        (client, owner, collateral_id)
+    }
+
+    #[test]
+    fn test_fractional_shares_cofund_collateral() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+        let investor_a = Address::generate(&env);
+        let investor_b = Address::generate(&env);
+        let market = Address::generate(&env);
+
+        assert_eq!(client.share_supply(&collateral_id), 100);
+        assert_eq!(client.share_balance(&collateral_id, &owner), 100);
+
+        client.transfer_shares(&collateral_id, &owner, &investor_a, &60);
+
+        // A marketplace moves shares under an allowance
+        let expiration = env.ledger().sequence() + 100;
+        client.approve_shares(&collateral_id, &owner, &market, &30, &expiration);
+        client.transfer_shares_from(&collateral_id, &market, &owner, &investor_b, &25);
+        assert_eq!(client.share_allowance(&collateral_id, &owner, &market), 5);
+
+        assert_eq!(client.share_balance(&collateral_id, &owner), 15);
+        assert_eq!(client.share_balance(&collateral_id, &investor_a), 60);
+        assert_eq!(client.share_balance(&collateral_id, &investor_b), 25);
+        assert_eq!(client.share_supply(&collateral_id), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_shares_frozen_while_locked() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+
+        client.lock_collateral(&collateral_id, &1, &1000);
+        client.transfer_shares(&collateral_id, &owner, &Address::generate(&env), &10);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #10)")]
+    fn test_fractionalize_only_once() {
+        let env = Env::default();
+        let (client, _owner, collateral_id) = setup_fractional(&env);
+
+        client.fractionalize(&collateral_id, &50);
+    }
+
+    #[test]
+    fn test_minority_owner_cannot_pledge_fractional_collateral() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+
+        client.transfer_shares(&collateral_id, &owner, &Address::generate(&env), &60);
+
+        env.as_contract(&client.address, || {
+            assert_eq!(
+                CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, 100),
+                Err(ContractError::NoShareMajority)
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #17)")]
+    fn test_minority_owner_cannot_transfer_fractional_collateral() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+
+        client.transfer_shares(&collateral_id, &owner, &Address::generate(&env), &50);
+        client.transfer_collateral(&collateral_id, &owner, &Address::generate(&env));
+    }
+
+    #[test]
+    fn test_fractional_collateral_moves_owner_shares_only() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+        let investor = Address::generate(&env);
+        let financier = Address::generate(&env);
+        let winner = Address::generate(&env);
+
+        client.transfer_shares(&collateral_id, &owner, &investor, &40);
+
+        // A majority holder may still assign it, taking their stake along
+        client.transfer_collateral(&collateral_id, &owner, &financier);
+        assert_eq!(client.share_balance(&collateral_id, &owner), 0);
+        assert_eq!(client.share_balance(&collateral_id, &financier), 60);
+
+        // Seizure hands over the owner's stake and leaves the investor's
+        client.set_liquidation_engine(&Address::generate(&env));
+        client.lock_collateral(&collateral_id, &1, &500);
+        client.seize_collateral(&collateral_id, &1, &winner);
+        assert_eq!(client.get_collateral(&collateral_id).unwrap().owner, winner);
+        assert_eq!(client.share_balance(&collateral_id, &financier), 0);
+        assert_eq!(client.share_balance(&collateral_id, &winner), 60);
+        assert_eq!(client.share_balance(&collateral_id, &investor), 40);
+        assert_eq!(client.share_supply(&collateral_id), 100);
+    }
+
+    #[test]
+    fn test_burning_all_shares_redeems_collateral() {
+        let env = Env::default();
+        let (client, owner, collateral_id) = setup_fractional(&env);
+        let investor = Address::generate(&env);
+
+        client.transfer_shares(&collateral_id, &owner, &investor, &40);
+        client.burn_shares(&collateral_id, &owner, &60);
+        assert_eq!(client.get_status(&collateral_id), CollateralStatus::Active);
+
+        client.burn_shares(&collateral_id, &investor, &40);
+        assert_eq!(client.share_supply(&collateral_id), 0);
+        assert_eq!(
+            client.get_status(&collateral_id),
+            CollateralStatus::Redeemed
+        );
+
+        env.as_contract(&client.address, || {
+            assert_eq!(
+                CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, 1000),
+                Err(ContractError::CollateralClosed)
+            );
+        });
+    }
+
+    #[test]
+    fn test_transfer_collateral_records_assignment() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let financier = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+
+        client.transfer_collateral(&collateral_id, &owner, &financier);
+
+        // Both parties had to sign
+        let auths = env.auths();
+        assert!(auths.iter().any(|(addr, _)| *addr == owner));
+        assert!(auths.iter().any(|(addr, _)| *addr == financier));
+
+        assert_eq!(client.get_collateral(&collateral_id).unwrap().owner, financier);
+        let history = client.get_assignment_history(&collateral_id);
+        assert_eq!(history.len(), 1);
+        let assignment = history.get(0).unwrap();
+        assert_eq!(assignment.from, owner);
+        assert_eq!(assignment.to, financier);
+
+        // The previous owner can no longer move it
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::transfer_collateral(
+                    env.clone(),
+                    collateral_id,
+                    owner.clone(),
+                    Address::generate(&env),
+                ),
+                Err(ContractError::Unauthorized)
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_transfer_locked_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.lock_collateral(&collateral_id, &1, &1000);
+
+        client.transfer_collateral(&collateral_id, &owner, &Address::generate(&env));
+    }
+
+    #[test]
+    fn test_liens_share_collateral_value() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        client.set_valuation_oracle(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+
+        // Two escrows encumber parts of the same collateral
+        client.lock_collateral(&collateral_id, &1, &600);
+        client.lock_collateral(&collateral_id, &2, &300);
+        assert_eq!(client.get_available_value(&collateral_id), 100);
+        assert_eq!(client.get_liens(&collateral_id).len(), 2);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::lock_collateral(env.clone(), collateral_id, 3, 200),
+                Err(ContractError::InsufficientValue)
+            );
+        });
+
+        // A drop in value leaves the junior lien under-covered
+        client.update_valuation(&collateral_id, &800);
+        let liens = client.get_liens(&collateral_id);
+        assert!(!liens.get(0).unwrap().under_covered);
+        assert!(liens.get(1).unwrap().under_covered);
+        assert_eq!(client.get_available_value(&collateral_id), 0);
+
+        // Releasing one escrow keeps the other lien in place
+        client.unlock_collateral(&collateral_id, &1);
+        assert!(client.is_locked(&collateral_id));
+        assert_eq!(client.get_available_value(&collateral_id), 500);
+
+        client.update_valuation(&collateral_id, &800);
+        assert!(!client.get_liens(&collateral_id).get(0).unwrap().under_covered);
+
+        client.unlock_collateral(&collateral_id, &2);
+        assert!(!client.is_locked(&collateral_id));
+        assert!(client.get_liens(&collateral_id).is_empty());
+    }
+
+    #[test]
+    fn test_expire_and_burn_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        let expiry_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &expiry_ts, &metadata_hash, &0);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::expire_collateral(env.clone(), collateral_id),
+                Err(ContractError::NotExpired)
+            );
+        });
+
+        // Past expiry it can no longer be locked, even before anyone sweeps it
+        env.ledger().with_mut(|li| li.timestamp = expiry_ts);
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, 100),
+                Err(ContractError::CollateralExpired)
+            );
+        });
+
+        client.expire_collateral(&collateral_id);
+        assert_eq!(client.get_status(&collateral_id), CollateralStatus::Expired);
+
+        client.burn_collateral(&collateral_id, &owner);
+        assert_eq!(client.get_status(&collateral_id), CollateralStatus::Burned);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::expire_collateral(env.clone(), collateral_id),
+                Err(ContractError::CollateralClosed)
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_burn_locked_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.lock_collateral(&collateral_id, &1, &500);
+
+        client.burn_collateral(&collateral_id, &owner);
+    }
+
+    #[test]
+    fn test_realize_collateral_fixes_value() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        client.set_escrow_manager(&Address::generate(&env));
+        client.set_valuation_oracle(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+        client.lock_collateral(&collateral_id, &1, &800);
+
+        // The invoice paid out short of its lien
+        client.realize_collateral(&collateral_id, &700);
+        assert_eq!(client.get_status(&collateral_id), CollateralStatus::Realized);
+        assert_eq!(client.get_valuation(&collateral_id), 700);
+        assert!(client.get_liens(&collateral_id).get(0).unwrap().under_covered);
+
+        // The escrow can still settle against it
+        client.unlock_collateral(&collateral_id, &1);
+        assert!(!client.is_locked(&collateral_id));
+
+        assert!(client.try_update_valuation(&collateral_id, &900).is_err());
+        assert_eq!(client.get_collateral(&collateral_id).unwrap().realized_value, 700);
+    }
+
+    #[test]
+    fn test_anchor_documents() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let owner = Address::generate(&env);
+        let carrier = Address::generate(&env);
+        let contract_id = env.register_contract(None, CollateralRegistry);
+        let client = CollateralRegistryClient::new(&env, &contract_id);
+
+        client.initialize(&Address::generate(&env));
+        let future_ts = env.ledger().timestamp() + 86400;
+        let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+        let collateral_id = client.register_collateral(&owner, &1000, &future_ts, &metadata_hash, &0);
+
+        let invoice = BytesN::from_array(&env, &[2; 32]);
+        let bill_of_lading = BytesN::from_array(&env, &[3; 32]);
+        assert_eq!(
+            client.anchor_document(&collateral_id, &owner, &DocumentType::Invoice, &invoice),
+            0
+        );
+
+        // Third parties attest only once approved
+        let lading_type = DocumentType::BillOfLading;
+        assert!(client
+            .try_anchor_document(&collateral_id, &carrier, &lading_type, &bill_of_lading)
+            .is_err());
+        client.set_document_attester(&carrier, &true);
+        assert_eq!(
+            client.anchor_document(&collateral_id, &carrier, &lading_type, &bill_of_lading),
+            1
+        );
+
+        let documents = client.get_documents(&collateral_id);
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents.get(0).unwrap().doc_type, DocumentType::Invoice);
+        let lading = documents.get(1).unwrap();
+        assert_eq!(lading.doc_type, DocumentType::BillOfLading);
+        assert_eq!(lading.content_hash, bill_of_lading);
+        assert_eq!(lading.attester, carrier);
+
+        // The same file cannot be anchored twice
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                CollateralRegistry::anchor_document(
+                    env.clone(),
+                    collateral_id,
+                    owner.clone(),
+                    DocumentType::Other,
+                    invoice.clone(),
+                ),
+                Err(ContractError::DuplicateMetadata)
+            );
+        });
+    }
+}
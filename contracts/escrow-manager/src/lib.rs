@@ -7,10 +7,16 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, Env, IntoVal,
-    Symbol, Val, Vec,
+    contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env,
+    IntoVal, Symbol, Val, Vec,
 };
 
+/// Default maximum loan-to-value for collateral types without an override (80%)
+const DEFAULT_MAX_LTV_BPS: u32 = 8000;
+
+/// Escrow storage layout this build expects; governance records it through `migrate`
+const CONTRACT_VERSION: u32 = 1;
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -21,6 +27,10 @@ pub enum EscrowStatus {
     Active = 0,
     Released = 1,
     Refunded = 2,
+    /// Frozen while an arbitrator rules on a dispute
+    Disputed = 3,
+    /// Settled by an arbitration ruling
+    Resolved = 4,
 }
 
 #[contracttype]
@@ -38,6 +48,22 @@ pub enum ContractError {
     InvalidOracleSet = 10,
     InvalidThreshold = 11,
     ConsensusNotMet = 12,
+    SwapAdapterNotSet = 13,
+    EscrowDisputed = 14,
+    DisputeNotFound = 15,
+    InvalidRuling = 16,
+    InvalidMilestones = 17,
+    MilestoneOutOfOrder = 18,
+    MilestoneNotFound = 19,
+    HasMilestones = 20,
+    CollateralNotFound = 21,
+    CollateralOwnerMismatch = 22,
+    CollateralExpiresTooSoon = 23,
+    LtvExceeded = 24,
+    CollateralLocked = 25,
+    InvalidLtv = 26,
+    InvalidSlash = 27,
+    InvalidVersion = 28,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -96,6 +122,23 @@ pub struct Escrow {
     pub oracle_set: Vec<Address>,
 }
 
+/// Local mirror of CollateralRegistry's Collateral for cross-contract deserialization.
+/// Field names and types must match the collateral-registry definition exactly.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CollateralRecord {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub collateral_type: u32,
+}
+
 /// Local mirror of OracleAdapter's ConfirmationData for cross-contract deserialization.
 /// Field names and types must match the oracle-adapter definition exactly.
 #[contracttype]
@@ -107,6 +150,57 @@ pub struct ConfirmationData {
     pub oracle: Address,
     pub timestamp: u64,
     pub verified: bool,
+    pub expires_at: u64,
+    pub consumed: bool,
+}
+
+/// Milestone definition supplied at escrow creation.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MilestoneConfig {
+    /// Oracle event type that releases this milestone
+    pub event_type: u32,
+    /// Oracle confirmations required from the escrow's oracle set (0 for single oracle)
+    pub required_confirmations: u32,
+    /// Share of the escrowed amount paid out, in basis points
+    pub share_bps: u32,
+}
+
+/// Stage of a milestone escrow, released independently of the others.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Milestone {
+    pub event_type: u32,
+    pub required_confirmations: u32,
+    pub share_bps: u32,
+    /// Gross amount released by this milestone (the last one absorbs rounding)
+    pub amount: i128,
+    pub released: bool,
+}
+
+/// Open or resolved dispute over an escrow.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Dispute {
+    pub escrow_id: u64,
+    /// Buyer or seller who raised the dispute
+    pub opened_by: Address,
+    /// Hash of the off-chain evidence bundle
+    pub evidence_hash: BytesN<32>,
+    pub opened_at: u64,
+    /// Panel appointed by governance for this dispute (empty means the arbitrator set rules)
+    pub panel: Vec<Address>,
+    pub resolved: bool,
+    /// Share of the escrowed amount awarded to the seller, in basis points
+    pub seller_bps: u32,
+    pub arbitrator: Option<Address>,
+}
+
+/// Local mirror of ReputationRegistry's ReputationEvent (only the variants recorded here).
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReputationEvent {
+    DisputeLost = 6,
 }
 
 // ---------------------------------------------------------------------------
@@ -119,6 +213,10 @@ pub struct EscrowManager;
 #[contractimpl]
 impl EscrowManager {
     /// Initialize the contract with admin and external contract addresses.
+    ///
+    /// Releases mark the confirmations they used as consumed in the
+    /// OracleAdapter, so the adapter's admin must also list this contract in
+    /// `OracleAdapter::set_consumers`; until then every release reverts.
     pub fn initialize(
         env: Env,
         admin: Address,
@@ -198,6 +296,13 @@ impl EscrowManager {
     /// * `min_destination_amount` - Minimum amount seller must receive (slippage protection)
     /// * `required_confirmations` - Number of oracle confirmations required (0 for single oracle)
     /// * `oracle_set` - Set of authorized oracles (empty means any registered oracle)
+    ///
+    /// # Errors
+    /// Rejects collateral that is missing (`CollateralNotFound`), without
+    /// enough unencumbered value left for the lien (`CollateralLocked`), not
+    /// owned by the buyer or seller
+    /// (`CollateralOwnerMismatch`), expiring before the escrow
+    /// (`CollateralExpiresTooSoon`) or too small for `amount` (`LtvExceeded`).
     pub fn create_escrow(
         env: Env,
         config: EscrowConfig,
@@ -240,12 +345,7 @@ impl EscrowManager {
             .get(&symbol_short!("coll_reg"))
             .ok_or(ContractError::Unauthorized)?;
 
-        let lock_args: Vec<Val> = Vec::from_array(&env, [config.collateral_id.into_val(&env)]);
-        env.invoke_contract::<Val>(&coll_reg, &Symbol::new(&env, "lock_collateral"), lock_args);
-
-        // Transfer funds from lender to this contract
-        let token_client = token::Client::new(&env, &config.asset);
-        token_client.transfer(&config.lender, &env.current_contract_address(), &config.amount);
+        let lien = Self::check_collateral(&env, &coll_reg, &config)?;
 
         let escrow_id: u64 = env
             .storage()
@@ -253,6 +353,20 @@ impl EscrowManager {
             .get(&symbol_short!("next_id"))
             .unwrap_or(1);
 
+        let lock_args: Vec<Val> = Vec::from_array(
+            &env,
+            [
+                config.collateral_id.into_val(&env),
+                escrow_id.into_val(&env),
+                lien.into_val(&env),
+            ],
+        );
+        env.invoke_contract::<Val>(&coll_reg, &Symbol::new(&env, "lock_collateral"), lock_args);
+
+        // Transfer funds from lender to this contract
+        let token_client = token::Client::new(&env, &config.asset);
+        token_client.transfer(&config.lender, &env.current_contract_address(), &config.amount);
+
         let escrow = Escrow {
             id: escrow_id,
             buyer: config.buyer.clone(),
@@ -284,6 +398,107 @@ impl EscrowManager {
         Ok(escrow_id)
     }
 
+    /// Check that the collateral can back an escrow and size its lien.
+    ///
+    /// The collateral must be owned by the buyer or seller, outlast the
+    /// escrow, and its current valuation (the price feed's value for
+    /// feed-bound collateral) must cover `amount` within the max LTV
+    /// configured for its collateral type. The lien is the value needed for
+    /// that cover, and must fit in what other escrows have left unencumbered.
+    fn check_collateral(
+        env: &Env,
+        coll_reg: &Address,
+        config: &EscrowConfig,
+    ) -> Result<i128, ContractError> {
+        let get_args: Vec<Val> = Vec::from_array(env, [config.collateral_id.into_val(env)]);
+        let collateral: CollateralRecord = env
+            .invoke_contract::<Option<CollateralRecord>>(
+                coll_reg,
+                &Symbol::new(env, "get_collateral"),
+                get_args,
+            )
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.owner != config.buyer && collateral.owner != config.seller {
+            return Err(ContractError::CollateralOwnerMismatch);
+        }
+
+        if collateral.expiry_ts <= config.expiry_ts {
+            return Err(ContractError::CollateralExpiresTooSoon);
+        }
+
+        // amount / valuation <= max_ltv_bps / 10000
+        let value_args: Vec<Val> = Vec::from_array(env, [config.collateral_id.into_val(env)]);
+        let valuation: i128 =
+            env.invoke_contract(coll_reg, &Symbol::new(env, "get_valuation"), value_args);
+        let max_ltv_bps = Self::get_max_ltv(env.clone(), collateral.collateral_type);
+        let max_amount = valuation
+            .checked_mul(max_ltv_bps as i128)
+            .ok_or(ContractError::InvalidAmount)?
+            / 10000;
+        if config.amount > max_amount {
+            return Err(ContractError::LtvExceeded);
+        }
+
+        // lien = ceil(amount * 10000 / max_ltv_bps)
+        let scaled = config
+            .amount
+            .checked_mul(10000)
+            .ok_or(ContractError::InvalidAmount)?;
+        let lien = (scaled + max_ltv_bps as i128 - 1) / max_ltv_bps as i128;
+
+        let avail_args: Vec<Val> = Vec::from_array(env, [config.collateral_id.into_val(env)]);
+        let available: i128 = env.invoke_contract(
+            coll_reg,
+            &Symbol::new(env, "get_available_value"),
+            avail_args,
+        );
+        if lien > available {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        Ok(lien)
+    }
+
+    /// Set the maximum loan-to-value for a collateral type (governance only).
+    ///
+    /// # Arguments
+    /// * `collateral_type` - Collateral type as registered in CollateralRegistry
+    /// * `max_ltv_bps` - Maximum escrow amount as a share of realized value (1..=10000)
+    pub fn set_max_ltv(
+        env: Env,
+        collateral_type: u32,
+        max_ltv_bps: u32,
+    ) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+        governance.require_auth();
+
+        if max_ltv_bps == 0 || max_ltv_bps > 10000 {
+            return Err(ContractError::InvalidLtv);
+        }
+
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("max_ltv"), collateral_type), &max_ltv_bps);
+
+        env.events()
+            .publish((symbol_short!("ltv_set"),), (collateral_type, max_ltv_bps));
+
+        Ok(())
+    }
+
+    /// Get the maximum loan-to-value for a collateral type in basis points.
+    pub fn get_max_ltv(env: Env, collateral_type: u32) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("max_ltv"), collateral_type))
+            .unwrap_or(DEFAULT_MAX_LTV_BPS)
+    }
+
     /// Release escrowed funds to the seller after oracle confirmation.
     ///
     /// For multi-oracle consensus: Queries OracleAdapter::check_consensus to verify
@@ -291,9 +506,10 @@ impl EscrowManager {
     /// For single oracle (backward compatibility): Falls back to get_confirmation check.
     ///
     /// If consensus is met:
-    /// - Executes path payment from source asset to destination asset (if different)
-    /// - Uses Stellar's built-in DEX for currency conversion
-    /// - Enforces slippage protection via min_destination_amount
+    /// - Transfers the protocol fee into ProtocolTreasury and records it there
+    /// - Swaps the remainder into destination asset via the swap adapter (if different)
+    /// - Enforces slippage protection via min_destination_amount on the amount received
+    /// - Pays the seller in destination_asset
     /// - Unlocks collateral via CollateralRegistry
     /// - Emits release event (for LoanManagement off-chain notification)
     pub fn release_funds_on_confirmation(env: Env, escrow_id: u64) -> Result<(), ContractError> {
@@ -303,10 +519,76 @@ impl EscrowManager {
             .get(&escrow_id)
             .ok_or(ContractError::EscrowNotFound)?;
 
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(ContractError::EscrowDisputed);
+        }
         if escrow.status != EscrowStatus::Active {
             return Err(ContractError::EscrowNotActive);
         }
 
+        if env
+            .storage()
+            .persistent()
+            .has(&(symbol_short!("miles"), escrow_id))
+        {
+            return Err(ContractError::HasMilestones);
+        }
+
+        Self::verify_confirmation(
+            &env,
+            escrow_id,
+            &escrow,
+            escrow.required_confirmation,
+            escrow.required_confirmations,
+        )?;
+
+        Self::pay_seller(
+            &env,
+            escrow_id,
+            &escrow,
+            escrow.amount,
+            escrow.min_destination_amount,
+        )?;
+        Self::consume_confirmation(&env, escrow_id, escrow.required_confirmation)?;
+
+        // Unlock collateral via CollateralRegistry
+        let coll_reg: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let unlock_args: Vec<Val> = Vec::from_array(
+            &env,
+            [escrow.collateral_id.into_val(&env), escrow_id.into_val(&env)],
+        );
+        env.invoke_contract::<Val>(
+            &coll_reg,
+            &Symbol::new(&env, "unlock_collateral"),
+            unlock_args,
+        );
+
+        escrow.status = EscrowStatus::Released;
+        env.storage().persistent().set(&escrow_id, &escrow);
+
+        env.events()
+            .publish((symbol_short!("esc_rel"),), (escrow_id,));
+
+        Ok(())
+    }
+
+    /// Check the oracle confirmation(s) for `event_type` on an escrow.
+    ///
+    /// With `required_confirmations > 0` the escrow's oracle set must reach
+    /// consensus via OracleAdapter::check_consensus; otherwise a single verified
+    /// confirmation of the event type is enough.
+    fn verify_confirmation(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &Escrow,
+        event_type: u32,
+        required_confirmations: u32,
+    ) -> Result<(), ContractError> {
         // Query OracleAdapter for consensus
         let oracle: Address = env
             .storage()
@@ -314,10 +596,10 @@ impl EscrowManager {
             .get(&symbol_short!("oracle"))
             .ok_or(ContractError::Unauthorized)?;
 
-        let escrow_id_bytes = Bytes::from_slice(&env, &escrow_id.to_be_bytes());
+        let escrow_id_bytes = Bytes::from_slice(env, &escrow_id.to_be_bytes());
 
         // Check if multi-oracle consensus is configured (required_confirmations > 0)
-        if escrow.required_confirmations > 0 {
+        if required_confirmations > 0 {
             // Multi-oracle consensus mode
             // Validate oracle_set is not empty when required_confirmations > 0
             if escrow.oracle_set.is_empty() {
@@ -325,24 +607,24 @@ impl EscrowManager {
             }
 
             // Validate threshold doesn't exceed oracle set size
-            if escrow.required_confirmations > escrow.oracle_set.len() {
+            if required_confirmations > escrow.oracle_set.len() {
                 return Err(ContractError::InvalidThreshold);
             }
 
             // Call OracleAdapter::check_consensus
             let consensus_args: Vec<Val> = Vec::from_array(
-                &env,
+                env,
                 [
-                    escrow_id_bytes.into_val(&env),
-                    escrow.required_confirmation.into_val(&env), // Add event type filtering
-                    escrow.required_confirmations.into_val(&env),
-                    escrow.oracle_set.into_val(&env),
+                    escrow_id_bytes.into_val(env),
+                    event_type.into_val(env), // Add event type filtering
+                    required_confirmations.into_val(env),
+                    escrow.oracle_set.into_val(env),
                 ],
             );
 
             let consensus_met: bool = env.invoke_contract(
                 &oracle,
-                &Symbol::new(&env, "check_consensus"),
+                &Symbol::new(env, "check_consensus"),
                 consensus_args,
             );
 
@@ -351,17 +633,23 @@ impl EscrowManager {
             }
         } else {
             // Single oracle mode (backward compatibility)
-            let conf_args: Vec<Val> = Vec::from_array(&env, [escrow_id_bytes.into_val(&env)]);
+            let conf_args: Vec<Val> = Vec::from_array(env, [escrow_id_bytes.into_val(env)]);
 
             let confirmations: Option<Vec<ConfirmationData>> =
-                env.invoke_contract(&oracle, &Symbol::new(&env, "get_confirmation"), conf_args);
+                env.invoke_contract(&oracle, &Symbol::new(env, "get_confirmation"), conf_args);
 
-            // Check if a verified confirmation matching the required event type exists
+            // Check if a verified, unexpired and unused confirmation of the event type exists
+            let now = env.ledger().timestamp();
             let confirmed = match confirmations {
                 Some(confs) => {
                     let mut found = false;
                     for conf in confs.iter() {
-                        if conf.event_type == escrow.required_confirmation && conf.verified {
+                        let expired = conf.expires_at != 0 && now > conf.expires_at;
+                        if conf.event_type == event_type
+                            && conf.verified
+                            && !expired
+                            && !conf.consumed
+                        {
                             found = true;
                             break;
                         }
@@ -376,394 +664,1997 @@ impl EscrowManager {
             }
         }
 
-        // Execute payment: path payment if assets differ, direct transfer otherwise
+        Ok(())
+    }
+
+    /// Mark the confirmations a release relied on as consumed in OracleAdapter,
+    /// so oracles can no longer revoke them.
+    ///
+    /// Reverts the release unless this contract is one of the adapter's
+    /// consumers (see `initialize`).
+    fn consume_confirmation(
+        env: &Env,
+        escrow_id: u64,
+        event_type: u32,
+    ) -> Result<(), ContractError> {
+        let oracle: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("oracle"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let consume_args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                env.current_contract_address().into_val(env),
+                Bytes::from_slice(env, &escrow_id.to_be_bytes()).into_val(env),
+                event_type.into_val(env),
+            ],
+        );
+        env.invoke_contract::<u32>(&oracle, &Symbol::new(env, "mark_consumed"), consume_args);
+
+        Ok(())
+    }
+
+    /// Pay `gross` out of the escrow to the seller, net of the protocol fee.
+    ///
+    /// Swaps through the adapter when the destination asset differs, requiring
+    /// at least `min_out` of the destination asset.
+    fn pay_seller(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &Escrow,
+        gross: i128,
+        min_out: i128,
+    ) -> Result<(), ContractError> {
+        // Collect the protocol fee out of the released amount before paying out
+        let protocol_fee = Self::collect_protocol_fee(env, escrow_id, escrow, gross)?;
+        let payout = gross - protocol_fee;
+
+        // Execute payment: swap through the adapter if assets differ, direct transfer otherwise
         if escrow.asset == escrow.destination_asset {
             // Direct transfer - no conversion needed
-            let token_client = token::Client::new(&env, &escrow.asset);
-            token_client.transfer(
-                &env.current_contract_address(),
-                &escrow.seller,
-                &escrow.amount,
-            );
+            let token_client = token::Client::new(env, &escrow.asset);
+            token_client.transfer(&env.current_contract_address(), &escrow.seller, &payout);
         } else {
-            // Path payment - use Stellar's built-in DEX
-            let source_token = token::Client::new(&env, &escrow.asset);
-
-            // Execute path payment using Stellar's native path payment functionality
-            // This leverages the Stellar DEX to find the best conversion path
-            let _amount_received = source_token.try_transfer_from(
-                &env.current_contract_address(),
-                &env.current_contract_address(),
-                &escrow.seller,
-                &escrow.amount,
-            );
-
-            // For path payments, we need to use a different approach
-            // Since Soroban doesn't have direct path payment support yet,
-            // we simulate it by doing a swap through the contract
-            // In production, this would integrate with Stellar's path payment protocol
-
-            // For now, we'll use a simplified approach:
-            // 1. Transfer source asset from escrow to a temporary holding
-            // 2. Invoke a swap operation (would be DEX in production)
-            // 3. Transfer destination asset to seller
-
-            // This is a placeholder for the actual path payment implementation
-            // In a real scenario, you'd call into Stellar's path payment host function
-            let _dest_token = token::Client::new(&env, &escrow.destination_asset);
-
-            // Simulate path payment by checking if we can meet minimum destination amount
-            // In production, this would be handled by Stellar's path payment protocol
-            let estimated_dest_amount = Self::estimate_path_payment(
-                &env,
-                &escrow.asset,
-                &escrow.destination_asset,
-                escrow.amount,
-            )?;
-
-            if estimated_dest_amount < escrow.min_destination_amount {
-                return Err(ContractError::SlippageExceeded);
-            }
+            let amount_received = Self::execute_swap(env, escrow, payout, min_out)?;
 
-            // Execute the path payment
-            // Note: In production Stellar contracts, this would use the native path payment
-            // host function which automatically finds the best path through the DEX
-            source_token.transfer(
+            // Pay the seller exactly what the swap delivered, in the destination asset
+            let dest_token = token::Client::new(env, &escrow.destination_asset);
+            dest_token.transfer(
                 &env.current_contract_address(),
                 &escrow.seller,
-                &escrow.amount,
+                &amount_received,
             );
 
             // Emit path payment event for tracking
             env.events().publish(
                 (symbol_short!("path_pay"),),
-                (escrow_id, escrow.amount, estimated_dest_amount),
+                (escrow_id, payout, amount_received),
             );
         }
 
-        // Calculate and collect protocol fee if treasury is configured
-        let treasury_opt: Option<Address> = env.storage().instance().get(&symbol_short!("treasury"));
-        let protocol_fee = if treasury_opt.is_some() {
-            let treasury = treasury_opt.as_ref().unwrap();
-            
-            // Query fee_bps from ProtocolTreasury
-            let fee_bps_args: soroban_sdk::Vec<Val> = soroban_sdk::Vec::new(&env);
-            let fee_bps: u32 = env.invoke_contract(
-                &treasury,
-                &Symbol::new(&env, "get_fee_bps"),
-                fee_bps_args,
-            );
-            
-            // Calculate fee on the escrow amount
-            let fee_amount = (escrow.amount * fee_bps as i128) / 10000;
-            
-            if fee_amount > 0 {
-                // Record the fee deposit in treasury
-                // Note: In a full implementation, the actual token transfer would happen
-                // before this call, either deducted from the payment or transferred separately
-                let deposit_args: soroban_sdk::Vec<Val> = soroban_sdk::Vec::from_array(
-                    &env,
-                    [
-                        escrow.asset.into_val(&env), // Asset address
-                        fee_amount.into_val(&env),
-                    ],
-                );
-                env.invoke_contract(
-                    &treasury,
-                    &Symbol::new(&env, "deposit_fee"),
-                    deposit_args,
-                );
-                
-                // Emit fee collection event
-                env.events().publish(
-                    (symbol_short!("fee_col"),),
-                    (escrow_id, fee_amount, escrow.asset),
-                );
-            }
-            
-            fee_amount
-        } else {
-            0i128
+        Ok(())
+    }
+
+    /// Transfer the protocol fee on an escrow release into the treasury and
+    /// record it there. Returns the fee taken out of the `gross` amount released.
+    fn collect_protocol_fee(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &Escrow,
+        gross: i128,
+    ) -> Result<i128, ContractError> {
+        let treasury: Address = match env.storage().instance().get(&symbol_short!("treasury")) {
+            Some(treasury) => treasury,
+            None => return Ok(0),
         };
 
-        // Unlock collateral via CollateralRegistry
-        let coll_reg: Address = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("coll_reg"))
-            .ok_or(ContractError::Unauthorized)?;
+        let fee_bps: u32 =
+            env.invoke_contract(&treasury, &Symbol::new(env, "get_fee_bps"), Vec::new(env));
 
-        let unlock_args: Vec<Val> = Vec::from_array(&env, [escrow.collateral_id.into_val(&env)]);
-        env.invoke_contract::<Val>(
-            &coll_reg,
-            &Symbol::new(&env, "unlock_collateral"),
-            unlock_args,
-        );
+        let fee_amount = (gross * fee_bps as i128) / 10000;
+        if fee_amount <= 0 {
+            return Ok(0);
+        }
 
-        escrow.status = EscrowStatus::Released;
-        env.storage().persistent().set(&escrow_id, &escrow);
+        // Move the fee first; the treasury only records fees it actually holds
+        let this = env.current_contract_address();
+        token::Client::new(env, &escrow.asset).transfer(&this, &treasury, &fee_amount);
 
-        env.events()
-            .publish((symbol_short!("esc_rel"),), (escrow_id,));
+        let deposit_args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                this.into_val(env),
+                escrow.asset.into_val(env),
+                fee_amount.into_val(env),
+            ],
+        );
+        env.invoke_contract::<()>(&treasury, &Symbol::new(env, "deposit_fee"), deposit_args);
 
-        Ok(())
+        env.events().publish(
+            (symbol_short!("fee_col"),),
+            (escrow_id, fee_amount, escrow.asset.clone()),
+        );
+
+        Ok(fee_amount)
     }
 
-    /// Estimate the destination amount for a path payment.
+    /// Convert the escrowed source asset into the destination asset through
+    /// the configured swap adapter.
     ///
-    /// In production, this would query Stellar's DEX for the best path.
-    /// For testing, we use a simplified estimation.
-    fn estimate_path_payment(
+    /// `amount_in` of the source asset is pushed to the adapter, which must deliver the
+    /// output to this contract. Slippage protection is enforced against the
+    /// destination balance actually received, not the adapter's return value.
+    fn execute_swap(
         env: &Env,
-        _source_asset: &Address,
-        _dest_asset: &Address,
-        source_amount: i128,
+        escrow: &Escrow,
+        amount_in: i128,
+        min_out: i128,
     ) -> Result<i128, ContractError> {
-        // Simplified estimation for testing
-        // In production, this would query the actual DEX liquidity and paths
-        // For now, assume a 1:1 ratio (would be replaced with actual DEX query)
-
-        // Check if we have a stored exchange rate for testing
-        let rate_key = symbol_short!("test_rate");
-        let exchange_rate: i128 = env.storage().instance().get(&rate_key).unwrap_or(1_000_000); // Default 1:1 (with 6 decimals precision)
-
-        // Calculate destination amount: source_amount * rate / 1_000_000
-        let dest_amount = source_amount
-            .checked_mul(exchange_rate)
-            .and_then(|v| v.checked_div(1_000_000))
-            .ok_or(ContractError::PathPaymentFailed)?;
+        let adapter: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("swap_adp"))
+            .ok_or(ContractError::SwapAdapterNotSet)?;
 
-        Ok(dest_amount)
-    }
+        let this = env.current_contract_address();
+        let source_token = token::Client::new(env, &escrow.asset);
+        let dest_token = token::Client::new(env, &escrow.destination_asset);
 
-    /// Set exchange rate for testing path payments.
-    /// Rate is expressed with 6 decimals precision (1_000_000 = 1:1 ratio).
-    /// This is a test helper and would not exist in production.
-    pub fn set_test_exchange_rate(env: Env, rate: i128) {
-        env.storage()
-            .instance()
-            .set(&symbol_short!("test_rate"), &rate);
-    }
+        let balance_before = dest_token.balance(&this);
 
-    /// Refund the escrowed funds to the lender if the escrow has expired.
-    ///
-    /// Anyone can call this after expiry. Unlocks collateral and returns
-    /// funds to the lender.
-    pub fn refund_escrow(env: Env, escrow_id: u64) -> Result<(), ContractError> {
-        let mut escrow: Escrow = env
-            .storage()
-            .persistent()
-            .get(&escrow_id)
-            .ok_or(ContractError::EscrowNotFound)?;
+        source_token.transfer(&this, &adapter, &amount_in);
 
-        if escrow.status != EscrowStatus::Active {
-            return Err(ContractError::EscrowNotActive);
+        let swap_args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                escrow.asset.into_val(env),
+                escrow.destination_asset.into_val(env),
+                amount_in.into_val(env),
+                min_out.into_val(env),
+                this.into_val(env),
+            ],
+        );
+        let swap_result = env.try_invoke_contract::<i128, soroban_sdk::Error>(
+            &adapter,
+            &Symbol::new(env, "swap"),
+            swap_args,
+        );
+        if !matches!(swap_result, Ok(Ok(_))) {
+            return Err(ContractError::PathPaymentFailed);
         }
 
-        let current_ts = env.ledger().timestamp();
-        if current_ts <= escrow.expiry_ts {
-            return Err(ContractError::EscrowNotExpired);
+        let amount_received = dest_token
+            .balance(&this)
+            .checked_sub(balance_before)
+            .ok_or(ContractError::PathPaymentFailed)?;
+
+        if amount_received < min_out {
+            return Err(ContractError::SlippageExceeded);
         }
 
-        // Refund lender
-        let token_client = token::Client::new(&env, &escrow.asset);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &escrow.lender,
-            &escrow.amount,
-        );
+        Ok(amount_received)
+    }
 
-        // Unlock collateral via CollateralRegistry
-        let coll_reg: Address = env
+    /// Set the swap adapter used for cross-asset settlement (admin only).
+    ///
+    /// The adapter must expose
+    /// `swap(source_asset, destination_asset, amount_in, min_amount_out, recipient) -> i128`,
+    /// having already received `amount_in` of `source_asset` before the call.
+    pub fn set_swap_adapter(env: Env, adapter: Address) -> Result<(), ContractError> {
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&symbol_short!("coll_reg"))
+            .get(&symbol_short!("admin"))
             .ok_or(ContractError::Unauthorized)?;
 
-        let unlock_args: Vec<Val> = Vec::from_array(&env, [escrow.collateral_id.into_val(&env)]);
-        env.invoke_contract::<Val>(
-            &coll_reg,
-            &Symbol::new(&env, "unlock_collateral"),
-            unlock_args,
-        );
+        admin.require_auth();
 
-        escrow.status = EscrowStatus::Refunded;
-        env.storage().persistent().set(&escrow_id, &escrow);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("swap_adp"), &adapter);
 
         env.events()
-            .publish((symbol_short!("esc_rfnd"),), (escrow_id,));
+            .publish((symbol_short!("swap_set"),), (adapter,));
 
         Ok(())
     }
 
-    /// Get escrow details.
-    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
-        env.storage().persistent().get(&escrow_id)
+    /// Get the configured swap adapter address.
+    pub fn get_swap_adapter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("swap_adp"))
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// Create an escrow that releases in stages.
+    ///
+    /// Each milestone releases its share of `config.amount` once its own oracle
+    /// event type is confirmed, using the escrow's `oracle_set` for consensus.
+    /// `config.required_confirmation` and `config.required_confirmations` are
+    /// not used; shares must sum to 10000 bps and event types must differ, as a
+    /// release consumes the confirmations of its event type.
+    pub fn create_milestone_escrow(
+        env: Env,
+        config: EscrowConfig,
+        milestones: Vec<MilestoneConfig>,
+    ) -> Result<u64, ContractError> {
+        if milestones.is_empty() {
+            return Err(ContractError::InvalidMilestones);
+        }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{
-        testutils::Address as _, testutils::Ledger as _, token, Address, Bytes, Env, Vec,
-    };
+        let mut total_bps: u32 = 0;
+        let mut event_types: Vec<u32> = Vec::new(&env);
+        for m in milestones.iter() {
+            if m.share_bps == 0 || event_types.contains(m.event_type) {
+                return Err(ContractError::InvalidMilestones);
+            }
+            event_types.push_back(m.event_type);
+            total_bps = total_bps
+                .checked_add(m.share_bps)
+                .ok_or(ContractError::InvalidMilestones)?;
+            if m.required_confirmations > 0 {
+                if config.oracle_set.is_empty() {
+                    return Err(ContractError::InvalidOracleSet);
+                }
+                if m.required_confirmations > config.oracle_set.len() {
+                    return Err(ContractError::InvalidThreshold);
+                }
+            }
+        }
+        if total_bps != 10000 {
+            return Err(ContractError::InvalidMilestones);
+        }
 
-    // -- Mock CollateralRegistry ------------------------------------------
+        let amount = config.amount;
+        let escrow_id = Self::create_escrow(env.clone(), config)?;
+
+        let mut stages: Vec<Milestone> = Vec::new(&env);
+        let mut allocated: i128 = 0;
+        let last = milestones.len() - 1;
+        for (i, m) in milestones.iter().enumerate() {
+            let stage_amount = if i as u32 == last {
+                amount - allocated
+            } else {
+                (amount * m.share_bps as i128) / 10000
+            };
+            allocated += stage_amount;
+            stages.push_back(Milestone {
+                event_type: m.event_type,
+                required_confirmations: m.required_confirmations,
+                share_bps: m.share_bps,
+                amount: stage_amount,
+                released: false,
+            });
+        }
 
-    #[contract]
-    pub struct MockCollateralRegistry;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("miles"), escrow_id), &stages);
 
-    #[contractimpl]
-    impl MockCollateralRegistry {
-        pub fn lock_collateral(env: Env, id: u64) {
-            env.storage().persistent().set(&id, &true);
-            env.events().publish((symbol_short!("coll_lock"),), (id,));
+        env.events()
+            .publish((symbol_short!("ms_crtd"),), (escrow_id, stages.len()));
+
+        Ok(escrow_id)
+    }
+
+    /// Release one milestone of a milestone escrow to the seller.
+    ///
+    /// Milestones release in order, each once its event type is confirmed.
+    /// The protocol fee and swap apply to the milestone amount, with
+    /// `min_destination_amount` scaled by the milestone's share. Releasing the
+    /// last milestone closes the escrow and unlocks the collateral.
+    pub fn release_milestone(env: Env, escrow_id: u64, index: u32) -> Result<(), ContractError> {
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&escrow_id)
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(ContractError::EscrowDisputed);
+        }
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowNotActive);
         }
 
-        pub fn unlock_collateral(env: Env, id: u64) {
-            env.storage().persistent().set(&id, &false);
-            env.events().publish((symbol_short!("coll_unlk"),), (id,));
+        let key = (symbol_short!("miles"), escrow_id);
+        let mut milestones: Vec<Milestone> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::MilestoneNotFound)?;
+        let mut milestone = milestones
+            .get(index)
+            .ok_or(ContractError::MilestoneNotFound)?;
+
+        // Only the first unreleased milestone may be released
+        if milestone.released {
+            return Err(ContractError::MilestoneOutOfOrder);
+        }
+        for i in 0..index {
+            if !milestones.get(i).unwrap().released {
+                return Err(ContractError::MilestoneOutOfOrder);
+            }
+        }
+
+        Self::verify_confirmation(
+            &env,
+            escrow_id,
+            &escrow,
+            milestone.event_type,
+            milestone.required_confirmations,
+        )?;
+
+        let min_out = (escrow.min_destination_amount * milestone.amount) / escrow.amount;
+        Self::pay_seller(&env, escrow_id, &escrow, milestone.amount, min_out)?;
+        Self::consume_confirmation(&env, escrow_id, milestone.event_type)?;
+
+        milestone.released = true;
+        milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&key, &milestones);
+
+        env.events().publish(
+            (symbol_short!("ms_rel"),),
+            (escrow_id, index, milestone.event_type, milestone.amount),
+        );
+
+        if index == milestones.len() - 1 {
+            Self::unlock_collateral(&env, &escrow)?;
+
+            escrow.status = EscrowStatus::Released;
+            env.storage().persistent().set(&escrow_id, &escrow);
+
+            env.events()
+                .publish((symbol_short!("esc_rel"),), (escrow_id,));
         }
+
+        Ok(())
     }
 
-    // -- Mock OracleAdapter with Multi-Oracle Support --------------------------
+    /// Get the milestones of an escrow (empty for single-release escrows).
+    pub fn get_milestones(env: Env, escrow_id: u64) -> Vec<Milestone> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("miles"), escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
 
-    #[contract]
-    pub struct MockOracleAdapterWithConsensus;
+    /// Amount of the escrowed asset still held for an escrow.
+    fn held_amount(env: &Env, escrow_id: u64, escrow: &Escrow) -> i128 {
+        let milestones: Option<Vec<Milestone>> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("miles"), escrow_id));
+        match milestones {
+            Some(milestones) => milestones
+                .iter()
+                .filter(|m| !m.released)
+                .map(|m| m.amount)
+                .sum(),
+            None => escrow.amount,
+        }
+    }
 
-    #[contractimpl]
-    impl MockOracleAdapterWithConsensus {
-        /// Returns confirmations stored under the escrow_id key.
-        pub fn get_confirmation(env: Env, escrow_id: Bytes) -> Option<Vec<ConfirmationData>> {
-            env.storage().persistent().get(&escrow_id)
+    /// Unlock the escrow's collateral via CollateralRegistry.
+    fn unlock_collateral(env: &Env, escrow: &Escrow) -> Result<(), ContractError> {
+        let coll_reg: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let unlock_args: Vec<Val> = Vec::from_array(
+            env,
+            [escrow.collateral_id.into_val(env), escrow.id.into_val(env)],
+        );
+        env.invoke_contract::<Val>(
+            &coll_reg,
+            &Symbol::new(env, "unlock_collateral"),
+            unlock_args,
+        );
+
+        Ok(())
+    }
+
+    /// Refund the escrowed funds to the lender if the escrow has expired.
+    ///
+    /// Anyone can call this after expiry. Unlocks collateral and returns
+    /// funds to the lender. For milestone escrows only the unreleased
+    /// milestones are refunded; released ones stay with the seller.
+    pub fn refund_escrow(env: Env, escrow_id: u64) -> Result<(), ContractError> {
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&escrow_id)
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(ContractError::EscrowDisputed);
+        }
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowNotActive);
         }
 
-        /// Test helper: store confirmation data for a given escrow_id.
-        pub fn set_confirmation(env: Env, escrow_id: Bytes, confirmations: Vec<ConfirmationData>) {
-            env.storage().persistent().set(&escrow_id, &confirmations);
+        let current_ts = env.ledger().timestamp();
+        if current_ts <= escrow.expiry_ts {
+            return Err(ContractError::EscrowNotExpired);
         }
 
-        /// Mock implementation of check_consensus for testing
-        pub fn check_consensus(
-            env: Env,
-            escrow_id: Bytes,
-            threshold: u32,
-            oracle_set: Vec<Address>,
-        ) -> bool {
-            if let Some(confirmations) = Self::get_confirmation(env, escrow_id.clone()) {
-                let mut unique_oracle_count = 0u32;
-                
-                // Count unique oracle confirmations from authorized set
-                for conf in confirmations.iter() {
-                    if !conf.verified {
-                        continue;
-                    }
-                    
-                    // Check if oracle is in the authorized set
-                    let is_authorized = if oracle_set.is_empty() {
-                        true // Any oracle is authorized if set is empty
-                    } else {
-                        let mut found = false;
-                        for authorized in oracle_set.iter() {
-                            if authorized == conf.oracle {
-                                found = true;
-                                break;
-                            }
-                        }
-                        found
-                    };
-                    
-                    if is_authorized {
-                        unique_oracle_count += 1;
-                    }
-                }
-                
-                return unique_oracle_count >= threshold;
-            }
-            false
+        // Refund lender whatever has not been released yet
+        let refund_amount = Self::held_amount(&env, escrow_id, &escrow);
+        if refund_amount > 0 {
+            let token_client = token::Client::new(&env, &escrow.asset);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &escrow.lender,
+                &refund_amount,
+            );
+        }
+
+        // Unlock collateral via CollateralRegistry
+        let coll_reg: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let unlock_args: Vec<Val> = Vec::from_array(
+            &env,
+            [escrow.collateral_id.into_val(&env), escrow_id.into_val(&env)],
+        );
+        env.invoke_contract::<Val>(
+            &coll_reg,
+            &Symbol::new(&env, "unlock_collateral"),
+            unlock_args,
+        );
+
+        escrow.status = EscrowStatus::Refunded;
+        env.storage().persistent().set(&escrow_id, &escrow);
+
+        env.events()
+            .publish((symbol_short!("esc_rfnd"),), (escrow_id,));
+
+        if refund_amount < escrow.amount {
+            env.events().publish(
+                (symbol_short!("ms_rfnd"),),
+                (escrow_id, refund_amount, escrow.amount - refund_amount),
+            );
         }
+
+        Ok(())
     }
 
-    // -- Helpers -----------------------------------------------------------
+    /// Open a dispute over an active escrow (buyer or seller).
+    ///
+    /// Release and refund are frozen until the dispute is resolved.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Escrow under dispute
+    /// * `caller` - Buyer or seller raising the dispute
+    /// * `evidence_hash` - Hash of the off-chain evidence bundle
+    pub fn open_dispute(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
 
-    struct TestEnv<'a> {
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&escrow_id)
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        if caller != escrow.buyer && caller != escrow.seller {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(ContractError::EscrowDisputed);
+        }
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowNotActive);
+        }
+
+        let dispute = Dispute {
+            escrow_id,
+            opened_by: caller.clone(),
+            evidence_hash: evidence_hash.clone(),
+            opened_at: env.ledger().timestamp(),
+            panel: Vec::new(&env),
+            resolved: false,
+            seller_bps: 0,
+            arbitrator: None,
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("dispute"), escrow_id), &dispute);
+
+        escrow.status = EscrowStatus::Disputed;
+        env.storage().persistent().set(&escrow_id, &escrow);
+
+        env.events().publish(
+            (symbol_short!("esc_dsp"),),
+            (escrow_id, caller, evidence_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Appoint the panel that rules on a specific dispute (governance only).
+    ///
+    /// A non-empty panel replaces the standing arbitrator set for this dispute.
+    pub fn appoint_panel(
         env: Env,
-        escrow_client: EscrowManagerClient<'a>,
-        escrow_id_addr: Address,
-        coll_reg_addr: Address,
-        oracle_client: MockOracleAdapterClient<'a>,
-        oracle_addr: Option<Address>, // Add field for multi-oracle tests
-        token_addr: Address,
-        treasury_addr: Address,
-        buyer: Address,
-        seller: Address,
-        lender: Address,
+        escrow_id: u64,
+        panel: Vec<Address>,
+    ) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+        governance.require_auth();
+
+        let key = (symbol_short!("dispute"), escrow_id);
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::DisputeNotFound)?;
+
+        if dispute.resolved {
+            return Err(ContractError::EscrowNotActive);
+        }
+
+        dispute.panel = panel.clone();
+        env.storage().persistent().set(&key, &dispute);
+
+        env.events()
+            .publish((symbol_short!("dsp_panel"),), (escrow_id, panel));
+
+        Ok(())
+    }
+
+    /// Resolve a dispute with a split ruling (arbitrator or panel member).
+    ///
+    /// The seller receives `seller_bps` of the amount still held, in the escrow
+    /// asset; the remainder is returned to the lender funding the buyer's side.
+    /// Collateral is unlocked. The side awarded less than half is recorded as
+    /// having lost the dispute in the ReputationRegistry, if one is configured.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Disputed escrow
+    /// * `arbitrator` - Member of the dispute's panel, or of the arbitrator set
+    /// * `seller_bps` - Seller's share in basis points (0..=10000)
+    pub fn resolve_dispute(
+        env: Env,
+        escrow_id: u64,
+        arbitrator: Address,
+        seller_bps: u32,
+    ) -> Result<(), ContractError> {
+        arbitrator.require_auth();
+
+        if seller_bps > 10000 {
+            return Err(ContractError::InvalidRuling);
+        }
+
+        let key = (symbol_short!("dispute"), escrow_id);
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::DisputeNotFound)?;
+
+        if dispute.resolved {
+            return Err(ContractError::EscrowNotActive);
+        }
+
+        let arbitrators = if dispute.panel.is_empty() {
+            Self::get_arbitrators(env.clone())
+        } else {
+            dispute.panel.clone()
+        };
+        if !arbitrators.contains(&arbitrator) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let mut escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&escrow_id)
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        // Split the funds still held (milestones already released are final)
+        let held = Self::held_amount(&env, escrow_id, &escrow);
+        let seller_amount = (held * seller_bps as i128) / 10000;
+        let lender_amount = held - seller_amount;
+        let token_client = token::Client::new(&env, &escrow.asset);
+        if seller_amount > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &escrow.seller,
+                &seller_amount,
+            );
+        }
+        if lender_amount > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &escrow.lender,
+                &lender_amount,
+            );
+        }
+
+        // Unlock collateral via CollateralRegistry
+        let coll_reg: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let unlock_args: Vec<Val> = Vec::from_array(
+            &env,
+            [escrow.collateral_id.into_val(&env), escrow_id.into_val(&env)],
+        );
+        env.invoke_contract::<Val>(
+            &coll_reg,
+            &Symbol::new(&env, "unlock_collateral"),
+            unlock_args,
+        );
+
+        escrow.status = EscrowStatus::Resolved;
+        env.storage().persistent().set(&escrow_id, &escrow);
+
+        dispute.resolved = true;
+        dispute.seller_bps = seller_bps;
+        dispute.arbitrator = Some(arbitrator.clone());
+        env.storage().persistent().set(&key, &dispute);
+
+        // An even split has no losing side
+        let loser = if seller_bps < 5000 {
+            Some(escrow.seller.clone())
+        } else if seller_bps > 5000 {
+            Some(escrow.buyer.clone())
+        } else {
+            None
+        };
+        if let Some(loser) = loser {
+            Self::record_dispute_lost(&env, &loser);
+        }
+
+        env.events().publish(
+            (symbol_short!("dsp_rslv"),),
+            (escrow_id, arbitrator, seller_amount, lender_amount),
+        );
+
+        Ok(())
+    }
+
+    /// Slash an oracle whose confirmation the dispute ruling proved wrong.
+    ///
+    /// Only the arbitrator who resolved the dispute may call this. A
+    /// confirmation of the escrow's required event vouches for the seller, so
+    /// it is only contradicted by a ruling that sided with the buyer
+    /// (`seller_bps` below 5000). The OracleAdapter takes `amount` from the
+    /// oracle's bond for that confirmation, pays it to the buyer and the
+    /// treasury, and refuses to slash the same confirmation twice. The
+    /// EscrowManager must be one of the adapter's slashers.
+    ///
+    /// # Returns
+    /// The amount actually slashed
+    pub fn slash_oracle(
+        env: Env,
+        escrow_id: u64,
+        arbitrator: Address,
+        oracle: Address,
+        amount: i128,
+    ) -> Result<i128, ContractError> {
+        arbitrator.require_auth();
+
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("dispute"), escrow_id))
+            .ok_or(ContractError::DisputeNotFound)?;
+
+        if !dispute.resolved || dispute.arbitrator != Some(arbitrator.clone()) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let escrow: Escrow = env
+            .storage()
+            .persistent()
+            .get(&escrow_id)
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        // A ruling for the seller, or an even split, agrees with the confirmation
+        if amount <= 0 || dispute.seller_bps >= 5000 {
+            return Err(ContractError::InvalidSlash);
+        }
+        let harmed = escrow.buyer;
+
+        let oracle_adapter: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("oracle"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let args: Vec<Val> = Vec::from_array(
+            &env,
+            [
+                env.current_contract_address().into_val(&env),
+                oracle.into_val(&env),
+                Bytes::from_slice(&env, &escrow_id.to_be_bytes()).into_val(&env),
+                escrow.required_confirmation.into_val(&env),
+                amount.into_val(&env),
+                harmed.into_val(&env),
+            ],
+        );
+        let slashed: i128 = env.invoke_contract(&oracle_adapter, &Symbol::new(&env, "slash"), args);
+
+        env.events().publish(
+            (symbol_short!("dsp_slash"),),
+            (escrow_id, oracle, slashed, harmed),
+        );
+
+        Ok(slashed)
+    }
+
+    /// Record `DisputeLost` for `user` in the ReputationRegistry, if configured.
+    fn record_dispute_lost(env: &Env, user: &Address) {
+        let registry: Address = match env.storage().instance().get(&symbol_short!("rep_reg")) {
+            Some(registry) => registry,
+            None => return,
+        };
+
+        let args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                env.current_contract_address().into_val(env),
+                user.into_val(env),
+                ReputationEvent::DisputeLost.into_val(env),
+                0i128.into_val(env),
+            ],
+        );
+        env.invoke_contract::<Val>(&registry, &Symbol::new(env, "record_event"), args);
+    }
+
+    /// Get the dispute recorded for an escrow.
+    pub fn get_dispute(env: Env, escrow_id: u64) -> Option<Dispute> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("dispute"), escrow_id))
+    }
+
+    /// Set the standing arbitrator set (admin only).
+    pub fn set_arbitrators(env: Env, arbitrators: Vec<Address>) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("arbiters"), &arbitrators);
+
+        env.events()
+            .publish((symbol_short!("arb_set"),), (arbitrators,));
+
+        Ok(())
+    }
+
+    /// Get the standing arbitrator set.
+    pub fn get_arbitrators(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("arbiters"))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the governance contract allowed to appoint dispute panels, set LTV limits and
+    /// upgrade the contract.
+    ///
+    /// The admin sets it once; afterwards only the current governance can replace it.
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&symbol_short!("gov"));
+        match current {
+            Some(current) => current.require_auth(),
+            None => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("admin"))
+                    .ok_or(ContractError::Unauthorized)?;
+                admin.require_auth();
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
+        env.events()
+            .publish((symbol_short!("gov_set"),), (governance,));
+
+        Ok(())
+    }
+
+    /// Set the ReputationRegistry that records dispute outcomes (admin only).
+    pub fn set_reputation_registry(env: Env, registry: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("rep_reg"), &registry);
+
+        env.events()
+            .publish((symbol_short!("rep_set"),), (registry,));
+
+        Ok(())
+    }
+
+    /// Get escrow details.
+    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
+        env.storage().persistent().get(&escrow_id)
+    }
+
+    /// Swap the escrow manager's code for an uploaded Wasm (governance only).
+    /// Open escrows, disputes and LTV limits are left untouched, so funds held
+    /// in escrow remain releasable under the new code.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    /// Record that escrow storage has been brought up to `version` (governance only).
+    /// Rejects anything not newer than the stored layout or beyond what this code reads.
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("version"), &version);
+
+        env.events()
+            .publish((symbol_short!("migrated"),), (current, version));
+
+        Ok(())
+    }
+
+    /// Storage layout version escrows are kept in.
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::Address as _, testutils::Ledger as _, token, Address, Bytes, BytesN, Env, Map,
+        Vec,
+    };
+
+    // -- Mock CollateralRegistry ------------------------------------------
+
+    #[contract]
+    pub struct MockCollateralRegistry;
+
+    #[contractimpl]
+    impl MockCollateralRegistry {
+        pub fn set_collateral(env: Env, collateral: CollateralRecord) {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("coll"), collateral.id), &collateral);
+        }
+
+        pub fn get_collateral(env: Env, id: u64) -> Option<CollateralRecord> {
+            env.storage().persistent().get(&(symbol_short!("coll"), id))
+        }
+
+        pub fn lock_collateral(env: Env, id: u64, escrow_id: u64, amount: i128) {
+            let mut liens = Self::get_liens(env.clone(), id);
+            liens.set(escrow_id, amount);
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("liens"), id), &liens);
+            env.storage().persistent().set(&id, &true);
+            env.events()
+                .publish((symbol_short!("coll_lock"),), (id, escrow_id, amount));
+        }
+
+        pub fn unlock_collateral(env: Env, id: u64, escrow_id: u64) {
+            let mut liens = Self::get_liens(env.clone(), id);
+            liens.remove(escrow_id);
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("liens"), id), &liens);
+            env.storage().persistent().set(&id, &!liens.is_empty());
+            env.events().publish(
+                (symbol_short!("coll_unlk"),),
+                (id, escrow_id, !liens.is_empty()),
+            );
+        }
+
+        pub fn get_liens(env: Env, id: u64) -> Map<u64, i128> {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("liens"), id))
+                .unwrap_or(Map::new(&env))
+        }
+
+        pub fn get_valuation(env: Env, id: u64) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("value"), id))
+                .or_else(|| Self::get_collateral(env.clone(), id).map(|c| c.realized_value))
+                .unwrap_or(0)
+        }
+
+        /// Value the collateral at a price feed's `value` instead of its realized value
+        pub fn set_valuation(env: Env, id: u64, value: i128) {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("value"), id), &value);
+        }
+
+        pub fn get_available_value(env: Env, id: u64) -> i128 {
+            let realized = Self::get_collateral(env.clone(), id)
+                .map(|collateral| collateral.realized_value)
+                .unwrap_or(0);
+            let encumbered: i128 = Self::get_liens(env, id).values().iter().sum();
+            realized - encumbered
+        }
+    }
+
+    // -- Mock ProtocolTreasury --------------------------------------------
+
+    #[contract]
+    pub struct MockTreasury;
+
+    #[contractimpl]
+    impl MockTreasury {
+        pub fn set_fee_bps(env: Env, fee_bps: u32) {
+            env.storage().instance().set(&symbol_short!("fee_bps"), &fee_bps);
+        }
+
+        pub fn get_fee_bps(env: Env) -> u32 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("fee_bps"))
+                .unwrap_or(0)
+        }
+
+        pub fn deposit_fee(env: Env, depositor: Address, asset: Address, amount: i128) {
+            depositor.require_auth();
+            let key = (symbol_short!("fees"), asset);
+            let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(current + amount));
+        }
+
+        pub fn get_total_fees(env: Env, asset: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("fees"), asset))
+                .unwrap_or(0)
+        }
+    }
+
+    // -- Mock AMM swap adapter --------------------------------------------
+
+    /// Constant-rate AMM holding destination liquidity. It deliberately does
+    /// not enforce `min_amount_out` so the escrow's own check is exercised.
+    #[contract]
+    pub struct MockAmm;
+
+    #[contractimpl]
+    impl MockAmm {
+        /// Rate scaled by 1_000_000 (950_000 = 0.95 destination per source unit).
+        pub fn set_rate(env: Env, rate: i128) {
+            env.storage().instance().set(&symbol_short!("rate"), &rate);
+        }
+
+        /// When set, the AMM reports `min_amount_out` as the output regardless
+        /// of what it actually delivered.
+        pub fn set_misreport(env: Env, misreport: bool) {
+            env.storage().instance().set(&symbol_short!("lie"), &misreport);
+        }
+
+        pub fn swap(
+            env: Env,
+            _source_asset: Address,
+            destination_asset: Address,
+            amount_in: i128,
+            min_amount_out: i128,
+            recipient: Address,
+        ) -> i128 {
+            let rate: i128 = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("rate"))
+                .unwrap_or(1_000_000);
+            let amount_out = amount_in * rate / 1_000_000;
+
+            token::Client::new(&env, &destination_asset).transfer(
+                &env.current_contract_address(),
+                &recipient,
+                &amount_out,
+            );
+
+            let misreport: bool = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("lie"))
+                .unwrap_or(false);
+            if misreport {
+                min_amount_out
+            } else {
+                amount_out
+            }
+        }
+    }
+
+    // -- Mock ReputationRegistry ------------------------------------------
+
+    #[contract]
+    pub struct MockReputationRegistry;
+
+    #[contractimpl]
+    impl MockReputationRegistry {
+        pub fn record_event(
+            env: Env,
+            caller: Address,
+            user: Address,
+            event_type: ReputationEvent,
+            _volume: i128,
+        ) {
+            caller.require_auth();
+            let key = (user, event_type as u32);
+            let count: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(count + 1));
+        }
+
+        pub fn get_count(env: Env, user: Address, event_type: u32) -> u32 {
+            env.storage()
+                .persistent()
+                .get(&(user, event_type))
+                .unwrap_or(0)
+        }
+    }
+
+    // -- Mock OracleAdapter with Multi-Oracle Support --------------------------
+
+    #[contract]
+    pub struct MockOracleAdapterWithConsensus;
+
+    #[contractimpl]
+    impl MockOracleAdapterWithConsensus {
+        /// Returns confirmations stored under the escrow_id key.
+        pub fn get_confirmation(env: Env, escrow_id: Bytes) -> Option<Vec<ConfirmationData>> {
+            env.storage().persistent().get(&escrow_id)
+        }
+
+        /// Test helper: store confirmation data for a given escrow_id.
+        pub fn set_confirmation(env: Env, escrow_id: Bytes, confirmations: Vec<ConfirmationData>) {
+            env.storage().persistent().set(&escrow_id, &confirmations);
+        }
+
+        /// Mock implementation of check_consensus for testing: counts verified,
+        /// unconsumed confirmations of `event_type` from the oracle set that
+        /// agree on the most common result.
+        pub fn check_consensus(
+            env: Env,
+            escrow_id: Bytes,
+            event_type: u32,
+            threshold: u32,
+            oracle_set: Vec<Address>,
+        ) -> bool {
+            let Some(confirmations) = Self::get_confirmation(env.clone(), escrow_id) else {
+                return false;
+            };
+
+            let mut votes: Map<Bytes, u32> = Map::new(&env);
+            for conf in confirmations.iter() {
+                let is_authorized = oracle_set.is_empty() || oracle_set.contains(&conf.oracle);
+                if conf.verified && !conf.consumed && conf.event_type == event_type && is_authorized
+                {
+                    let count = votes.get(conf.result.clone()).unwrap_or(0);
+                    votes.set(conf.result, count + 1);
+                }
+            }
+
+            votes.values().iter().any(|count| count >= threshold)
+        }
+
+        /// Marks stored confirmations of `event_type` as consumed.
+        pub fn mark_consumed(
+            env: Env,
+            consumer: Address,
+            escrow_id: Bytes,
+            event_type: u32,
+        ) -> u32 {
+            consumer.require_auth();
+            let Some(confirmations) = Self::get_confirmation(env.clone(), escrow_id.clone()) else {
+                return 0;
+            };
+
+            let mut marked = 0;
+            let mut updated = Vec::new(&env);
+            for mut conf in confirmations.iter() {
+                if conf.event_type == event_type && !conf.consumed {
+                    conf.consumed = true;
+                    marked += 1;
+                }
+                updated.push_back(conf);
+            }
+            Self::set_confirmation(env, escrow_id, updated);
+            marked
+        }
+
+        /// Records the slash request and reports the full amount as slashed.
+        pub fn slash(
+            env: Env,
+            slasher: Address,
+            oracle: Address,
+            escrow_id: Bytes,
+            event_type: u32,
+            amount: i128,
+            harmed: Address,
+        ) -> i128 {
+            slasher.require_auth();
+            env.storage().instance().set(
+                &symbol_short!("slashed"),
+                &(slasher, oracle, escrow_id, event_type, amount, harmed),
+            );
+            amount
+        }
+
+        /// Test helper: the last slash request received.
+        pub fn last_slash(env: Env) -> Option<(Address, Address, Bytes, u32, i128, Address)> {
+            env.storage().instance().get(&symbol_short!("slashed"))
+        }
+    }
+
+    // -- Helpers -----------------------------------------------------------
+
+    struct TestEnv<'a> {
+        env: Env,
+        escrow_client: EscrowManagerClient<'a>,
+        escrow_id_addr: Address,
+        coll_reg_addr: Address,
+        oracle_client: MockOracleAdapterWithConsensusClient<'a>,
+        oracle_addr: Option<Address>, // Add field for multi-oracle tests
+        token_addr: Address,
+        treasury_addr: Address,
+        buyer: Address,
+        seller: Address,
+        lender: Address,
+    }
+
+    fn setup() -> TestEnv<'static> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+
+        // Register contracts
+        let escrow_id_addr = env.register(EscrowManager, ());
+        let escrow_client = EscrowManagerClient::new(&env, &escrow_id_addr);
+
+        let coll_reg_addr = env.register(MockCollateralRegistry, ());
+        let oracle_addr = env.register(MockOracleAdapterWithConsensus, ());
+        let oracle_client = MockOracleAdapterWithConsensusClient::new(&env, &oracle_addr);
+
+        let loan_mgr_addr = Address::generate(&env); // placeholder
+        let treasury_addr = env.register(MockTreasury, ());
+
+        // Create a Stellar asset token
+        let token_admin = Address::generate(&env);
+        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+        let token_addr = token_contract.address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token_addr);
+        token_admin_client.mint(&lender, &1_000_000);
+
+        // Initialize escrow manager
+        escrow_client.initialize(&admin, &coll_reg_addr, &oracle_addr, &loan_mgr_addr, &treasury_addr);
+
+        // Collateral #1: owned by the buyer, worth far more than any test escrow
+        MockCollateralRegistryClient::new(&env, &coll_reg_addr)
+            .set_collateral(&test_collateral(&env, 1, &buyer, 1_000_000, 0));
+
+        // Leak lifetimes for test convenience
+        let escrow_client = unsafe {
+            core::mem::transmute::<EscrowManagerClient<'_>, EscrowManagerClient<'static>>(
+                escrow_client,
+            )
+        };
+        let oracle_client = unsafe {
+            core::mem::transmute::<MockOracleAdapterWithConsensusClient<'_>, MockOracleAdapterWithConsensusClient<'static>>(
+                oracle_client,
+            )
+        };
+
+        TestEnv {
+            env,
+            escrow_client,
+            escrow_id_addr,
+            coll_reg_addr,
+            oracle_client,
+            oracle_addr: None, // Initialize as None for single oracle setup
+            token_addr,
+            treasury_addr,
+            buyer,
+            seller,
+            lender,
+        }
+    }
+
+    fn test_collateral(
+        env: &Env,
+        id: u64,
+        owner: &Address,
+        realized_value: i128,
+        collateral_type: u32,
+    ) -> CollateralRecord {
+        CollateralRecord {
+            id,
+            owner: owner.clone(),
+            face_value: realized_value,
+            realized_value,
+            expiry_ts: env.ledger().timestamp() + 86_400 * 365,
+            metadata_hash: BytesN::from_array(env, &[id as u8; 32]),
+            registered_at: env.ledger().timestamp(),
+            last_valuation_ts: env.ledger().timestamp(),
+            locked: false,
+            collateral_type,
+        }
+    }
+
+    fn create_test_escrow(t: &TestEnv) -> u64 {
+        let expiry = t.env.ledger().timestamp() + 3600;
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32, // Delivery
+            expiry_ts: expiry,
+            destination_asset: t.token_addr.clone(),
+            min_destination_amount: 5000i128,
+            required_confirmations: 0u32,
+            oracle_set: Vec::new(&t.env),
+        })
+    }
+
+    fn set_oracle_confirmation(t: &TestEnv, escrow_id: u64, event_type: u32, verified: bool) {
+        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
+        let oracle_addr = Address::generate(&t.env);
+
+        let conf = ConfirmationData {
+            escrow_id: escrow_id_bytes.clone(),
+            event_type,
+            result: Bytes::from_slice(&t.env, b"confirmed"),
+            oracle: oracle_addr,
+            timestamp: t.env.ledger().timestamp(),
+            verified,
+            expires_at: 0,
+            consumed: false,
+        };
+
+        let confs = Vec::from_array(&t.env, [conf]);
+        t.oracle_client.set_confirmation(&escrow_id_bytes, &confs);
+    }
+
+    // -- Tests ------------------------------------------------------------
+
+    #[test]
+    fn test_initialize() {
+        let t = setup();
+
+        t.env.as_contract(&t.escrow_id_addr, || {
+            let admin: Address = t
+                .env
+                .storage()
+                .instance()
+                .get(&symbol_short!("admin"))
+                .unwrap();
+            assert!(admin == admin); // just check it exists
+
+            let coll_reg: Address = t
+                .env
+                .storage()
+                .instance()
+                .get(&symbol_short!("coll_reg"))
+                .unwrap();
+            assert_eq!(coll_reg, t.coll_reg_addr);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #2)")]
+    fn test_initialize_already_initialized() {
+        let t = setup();
+        let admin = Address::generate(&t.env);
+        let dummy = Address::generate(&t.env);
+        t.escrow_client
+            .initialize(&admin, &dummy, &dummy, &dummy, &dummy);
+    }
+
+    #[test]
+    fn test_create_escrow_success() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+        assert_eq!(escrow_id, 1);
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.buyer, t.buyer);
+        assert_eq!(escrow.seller, t.seller);
+        assert_eq!(escrow.lender, t.lender);
+        assert_eq!(escrow.collateral_id, 1);
+        assert_eq!(escrow.amount, 5000);
+        assert_eq!(escrow.required_confirmation, 2); // Delivery
+        assert_eq!(escrow.status, EscrowStatus::Active);
+
+        // Verify collateral was locked in mock
+        t.env.as_contract(&t.coll_reg_addr, || {
+            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
+            assert!(locked);
+        });
+
+        // Verify funds transferred to escrow contract
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.escrow_id_addr), 5000);
+        assert_eq!(token.balance(&t.lender), 1_000_000 - 5000);
+    }
+
+    #[test]
+    fn test_create_multiple_escrows() {
+        let t = setup();
+
+        let id1 = create_test_escrow(&t);
+        let id2 = create_test_escrow(&t);
+
+        assert_eq!(id1, 1);
+        assert_eq!(id2, 2);
+
+        assert!(t.escrow_client.get_escrow(&id1).is_some());
+        assert!(t.escrow_client.get_escrow(&id2).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #5)")]
+    fn test_create_escrow_invalid_amount() {
+        let t = setup();
+        let expiry = t.env.ledger().timestamp() + 3600;
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 0i128, // invalid
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32,
+            expiry_ts: expiry,
+            destination_asset: t.token_addr.clone(),
+            min_destination_amount: 5000i128,
+            required_confirmations: 0u32,
+            oracle_set: Vec::new(&t.env),
+        });
+    }
+
+    #[test]
+    fn test_release_funds_on_confirmation() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Set up oracle confirmation for Delivery (event_type=2)
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        // Verify status
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+
+        // Verify funds sent to seller
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.seller), 5000);
+        assert_eq!(token.balance(&t.escrow_id_addr), 0);
+
+        // Verify collateral was unlocked
+        t.env.as_contract(&t.coll_reg_addr, || {
+            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
+            assert!(!locked);
+        });
+
+        // Verify the confirmation can no longer be revoked
+        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
+        let confs = t.oracle_client.get_confirmation(&escrow_id_bytes).unwrap();
+        assert!(confs.get(0).unwrap().consumed);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_release_consumed_confirmation() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
+
+        // Already used by an earlier release, so it cannot release again
+        let conf = ConfirmationData {
+            escrow_id: escrow_id_bytes.clone(),
+            event_type: 2,
+            result: Bytes::from_slice(&t.env, b"confirmed"),
+            oracle: Address::generate(&t.env),
+            timestamp: t.env.ledger().timestamp(),
+            verified: true,
+            expires_at: 0,
+            consumed: true,
+        };
+        t.oracle_client
+            .set_confirmation(&escrow_id_bytes, &Vec::from_array(&t.env, [conf]));
+
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_release_expired_confirmation() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
+
+        let conf = ConfirmationData {
+            escrow_id: escrow_id_bytes.clone(),
+            event_type: 2,
+            result: Bytes::from_slice(&t.env, b"confirmed"),
+            oracle: Address::generate(&t.env),
+            timestamp: t.env.ledger().timestamp(),
+            verified: true,
+            expires_at: t.env.ledger().timestamp() + 600,
+            consumed: false,
+        };
+        t.oracle_client
+            .set_confirmation(&escrow_id_bytes, &Vec::from_array(&t.env, [conf]));
+
+        t.env.ledger().with_mut(|li| {
+            li.timestamp += 601;
+        });
+
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_release_without_confirmation() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // No oracle confirmation set
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_release_wrong_event_type() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Oracle confirmed Shipment (1) but escrow requires Delivery (2)
+        set_oracle_confirmation(&t, escrow_id, 1, false);
+
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_release_unverified_confirmation() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Right event type but verified=false
+        set_oracle_confirmation(&t, escrow_id, 2, false);
+
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #4)")]
+    fn test_release_already_released() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        // Try again
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    fn test_refund_escrow_success() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        let token = token::Client::new(&t.env, &t.token_addr);
+        let lender_balance_before = token.balance(&t.lender);
+
+        // Advance past expiry
+        t.env.ledger().with_mut(|li| {
+            li.timestamp += 3601;
+        });
+
+        t.escrow_client.refund_escrow(&escrow_id);
+
+        // Verify status
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Refunded);
+
+        // Verify funds returned to lender
+        assert_eq!(token.balance(&t.lender), lender_balance_before + 5000);
+        assert_eq!(token.balance(&t.escrow_id_addr), 0);
+
+        // Verify collateral unlocked
+        t.env.as_contract(&t.coll_reg_addr, || {
+            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
+            assert!(!locked);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #7)")]
+    fn test_refund_before_expiry() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Don't advance time - escrow not expired
+        t.escrow_client.refund_escrow(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #4)")]
+    fn test_refund_already_refunded() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        t.env.ledger().with_mut(|li| {
+            li.timestamp += 3601;
+        });
+
+        t.escrow_client.refund_escrow(&escrow_id);
+
+        // Try again
+        t.escrow_client.refund_escrow(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #4)")]
+    fn test_refund_after_release() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Release first
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        // Try to refund after release
+        t.env.ledger().with_mut(|li| {
+            li.timestamp += 3601;
+        });
+        t.escrow_client.refund_escrow(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #3)")]
+    fn test_release_nonexistent_escrow() {
+        let t = setup();
+        t.escrow_client.release_funds_on_confirmation(&999u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #3)")]
+    fn test_refund_nonexistent_escrow() {
+        let t = setup();
+        t.escrow_client.refund_escrow(&999u64);
+    }
+
+    #[test]
+    fn test_get_escrow_not_found() {
+        let t = setup();
+        assert!(t.escrow_client.get_escrow(&999u64).is_none());
+    }
+
+    #[test]
+    fn test_path_payment_same_asset() {
+        let t = setup();
+        let escrow_id = create_test_escrow(&t);
+
+        // Set oracle confirmation
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+
+        // Release with same source and destination asset
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+
+        // Verify seller received funds
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.seller), 5000);
+    }
+
+    #[test]
+    fn test_release_collects_protocol_fee() {
+        let t = setup();
+        let treasury = MockTreasuryClient::new(&t.env, &t.treasury_addr);
+        treasury.set_fee_bps(&100); // 1%
+
+        let escrow_id = create_test_escrow(&t);
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        // Fee is moved into the treasury and deducted from the seller payout
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.treasury_addr), 50);
+        assert_eq!(token.balance(&t.seller), 4950);
+        assert_eq!(token.balance(&t.escrow_id_addr), 0);
+        assert_eq!(treasury.get_total_fees(&t.token_addr), 50);
+    }
+
+    #[test]
+    fn test_cross_asset_release_swaps_net_of_fee() {
+        let t = setup();
+        let treasury = MockTreasuryClient::new(&t.env, &t.treasury_addr);
+        treasury.set_fee_bps(&100); // 1%
+
+        let (dest_token_addr, _amm) = setup_amm(&t, 1_000_000, 10_000);
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 4900);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        // Fee is taken in the source asset; only the remainder is swapped
+        let source = token::Client::new(&t.env, &t.token_addr);
+        let dest = token::Client::new(&t.env, &dest_token_addr);
+        assert_eq!(source.balance(&t.treasury_addr), 50);
+        assert_eq!(dest.balance(&t.seller), 4950);
+    }
+
+    /// Registers a destination token and a mock AMM funded with `liquidity`
+    /// of it, wired into the escrow manager as its swap adapter.
+    fn setup_amm(t: &TestEnv, rate: i128, liquidity: i128) -> (Address, MockAmmClient<'static>) {
+        let token_admin = Address::generate(&t.env);
+        let dest_token_addr = t
+            .env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+
+        let amm_addr = t.env.register(MockAmm, ());
+        let amm_client = MockAmmClient::new(&t.env, &amm_addr);
+        amm_client.set_rate(&rate);
+        token::StellarAssetClient::new(&t.env, &dest_token_addr).mint(&amm_addr, &liquidity);
+
+        t.escrow_client.set_swap_adapter(&amm_addr);
+
+        let amm_client = unsafe {
+            core::mem::transmute::<MockAmmClient<'_>, MockAmmClient<'static>>(amm_client)
+        };
+        (dest_token_addr, amm_client)
+    }
+
+    fn create_cross_asset_escrow(t: &TestEnv, dest_token_addr: &Address, min_out: i128) -> u64 {
+        let expiry = t.env.ledger().timestamp() + 3600;
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32,
+            expiry_ts: expiry,
+            destination_asset: dest_token_addr.clone(),
+            min_destination_amount: min_out,
+            required_confirmations: 0u32,
+            oracle_set: Vec::new(&t.env),
+        })
+    }
+
+    #[test]
+    fn test_set_swap_adapter() {
+        let t = setup();
+        assert!(t.escrow_client.get_swap_adapter().is_none());
+
+        let adapter = Address::generate(&t.env);
+        t.escrow_client.set_swap_adapter(&adapter);
+        assert_eq!(t.escrow_client.get_swap_adapter(), Some(adapter));
+    }
+
+    #[test]
+    fn test_path_payment_different_asset() {
+        let t = setup();
+
+        // AMM rate: 0.95 (5% loss in conversion)
+        let (dest_token_addr, _amm) = setup_amm(&t, 950_000, 10_000);
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 4500);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+
+        // Seller is paid in the destination asset, source asset went to the AMM
+        let source = token::Client::new(&t.env, &t.token_addr);
+        let dest = token::Client::new(&t.env, &dest_token_addr);
+        assert_eq!(dest.balance(&t.seller), 4750);
+        assert_eq!(source.balance(&t.seller), 0);
+        assert_eq!(source.balance(&t.escrow_id_addr), 0);
+        assert_eq!(dest.balance(&t.escrow_id_addr), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #9)")]
+    fn test_path_payment_slippage_exceeded() {
+        let t = setup();
+
+        // AMM rate: 0.95 (5% loss) with no slippage tolerance on the escrow
+        let (dest_token_addr, _amm) = setup_amm(&t, 950_000, 10_000);
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 5000);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+
+        // Release should fail due to slippage
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #9)")]
+    fn test_path_payment_misreported_output_rejected() {
+        let t = setup();
+
+        // AMM claims to meet min_out but only delivers 4750
+        let (dest_token_addr, amm) = setup_amm(&t, 950_000, 10_000);
+        amm.set_misreport(&true);
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 5000);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    fn test_path_payment_slippage_leaves_escrow_active() {
+        let t = setup();
+
+        let (dest_token_addr, _amm) = setup_amm(&t, 950_000, 10_000);
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 5000);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        let result = t.escrow_client.try_release_funds_on_confirmation(&escrow_id);
+        assert!(result.is_err());
+
+        // Failed swap is rolled back: funds still held by the escrow
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Active);
+        let source = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(source.balance(&t.escrow_id_addr), 5000);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #13)")]
+    fn test_path_payment_without_swap_adapter() {
+        let t = setup();
+
+        let token_admin = Address::generate(&t.env);
+        let dest_token_addr = t
+            .env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        let escrow_id = create_cross_asset_escrow(&t, &dest_token_addr, 4500);
+
+        set_oracle_confirmation(&t, escrow_id, 2, true);
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    }
+
+    #[test]
+    fn test_create_escrow_with_path_payment_params() {
+        let t = setup();
+
+        // Create a second token for destination
+        let token_admin = Address::generate(&t.env);
+        let dest_token_contract = t.env.register_stellar_asset_contract_v2(token_admin);
+        let dest_token_addr = dest_token_contract.address();
+
+        let expiry = t.env.ledger().timestamp() + 3600;
+        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32,
+            expiry_ts: expiry,
+            destination_asset: dest_token_addr.clone(),
+            min_destination_amount: 4500i128,
+            required_confirmations: 0u32,
+            oracle_set: Vec::new(&t.env),
+        });
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.destination_asset, dest_token_addr);
+        assert_eq!(escrow.min_destination_amount, 4500);
+        assert_eq!(escrow.status, EscrowStatus::Active);
+    }
+
+    // -- Multi-Oracle Consensus Tests -------------------------------------
+
+    fn setup_multi_oracle() -> TestEnv<'static> {
+        let mut t = setup();
+
+        // setup() already wires in the consensus-capable oracle mock
+        t.oracle_addr = Some(t.oracle_client.address.clone());
+
+        t
+    }
+
+    fn create_multi_oracle_escrow(t: &TestEnv, threshold: u32, oracle_set: Vec<Address>) -> u64 {
+        let expiry = t.env.ledger().timestamp() + 3600;
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32, // Delivery
+            expiry_ts: expiry,
+            destination_asset: t.token_addr.clone(),
+            min_destination_amount: 5000i128,
+            required_confirmations: threshold,
+            oracle_set,
+        })
+    }
+
+    fn set_multi_oracle_confirmations(
+        t: &TestEnv,
+        escrow_id: u64,
+        oracles: Vec<Address>,
+        event_type: u32,
+        verified: bool,
+    ) {
+        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
+        let mut confirmations = Vec::new(&t.env);
+
+        for oracle in oracles.iter() {
+            let conf = ConfirmationData {
+                escrow_id: escrow_id_bytes.clone(),
+                event_type,
+                result: Bytes::from_slice(&t.env, b"confirmed"),
+                oracle: oracle.clone(),
+                timestamp: t.env.ledger().timestamp(),
+                verified,
+                expires_at: 0,
+                consumed: false,
+            };
+            confirmations.push_back(conf);
+        }
+
+        let oracle_addr = t.oracle_addr.clone().expect("Oracle address must be set in multi-oracle tests");
+        let oracle_client = MockOracleAdapterWithConsensusClient::new(&t.env, &oracle_addr);
+        oracle_client.set_confirmation(&escrow_id_bytes, &confirmations);
     }
 
-    fn setup() -> TestEnv<'static> {
-        let env = Env::default();
-        env.mock_all_auths();
+    #[test]
+    fn test_multi_oracle_consensus_success() {
+        let t = setup_multi_oracle();
 
-        let admin = Address::generate(&env);
-        let buyer = Address::generate(&env);
-        let seller = Address::generate(&env);
-        let lender = Address::generate(&env);
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let oracle3 = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
 
-        // Register contracts
-        let escrow_id_addr = env.register(EscrowManager, ());
-        let escrow_client = EscrowManagerClient::new(&env, &escrow_id_addr);
+        // Create escrow requiring 2 out of 3 oracles
+        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set.clone());
 
-        let coll_reg_addr = env.register(MockCollateralRegistry, ());
-        let oracle_addr = env.register(MockOracleAdapter, ());
-        let oracle_client = MockOracleAdapterClient::new(&env, &oracle_addr);
+        // Set confirmations from 2 different oracles
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
+            2, // Delivery event
+            true,
+        );
 
-        let loan_mgr_addr = Address::generate(&env); // placeholder
-        let treasury_addr = Address::generate(&env); // placeholder treasury
+        // Release should succeed
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
 
-        // Create a Stellar asset token
-        let token_admin = Address::generate(&env);
-        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
-        let token_addr = token_contract.address();
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_addr);
-        token_admin_client.mint(&lender, &1_000_000);
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
 
-        // Initialize escrow manager
-        escrow_client.initialize(&admin, &coll_reg_addr, &oracle_addr, &loan_mgr_addr, &treasury_addr);
+        // Verify funds sent to seller
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.seller), 5000);
+    }
 
-        // Leak lifetimes for test convenience
-        let escrow_client = unsafe {
-            core::mem::transmute::<EscrowManagerClient<'_>, EscrowManagerClient<'static>>(
-                escrow_client,
-            )
-        };
-        let oracle_client = unsafe {
-            core::mem::transmute::<MockOracleAdapterClient<'_>, MockOracleAdapterClient<'static>>(
-                oracle_client,
-            )
-        };
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #12)")]
+    fn test_multi_oracle_consensus_insufficient_confirmations() {
+        let t = setup_multi_oracle();
 
-        TestEnv {
-            env,
-            escrow_client,
-            escrow_id_addr,
-            coll_reg_addr,
-            oracle_client,
-            oracle_addr: None, // Initialize as None for single oracle setup
-            token_addr,
-            treasury_addr,
-            buyer,
-            seller,
-            lender,
-        }
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let oracle3 = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
+
+        // Create escrow requiring 3 out of 3 oracles
+        let escrow_id = create_multi_oracle_escrow(&t, 3u32, oracle_set.clone());
+
+        // Only provide 2 confirmations
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
+            2, // Delivery event
+            true,
+        );
+
+        // Release should fail - consensus not met
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
     }
 
-    fn create_test_escrow(t: &TestEnv) -> u64 {
-        let expiry = t.env.ledger().timestamp() + 3600;
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #10)")]
+    fn test_multi_oracle_create_escrow_empty_oracle_set() {
+        let t = setup_multi_oracle();
+
+        // Try to create escrow with required_confirmations > 0 but empty oracle_set
         t.escrow_client.create_escrow(&EscrowConfig {
             buyer: t.buyer.clone(),
             seller: t.seller.clone(),
@@ -771,696 +2662,764 @@ mod test {
             collateral_id: 1u64,
             amount: 5000i128,
             asset: t.token_addr.clone(),
-            required_confirmation: 2u32, // Delivery
-            expiry_ts: expiry,
+            required_confirmation: 2u32,
+            expiry_ts: t.env.ledger().timestamp() + 3600,
             destination_asset: t.token_addr.clone(),
             min_destination_amount: 5000i128,
-            required_confirmations: 0u32,
-            oracle_set: Vec::new(&t.env),
-        })
+            required_confirmations: 2u32, // > 0 requires oracle_set
+            oracle_set: Vec::new(&t.env), // Empty - should fail
+        });
     }
 
-    fn set_oracle_confirmation(t: &TestEnv, escrow_id: u64, event_type: u32, verified: bool) {
-        let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
-        let oracle_addr = Address::generate(&t.env);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #11)")]
+    fn test_multi_oracle_create_escrow_threshold_exceeds_set_size() {
+        let t = setup_multi_oracle();
 
-        let conf = ConfirmationData {
-            escrow_id: escrow_id_bytes.clone(),
-            event_type,
-            result: Bytes::from_slice(&t.env, b"confirmed"),
-            oracle: oracle_addr,
-            timestamp: t.env.ledger().timestamp(),
-            verified,
-        };
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
 
-        let confs = Vec::from_array(&t.env, [conf]);
-        t.oracle_client.set_confirmation(&escrow_id_bytes, &confs);
+        // Try to create escrow with threshold 3 but only 2 oracles in set
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32,
+            expiry_ts: t.env.ledger().timestamp() + 3600,
+            destination_asset: t.token_addr.clone(),
+            min_destination_amount: 5000i128,
+            required_confirmations: 3u32, // > oracle_set.len()
+            oracle_set,
+        });
     }
 
-    // -- Tests ------------------------------------------------------------
-
     #[test]
-    fn test_initialize() {
-        let t = setup();
+    #[should_panic(expected = "HostError: Error(Contract, #10)")]
+    fn test_multi_oracle_create_escrow_duplicate_oracles() {
+        let t = setup_multi_oracle();
 
-        t.env.as_contract(&t.escrow_id_addr, || {
-            let admin: Address = t
-                .env
-                .storage()
-                .instance()
-                .get(&symbol_short!("admin"))
-                .unwrap();
-            assert!(admin == admin); // just check it exists
+        let oracle1 = Address::generate(&t.env);
+        // Include the same oracle twice
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle1.clone()]);
 
-            let coll_reg: Address = t
-                .env
-                .storage()
-                .instance()
-                .get(&symbol_short!("coll_reg"))
-                .unwrap();
-            assert_eq!(coll_reg, t.coll_reg_addr);
+        t.escrow_client.create_escrow(&EscrowConfig {
+            buyer: t.buyer.clone(),
+            seller: t.seller.clone(),
+            lender: t.lender.clone(),
+            collateral_id: 1u64,
+            amount: 5000i128,
+            asset: t.token_addr.clone(),
+            required_confirmation: 2u32,
+            expiry_ts: t.env.ledger().timestamp() + 3600,
+            destination_asset: t.token_addr.clone(),
+            min_destination_amount: 5000i128,
+            required_confirmations: 2u32,
+            oracle_set, // Contains duplicate
         });
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #2)")]
-    fn test_initialize_already_initialized() {
-        let t = setup();
-        let admin = Address::generate(&t.env);
-        let dummy = Address::generate(&t.env);
-        t.escrow_client.initialize(&admin, &dummy, &dummy, &dummy);
+    #[should_panic(expected = "HostError: Error(Contract, #12)")]
+    fn test_multi_oracle_consensus_ignores_unauthorized_oracles() {
+        let t = setup_multi_oracle();
+
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let unauthorized_oracle = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
+
+        // Create escrow requiring 2 oracles from the authorized set
+        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set.clone());
+
+        // Set confirmations: 1 authorized + 1 unauthorized
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1.clone(), unauthorized_oracle]),
+            2, // Delivery event
+            true,
+        );
+
+        // Release should fail - only 1 authorized confirmation
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
     }
 
     #[test]
-    fn test_create_escrow_success() {
-        let t = setup();
-        let escrow_id = create_test_escrow(&t);
-        assert_eq!(escrow_id, 1);
+    fn test_multi_oracle_consensus_after_second_authorized_oracle() {
+        let t = setup_multi_oracle();
 
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.buyer, t.buyer);
-        assert_eq!(escrow.seller, t.seller);
-        assert_eq!(escrow.lender, t.lender);
-        assert_eq!(escrow.collateral_id, 1);
-        assert_eq!(escrow.amount, 5000);
-        assert_eq!(escrow.required_confirmation, 2); // Delivery
-        assert_eq!(escrow.status, EscrowStatus::Active);
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let unauthorized_oracle = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
 
-        // Verify collateral was locked in mock
-        t.env.as_contract(&t.coll_reg_addr, || {
-            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
-            assert!(locked);
-        });
+        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set.clone());
+
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1.clone(), unauthorized_oracle]),
+            2, // Delivery event
+            true,
+        );
+        assert!(t
+            .escrow_client
+            .try_release_funds_on_confirmation(&escrow_id)
+            .is_err());
+
+        // Add the second authorized confirmation
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
+            2, // Delivery event
+            true,
+        );
 
-        // Verify funds transferred to escrow contract
-        let token = token::Client::new(&t.env, &t.token_addr);
-        assert_eq!(token.balance(&t.escrow_id_addr), 5000);
-        assert_eq!(token.balance(&t.lender), 1_000_000 - 5000);
+        // Now release should succeed
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
     }
 
     #[test]
-    fn test_create_multiple_escrows() {
-        let t = setup();
+    #[should_panic(expected = "HostError: Error(Contract, #12)")]
+    fn test_multi_oracle_consensus_ignores_other_event_types() {
+        let t = setup_multi_oracle();
 
-        let id1 = create_test_escrow(&t);
-        let id2 = create_test_escrow(&t);
+        let oracle1 = Address::generate(&t.env);
+        let oracle2 = Address::generate(&t.env);
+        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
 
-        assert_eq!(id1, 1);
-        assert_eq!(id2, 2);
+        // Escrow requires Delivery; both oracles only confirmed Shipment
+        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set);
+        set_multi_oracle_confirmations(
+            &t,
+            escrow_id,
+            Vec::from_array(&t.env, [oracle1, oracle2]),
+            1, // Shipment event
+            true,
+        );
 
-        assert!(t.escrow_client.get_escrow(&id1).is_some());
-        assert!(t.escrow_client.get_escrow(&id2).is_some());
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #5)")]
-    fn test_create_escrow_invalid_amount() {
-        let t = setup();
-        let expiry = t.env.ledger().timestamp() + 3600;
-        t.escrow_client.create_escrow(&EscrowConfig {
+    fn test_multi_oracle_backward_compatibility_single_oracle() {
+        let t = setup_multi_oracle();
+
+        // Create escrow with required_confirmations = 0 (single oracle mode)
+        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
             buyer: t.buyer.clone(),
             seller: t.seller.clone(),
             lender: t.lender.clone(),
             collateral_id: 1u64,
-            amount: 0i128, // invalid
+            amount: 5000i128,
             asset: t.token_addr.clone(),
             required_confirmation: 2u32,
-            expiry_ts: expiry,
+            expiry_ts: t.env.ledger().timestamp() + 3600,
             destination_asset: t.token_addr.clone(),
             min_destination_amount: 5000i128,
-            required_confirmations: 0u32,
-            oracle_set: Vec::new(&t.env),
+            required_confirmations: 0u32, // Single oracle mode
+            oracle_set: Vec::new(&t.env), // Ignored in single oracle mode
         });
-    }
-
-    #[test]
-    fn test_release_funds_on_confirmation() {
-        let t = setup();
-        let escrow_id = create_test_escrow(&t);
 
-        // Set up oracle confirmation for Delivery (event_type=2)
+        // Set single oracle confirmation using the old method
         set_oracle_confirmation(&t, escrow_id, 2, true);
 
+        // Release should succeed using backward compatibility
         t.escrow_client.release_funds_on_confirmation(&escrow_id);
 
-        // Verify status
         let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
         assert_eq!(escrow.status, EscrowStatus::Released);
+    }
 
-        // Verify funds sent to seller
-        let token = token::Client::new(&t.env, &t.token_addr);
-        assert_eq!(token.balance(&t.seller), 5000);
-        assert_eq!(token.balance(&t.escrow_id_addr), 0);
+    // -- Disputes -----------------------------------------------------------
+
+    fn setup_disputes(t: &TestEnv) -> (Address, MockReputationRegistryClient<'static>) {
+        let arbitrator = Address::generate(&t.env);
+        t.escrow_client
+            .set_arbitrators(&Vec::from_array(&t.env, [arbitrator.clone()]));
+
+        let registry_addr = t.env.register(MockReputationRegistry, ());
+        t.escrow_client.set_reputation_registry(&registry_addr);
+        let registry = MockReputationRegistryClient::new(&t.env, &registry_addr);
+        let registry = unsafe {
+            core::mem::transmute::<
+                MockReputationRegistryClient<'_>,
+                MockReputationRegistryClient<'static>,
+            >(registry)
+        };
 
-        // Verify collateral was unlocked
-        t.env.as_contract(&t.coll_reg_addr, || {
-            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
-            assert!(!locked);
-        });
+        (arbitrator, registry)
     }
 
-    #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #6)")]
-    fn test_release_without_confirmation() {
-        let t = setup();
-        let escrow_id = create_test_escrow(&t);
-
-        // No oracle confirmation set
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+    fn evidence(t: &TestEnv) -> BytesN<32> {
+        BytesN::from_array(&t.env, &[7u8; 32])
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #6)")]
-    fn test_release_wrong_event_type() {
+    fn test_open_dispute() {
         let t = setup();
         let escrow_id = create_test_escrow(&t);
 
-        // Oracle confirmed Shipment (1) but escrow requires Delivery (2)
-        set_oracle_confirmation(&t, escrow_id, 1, false);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
 
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Disputed);
+
+        let dispute = t.escrow_client.get_dispute(&escrow_id).unwrap();
+        assert_eq!(dispute.opened_by, t.buyer);
+        assert_eq!(dispute.evidence_hash, evidence(&t));
+        assert!(!dispute.resolved);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #6)")]
-    fn test_release_unverified_confirmation() {
+    #[should_panic(expected = "HostError: Error(Contract, #1)")]
+    fn test_lender_cannot_open_dispute() {
         let t = setup();
         let escrow_id = create_test_escrow(&t);
 
-        // Right event type but verified=false
-        set_oracle_confirmation(&t, escrow_id, 2, false);
-
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.lender, &evidence(&t));
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #4)")]
-    fn test_release_already_released() {
+    #[should_panic(expected = "HostError: Error(Contract, #14)")]
+    fn test_dispute_freezes_release() {
         let t = setup();
         let escrow_id = create_test_escrow(&t);
-
         set_oracle_confirmation(&t, escrow_id, 2, true);
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
 
-        // Try again
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.seller, &evidence(&t));
         t.escrow_client.release_funds_on_confirmation(&escrow_id);
     }
 
     #[test]
-    fn test_refund_escrow_success() {
+    #[should_panic(expected = "HostError: Error(Contract, #14)")]
+    fn test_dispute_freezes_refund() {
         let t = setup();
         let escrow_id = create_test_escrow(&t);
 
-        let token = token::Client::new(&t.env, &t.token_addr);
-        let lender_balance_before = token.balance(&t.lender);
-
-        // Advance past expiry
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
         t.env.ledger().with_mut(|li| {
             li.timestamp += 3601;
         });
-
         t.escrow_client.refund_escrow(&escrow_id);
-
-        // Verify status
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Refunded);
-
-        // Verify funds returned to lender
-        assert_eq!(token.balance(&t.lender), lender_balance_before + 5000);
-        assert_eq!(token.balance(&t.escrow_id_addr), 0);
-
-        // Verify collateral unlocked
-        t.env.as_contract(&t.coll_reg_addr, || {
-            let locked: bool = t.env.storage().persistent().get(&1u64).unwrap();
-            assert!(!locked);
-        });
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #7)")]
-    fn test_refund_before_expiry() {
+    fn test_resolve_dispute_split_ruling() {
         let t = setup();
+        let (arbitrator, registry) = setup_disputes(&t);
         let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
 
-        // Don't advance time - escrow not expired
-        t.escrow_client.refund_escrow(&escrow_id);
+        t.escrow_client
+            .resolve_dispute(&escrow_id, &arbitrator, &7000);
+
+        let token = token::Client::new(&t.env, &t.token_addr);
+        assert_eq!(token.balance(&t.seller), 3500);
+        assert_eq!(token.balance(&t.lender), 1_000_000 - 5000 + 1500);
+
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Resolved);
+        let dispute = t.escrow_client.get_dispute(&escrow_id).unwrap();
+        assert!(dispute.resolved);
+        assert_eq!(dispute.seller_bps, 7000);
+        assert_eq!(dispute.arbitrator, Some(arbitrator));
+
+        // The buyer's side was awarded less, so the buyer lost the dispute
+        let dispute_lost = ReputationEvent::DisputeLost as u32;
+        assert_eq!(registry.get_count(&t.buyer, &dispute_lost), 1);
+        assert_eq!(registry.get_count(&t.seller, &dispute_lost), 0);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #4)")]
-    fn test_refund_already_refunded() {
+    fn test_even_split_records_no_loser() {
         let t = setup();
+        let (arbitrator, registry) = setup_disputes(&t);
         let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.seller, &evidence(&t));
 
-        t.env.ledger().with_mut(|li| {
-            li.timestamp += 3601;
-        });
-
-        t.escrow_client.refund_escrow(&escrow_id);
+        t.escrow_client
+            .resolve_dispute(&escrow_id, &arbitrator, &5000);
 
-        // Try again
-        t.escrow_client.refund_escrow(&escrow_id);
+        let dispute_lost = ReputationEvent::DisputeLost as u32;
+        assert_eq!(registry.get_count(&t.buyer, &dispute_lost), 0);
+        assert_eq!(registry.get_count(&t.seller, &dispute_lost), 0);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #4)")]
-    fn test_refund_after_release() {
+    #[should_panic(expected = "HostError: Error(Contract, #1)")]
+    fn test_resolve_dispute_requires_arbitrator() {
         let t = setup();
+        setup_disputes(&t);
         let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
 
-        // Release first
-        set_oracle_confirmation(&t, escrow_id, 2, true);
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
-
-        // Try to refund after release
-        t.env.ledger().with_mut(|li| {
-            li.timestamp += 3601;
-        });
-        t.escrow_client.refund_escrow(&escrow_id);
+        t.escrow_client
+            .resolve_dispute(&escrow_id, &t.seller, &10000);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #3)")]
-    fn test_release_nonexistent_escrow() {
+    #[should_panic(expected = "HostError: Error(Contract, #16)")]
+    fn test_resolve_dispute_invalid_ruling() {
         let t = setup();
-        t.escrow_client.release_funds_on_confirmation(&999u64);
-    }
+        let (arbitrator, _) = setup_disputes(&t);
+        let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
 
-    #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #3)")]
-    fn test_refund_nonexistent_escrow() {
-        let t = setup();
-        t.escrow_client.refund_escrow(&999u64);
+        t.escrow_client
+            .resolve_dispute(&escrow_id, &arbitrator, &10001);
     }
 
     #[test]
-    fn test_get_escrow_not_found() {
+    fn test_governance_panel_replaces_arbitrators() {
         let t = setup();
-        assert!(t.escrow_client.get_escrow(&999u64).is_none());
-    }
+        let (arbitrator, registry) = setup_disputes(&t);
+        let governance = Address::generate(&t.env);
+        t.escrow_client.set_governance(&governance);
 
-    #[test]
-    fn test_path_payment_same_asset() {
-        let t = setup();
         let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.seller, &evidence(&t));
 
-        // Set oracle confirmation
-        set_oracle_confirmation(&t, escrow_id, 2, true);
+        let panelist = Address::generate(&t.env);
+        t.escrow_client
+            .appoint_panel(&escrow_id, &Vec::from_array(&t.env, [panelist.clone()]));
 
-        // Release with same source and destination asset
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        // The standing arbitrator no longer rules on this dispute
+        assert!(t
+            .escrow_client
+            .try_resolve_dispute(&escrow_id, &arbitrator, &0)
+            .is_err());
 
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Released);
+        t.escrow_client.resolve_dispute(&escrow_id, &panelist, &0);
 
-        // Verify seller received funds
         let token = token::Client::new(&t.env, &t.token_addr);
-        assert_eq!(token.balance(&t.seller), 5000);
+        assert_eq!(token.balance(&t.lender), 1_000_000);
+        let dispute_lost = ReputationEvent::DisputeLost as u32;
+        assert_eq!(registry.get_count(&t.seller, &dispute_lost), 1);
     }
 
     #[test]
-    fn test_path_payment_different_asset() {
+    fn test_slash_oracle_after_ruling() {
         let t = setup();
-
-        // Create a second token for destination
-        let token_admin = Address::generate(&t.env);
-        let dest_token_contract = t
-            .env
-            .register_stellar_asset_contract_v2(token_admin.clone());
-        let dest_token_addr = dest_token_contract.address();
-        let dest_token_admin_client = token::StellarAssetClient::new(&t.env, &dest_token_addr);
-
-        // Mint destination tokens to the escrow contract for the swap
-        dest_token_admin_client.mint(&t.escrow_id_addr, &10_000);
-
-        // Create escrow with different destination asset
-        let expiry = t.env.ledger().timestamp() + 3600;
-        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: expiry,
-            destination_asset: dest_token_addr.clone(),
-            min_destination_amount: 4500i128,
-            required_confirmations: 0u32,
-            oracle_set: Vec::new(&t.env),
-        });
-
-        // Set exchange rate: 0.95 (5% loss in conversion)
-        t.escrow_client.set_test_exchange_rate(&950_000i128);
-
-        // Set oracle confirmation
-        set_oracle_confirmation(&t, escrow_id, 2, true);
-
-        // Release with path payment
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
-
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Released);
+        let (arbitrator, _) = setup_disputes(&t);
+        let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
+        t.escrow_client.resolve_dispute(&escrow_id, &arbitrator, &0);
+
+        // The oracle confirmed a delivery the ruling found did not happen
+        let oracle = Address::generate(&t.env);
+        let slashed = t
+            .escrow_client
+            .slash_oracle(&escrow_id, &arbitrator, &oracle, &500);
+        assert_eq!(slashed, 500);
+
+        let (slasher, slashed_oracle, escrow_bytes, event_type, amount, harmed) =
+            t.oracle_client.last_slash().unwrap();
+        assert_eq!(slasher, t.escrow_id_addr);
+        assert_eq!(slashed_oracle, oracle);
+        assert_eq!(
+            escrow_bytes,
+            Bytes::from_slice(&t.env, &escrow_id.to_be_bytes())
+        );
+        assert_eq!(event_type, 2);
+        assert_eq!(amount, 500);
+        assert_eq!(harmed, t.buyer);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #9)")]
-    fn test_path_payment_slippage_exceeded() {
+    #[should_panic(expected = "HostError: Error(Contract, #1)")]
+    fn test_slash_oracle_requires_ruling_arbitrator() {
         let t = setup();
+        let (arbitrator, _) = setup_disputes(&t);
+        let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
+        t.escrow_client.resolve_dispute(&escrow_id, &arbitrator, &0);
 
-        // Create a second token for destination
-        let token_admin = Address::generate(&t.env);
-        let dest_token_contract = t
-            .env
-            .register_stellar_asset_contract_v2(token_admin.clone());
-        let dest_token_addr = dest_token_contract.address();
-
-        // Create escrow with different destination asset
-        let expiry = t.env.ledger().timestamp() + 3600;
-        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: expiry,
-            destination_asset: dest_token_addr.clone(),
-            min_destination_amount: 5000i128, // Same as source amount (no slippage tolerance)
-            required_confirmations: 0u32,
-            oracle_set: Vec::new(&t.env),
-        });
-
-        // Set exchange rate: 0.95 (5% loss in conversion)
-        t.escrow_client.set_test_exchange_rate(&950_000i128);
-
-        // Set oracle confirmation
-        set_oracle_confirmation(&t, escrow_id, 2, true);
-
-        // Release should fail due to slippage
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        let oracle = Address::generate(&t.env);
+        t.escrow_client
+            .slash_oracle(&escrow_id, &t.buyer, &oracle, &500);
     }
 
     #[test]
-    fn test_create_escrow_with_path_payment_params() {
+    #[should_panic(expected = "HostError: Error(Contract, #1)")]
+    fn test_slash_oracle_requires_resolved_dispute() {
         let t = setup();
-
-        // Create a second token for destination
-        let token_admin = Address::generate(&t.env);
-        let dest_token_contract = t.env.register_stellar_asset_contract_v2(token_admin);
-        let dest_token_addr = dest_token_contract.address();
-
-        let expiry = t.env.ledger().timestamp() + 3600;
-        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: expiry,
-            destination_asset: dest_token_addr.clone(),
-            min_destination_amount: 4500i128,
-            required_confirmations: 0u32,
-            oracle_set: Vec::new(&t.env),
-        });
-
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.destination_asset, dest_token_addr);
-        assert_eq!(escrow.min_destination_amount, 4500);
-        assert_eq!(escrow.status, EscrowStatus::Active);
+        let (arbitrator, _) = setup_disputes(&t);
+        let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
+
+        let oracle = Address::generate(&t.env);
+        t.escrow_client
+            .slash_oracle(&escrow_id, &arbitrator, &oracle, &500);
     }
 
-    // -- Multi-Oracle Consensus Tests -------------------------------------
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #27)")]
+    fn test_slash_oracle_requires_ruling_against_confirmation() {
+        let t = setup();
+        let (arbitrator, _) = setup_disputes(&t);
+        let escrow_id = create_test_escrow(&t);
+        t.escrow_client
+            .open_dispute(&escrow_id, &t.buyer, &evidence(&t));
 
-    fn setup_multi_oracle() -> TestEnv<'static> {
-        let mut t = setup();
-        
-        // Replace with multi-oracle consensus adapter
-        let oracle_addr = t.env.register(MockOracleAdapterWithConsensus, ());
-        
-        // Store oracle address in TestEnv for test helpers
-        t.oracle_addr = Some(oracle_addr.clone());
-        
-        // Update oracle address in storage directly (avoid re-initialization)
-        t.env.as_contract(&t.escrow_client.address, || {
-            t.env.storage()
-                .instance()
-                .set(&symbol_short!("oracle"), &oracle_addr);
-        });
+        // The ruling sided with the seller, as the confirmation did
+        t.escrow_client.resolve_dispute(&escrow_id, &arbitrator, &7000);
 
-        t
+        let oracle = Address::generate(&t.env);
+        t.escrow_client
+            .slash_oracle(&escrow_id, &arbitrator, &oracle, &500);
     }
 
-    fn create_multi_oracle_escrow(t: &TestEnv, threshold: u32, oracle_set: Vec<Address>) -> u64 {
-        let expiry = t.env.ledger().timestamp() + 3600;
-        t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32, // Delivery
-            expiry_ts: expiry,
-            destination_asset: t.token_addr.clone(),
-            min_destination_amount: 5000i128,
-            required_confirmations: threshold,
-            oracle_set,
-        })
+    // -- Milestones ---------------------------------------------------------
+
+    /// 30% on Shipment, 50% on Delivery, 20% on Quality.
+    fn create_milestone_escrow(t: &TestEnv) -> u64 {
+        let milestone = |event_type: u32, share_bps: u32| MilestoneConfig {
+            event_type,
+            required_confirmations: 0,
+            share_bps,
+        };
+        t.escrow_client.create_milestone_escrow(
+            &EscrowConfig {
+                buyer: t.buyer.clone(),
+                seller: t.seller.clone(),
+                lender: t.lender.clone(),
+                collateral_id: 1u64,
+                amount: 5000i128,
+                asset: t.token_addr.clone(),
+                required_confirmation: 0u32,
+                expiry_ts: t.env.ledger().timestamp() + 3600,
+                destination_asset: t.token_addr.clone(),
+                min_destination_amount: 5000i128,
+                required_confirmations: 0u32,
+                oracle_set: Vec::new(&t.env),
+            },
+            &Vec::from_array(
+                &t.env,
+                [milestone(1, 3000), milestone(2, 5000), milestone(3, 2000)],
+            ),
+        )
     }
 
-    fn set_multi_oracle_confirmations(
-        t: &TestEnv,
-        escrow_id: u64,
-        oracles: Vec<Address>,
-        event_type: u32,
-        verified: bool,
-    ) {
+    fn confirm_events(t: &TestEnv, escrow_id: u64, event_types: &[u32]) {
         let escrow_id_bytes = Bytes::from_slice(&t.env, &escrow_id.to_be_bytes());
-        let mut confirmations = Vec::new(&t.env);
-
-        for oracle in oracles.iter() {
-            let conf = ConfirmationData {
+        let mut confs = Vec::new(&t.env);
+        for event_type in event_types {
+            confs.push_back(ConfirmationData {
                 escrow_id: escrow_id_bytes.clone(),
-                event_type,
+                event_type: *event_type,
                 result: Bytes::from_slice(&t.env, b"confirmed"),
-                oracle: oracle.clone(),
+                oracle: Address::generate(&t.env),
                 timestamp: t.env.ledger().timestamp(),
-                verified,
-            };
-            confirmations.push_back(conf);
+                verified: true,
+                expires_at: 0,
+                consumed: false,
+            });
         }
-
-        let oracle_addr = t.oracle_addr.expect("Oracle address must be set in multi-oracle tests");
-        let oracle_client = MockOracleAdapterWithConsensusClient::new(&t.env, &oracle_addr);
-        oracle_client.set_confirmation(&escrow_id_bytes, &confirmations);
+        t.oracle_client.set_confirmation(&escrow_id_bytes, &confs);
     }
 
     #[test]
-    fn test_multi_oracle_consensus_success() {
-        let t = setup_multi_oracle();
-
-        let oracle1 = Address::generate(&t.env);
-        let oracle2 = Address::generate(&t.env);
-        let oracle3 = Address::generate(&t.env);
-        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
+    fn test_milestones_release_in_stages() {
+        let t = setup();
+        let escrow_id = create_milestone_escrow(&t);
+        let token = token::Client::new(&t.env, &t.token_addr);
 
-        // Create escrow requiring 2 out of 3 oracles
-        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set.clone());
+        let milestones = t.escrow_client.get_milestones(&escrow_id);
+        assert_eq!(milestones.len(), 3);
+        assert_eq!(milestones.get(1).unwrap().amount, 2500);
 
-        // Set confirmations from 2 different oracles
-        set_multi_oracle_confirmations(
-            &t,
-            escrow_id,
-            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
-            2, // Delivery event
-            true,
-        );
+        confirm_events(&t, escrow_id, &[1]);
+        t.escrow_client.release_milestone(&escrow_id, &0);
+        assert_eq!(token.balance(&t.seller), 1500);
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Active);
 
-        // Release should succeed
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        confirm_events(&t, escrow_id, &[1, 2, 3]);
+        t.escrow_client.release_milestone(&escrow_id, &1);
+        t.escrow_client.release_milestone(&escrow_id, &2);
 
+        assert_eq!(token.balance(&t.seller), 5000);
         let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
         assert_eq!(escrow.status, EscrowStatus::Released);
+        assert!(t
+            .escrow_client
+            .get_milestones(&escrow_id)
+            .iter()
+            .all(|m| m.released));
+    }
 
-        // Verify funds sent to seller
-        let token = token::Client::new(&t.env, &t.token_addr);
-        assert_eq!(token.balance(&t.seller), 5000);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #17)")]
+    fn test_milestone_shares_must_total_full_amount() {
+        let t = setup();
+        t.escrow_client.create_milestone_escrow(
+            &EscrowConfig {
+                buyer: t.buyer.clone(),
+                seller: t.seller.clone(),
+                lender: t.lender.clone(),
+                collateral_id: 1u64,
+                amount: 5000i128,
+                asset: t.token_addr.clone(),
+                required_confirmation: 0u32,
+                expiry_ts: t.env.ledger().timestamp() + 3600,
+                destination_asset: t.token_addr.clone(),
+                min_destination_amount: 5000i128,
+                required_confirmations: 0u32,
+                oracle_set: Vec::new(&t.env),
+            },
+            &Vec::from_array(
+                &t.env,
+                [MilestoneConfig {
+                    event_type: 1,
+                    required_confirmations: 0,
+                    share_bps: 9000,
+                }],
+            ),
+        );
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #12)")]
-    fn test_multi_oracle_consensus_insufficient_confirmations() {
-        let t = setup_multi_oracle();
+    #[should_panic(expected = "HostError: Error(Contract, #17)")]
+    fn test_milestone_event_types_must_differ() {
+        let t = setup();
+        let milestone = MilestoneConfig {
+            event_type: 2,
+            required_confirmations: 0,
+            share_bps: 5000,
+        };
+        t.escrow_client.create_milestone_escrow(
+            &EscrowConfig {
+                buyer: t.buyer.clone(),
+                seller: t.seller.clone(),
+                lender: t.lender.clone(),
+                collateral_id: 1u64,
+                amount: 5000i128,
+                asset: t.token_addr.clone(),
+                required_confirmation: 0u32,
+                expiry_ts: t.env.ledger().timestamp() + 3600,
+                destination_asset: t.token_addr.clone(),
+                min_destination_amount: 5000i128,
+                required_confirmations: 0u32,
+                oracle_set: Vec::new(&t.env),
+            },
+            &Vec::from_array(&t.env, [milestone.clone(), milestone]),
+        );
+    }
 
-        let oracle1 = Address::generate(&t.env);
-        let oracle2 = Address::generate(&t.env);
-        let oracle3 = Address::generate(&t.env);
-        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #18)")]
+    fn test_milestone_out_of_order() {
+        let t = setup();
+        let escrow_id = create_milestone_escrow(&t);
+        confirm_events(&t, escrow_id, &[1, 2]);
 
-        // Create escrow requiring 3 out of 3 oracles
-        let escrow_id = create_multi_oracle_escrow(&t, 3u32, oracle_set.clone());
+        t.escrow_client.release_milestone(&escrow_id, &1);
+    }
 
-        // Only provide 2 confirmations
-        set_multi_oracle_confirmations(
-            &t,
-            escrow_id,
-            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
-            2, // Delivery event
-            true,
-        );
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #6)")]
+    fn test_milestone_requires_its_own_event() {
+        let t = setup();
+        let escrow_id = create_milestone_escrow(&t);
+        confirm_events(&t, escrow_id, &[1]);
+        t.escrow_client.release_milestone(&escrow_id, &0);
 
-        // Release should fail - consensus not met
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        // Shipment confirmed, Delivery not yet
+        t.escrow_client.release_milestone(&escrow_id, &1);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #10)")]
-    fn test_multi_oracle_create_escrow_empty_oracle_set() {
-        let t = setup_multi_oracle();
+    #[should_panic(expected = "HostError: Error(Contract, #20)")]
+    fn test_full_release_rejected_for_milestone_escrow() {
+        let t = setup();
+        let escrow_id = create_milestone_escrow(&t);
+        confirm_events(&t, escrow_id, &[1, 2, 3]);
 
-        // Try to create escrow with required_confirmations > 0 but empty oracle_set
-        t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: t.env.ledger().timestamp() + 3600,
-            destination_asset: t.token_addr.clone(),
-            min_destination_amount: 5000i128,
-            required_confirmations: 2u32, // > 0 requires oracle_set
-            oracle_set: Vec::new(&t.env), // Empty - should fail
-        });
+        t.escrow_client.release_funds_on_confirmation(&escrow_id);
     }
 
     #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #11)")]
-    fn test_multi_oracle_create_escrow_threshold_exceeds_set_size() {
-        let t = setup_multi_oracle();
+    fn test_expiry_mid_way_refunds_unreleased_milestones() {
+        let t = setup();
+        let escrow_id = create_milestone_escrow(&t);
+        let token = token::Client::new(&t.env, &t.token_addr);
 
-        let oracle1 = Address::generate(&t.env);
-        let oracle2 = Address::generate(&t.env);
-        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
+        confirm_events(&t, escrow_id, &[1]);
+        t.escrow_client.release_milestone(&escrow_id, &0);
 
-        // Try to create escrow with threshold 3 but only 2 oracles in set
-        t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: t.env.ledger().timestamp() + 3600,
-            destination_asset: t.token_addr.clone(),
-            min_destination_amount: 5000i128,
-            required_confirmations: 3u32, // > oracle_set.len()
-            oracle_set,
+        t.env.ledger().with_mut(|li| {
+            li.timestamp += 3601;
         });
-    }
+        t.escrow_client.refund_escrow(&escrow_id);
 
-    #[test]
-    #[should_panic(expected = "HostError: Error(Contract, #10)")]
-    fn test_multi_oracle_create_escrow_duplicate_oracles() {
-        let t = setup_multi_oracle();
+        assert_eq!(token.balance(&t.seller), 1500);
+        assert_eq!(token.balance(&t.lender), 1_000_000 - 1500);
+        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Refunded);
+    }
 
-        let oracle1 = Address::generate(&t.env);
-        // Include the same oracle twice
-        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle1.clone()]);
+    // -- Collateral eligibility ----------------------------------------------
 
-        t.escrow_client.create_escrow(&EscrowConfig {
+    fn escrow_config(t: &TestEnv, collateral_id: u64, amount: i128) -> EscrowConfig {
+        EscrowConfig {
             buyer: t.buyer.clone(),
             seller: t.seller.clone(),
             lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
+            collateral_id,
+            amount,
             asset: t.token_addr.clone(),
             required_confirmation: 2u32,
             expiry_ts: t.env.ledger().timestamp() + 3600,
             destination_asset: t.token_addr.clone(),
-            min_destination_amount: 5000i128,
-            required_confirmations: 2u32,
-            oracle_set, // Contains duplicate
-        });
+            min_destination_amount: amount,
+            required_confirmations: 0u32,
+            oracle_set: Vec::new(&t.env),
+        }
+    }
+
+    fn register_collateral(t: &TestEnv, collateral: &CollateralRecord) {
+        MockCollateralRegistryClient::new(&t.env, &t.coll_reg_addr).set_collateral(collateral);
     }
 
     #[test]
-    fn test_multi_oracle_consensus_ignores_unauthorized_oracles() {
-        let t = setup_multi_oracle();
+    #[should_panic(expected = "HostError: Error(Contract, #21)")]
+    fn test_create_escrow_unknown_collateral() {
+        let t = setup();
+        t.escrow_client.create_escrow(&escrow_config(&t, 99, 5000));
+    }
 
-        let oracle1 = Address::generate(&t.env);
-        let oracle2 = Address::generate(&t.env);
-        let unauthorized_oracle = Address::generate(&t.env);
-        let oracle_set = Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #22)")]
+    fn test_create_escrow_collateral_not_owned_by_party() {
+        let t = setup();
+        let stranger = Address::generate(&t.env);
+        register_collateral(&t, &test_collateral(&t.env, 2, &stranger, 100_000, 0));
 
-        // Create escrow requiring 2 oracles from the authorized set
-        let escrow_id = create_multi_oracle_escrow(&t, 2u32, oracle_set.clone());
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 5000));
+    }
 
-        // Set confirmations: 1 authorized + 1 unauthorized
-        set_multi_oracle_confirmations(
-            &t,
-            escrow_id,
-            Vec::from_array(&t.env, [oracle1.clone(), unauthorized_oracle]),
-            2, // Delivery event
-            true,
-        );
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #23)")]
+    fn test_create_escrow_collateral_expires_first() {
+        let t = setup();
+        let mut collateral = test_collateral(&t.env, 2, &t.seller, 100_000, 0);
+        collateral.expiry_ts = t.env.ledger().timestamp() + 1800;
+        register_collateral(&t, &collateral);
 
-        // Release should fail - only 1 authorized confirmation
-        assert_eq!(
-            t.escrow_client
-                .try_release_funds_on_confirmation(&escrow_id),
-            Err(Ok(ContractError::ConsensusNotMet))
-        );
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 5000));
+    }
 
-        // Add the second authorized confirmation
-        set_multi_oracle_confirmations(
-            &t,
-            escrow_id,
-            Vec::from_array(&t.env, [oracle1.clone(), oracle2.clone()]),
-            2, // Delivery event
-            true,
-        );
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #25)")]
+    fn test_create_escrow_collateral_already_locked() {
+        let t = setup();
+        register_collateral(&t, &test_collateral(&t.env, 2, &t.buyer, 100_000, 0));
 
-        // Now release should succeed
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        // 5000 at 80% LTV encumbers 6250, leaving 93_750 of value
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 5000));
+        // 76_000 needs a 95_000 lien: within LTV but over what is left
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 76_000));
+    }
 
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Released);
+    #[test]
+    fn test_escrows_share_collateral_liens() {
+        let t = setup();
+        register_collateral(&t, &test_collateral(&t.env, 2, &t.buyer, 100_000, 0));
+        let registry = MockCollateralRegistryClient::new(&t.env, &t.coll_reg_addr);
+
+        let first = t.escrow_client.create_escrow(&escrow_config(&t, 2, 40_000));
+        let second = t.escrow_client.create_escrow(&escrow_config(&t, 2, 20_000));
+        assert_eq!(registry.get_liens(&2).get(first), Some(50_000));
+        assert_eq!(registry.get_liens(&2).get(second), Some(25_000));
+        assert_eq!(registry.get_available_value(&2), 25_000);
+
+        // Refunding one escrow releases only its own lien
+        t.env.ledger().with_mut(|li| li.timestamp += 7200);
+        t.escrow_client.refund_escrow(&first);
+        assert_eq!(registry.get_liens(&2).get(first), None);
+        assert_eq!(registry.get_liens(&2).get(second), Some(25_000));
+        assert_eq!(registry.get_available_value(&2), 75_000);
     }
 
     #[test]
-    fn test_multi_oracle_backward_compatibility_single_oracle() {
-        let t = setup_multi_oracle();
+    fn test_create_escrow_enforces_max_ltv_per_type() {
+        let t = setup();
+        register_collateral(&t, &test_collateral(&t.env, 2, &t.buyer, 10_000, 0));
+        register_collateral(&t, &test_collateral(&t.env, 3, &t.buyer, 10_000, 1));
+
+        // Default 80% LTV: 8000 against 10_000 is fine, 8001 is not
+        assert_eq!(t.escrow_client.get_max_ltv(&0), 8000);
+        assert!(t
+            .escrow_client
+            .try_create_escrow(&escrow_config(&t, 2, 8001))
+            .is_err());
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 8000));
+
+        // Governance tightens type 1 to 50%
+        let governance = Address::generate(&t.env);
+        t.escrow_client.set_governance(&governance);
+        t.escrow_client.set_max_ltv(&1, &5000);
+
+        assert_eq!(t.escrow_client.get_max_ltv(&1), 5000);
+        assert!(t
+            .escrow_client
+            .try_create_escrow(&escrow_config(&t, 3, 5001))
+            .is_err());
+        t.escrow_client.create_escrow(&escrow_config(&t, 3, 5000));
+    }
 
-        // Create escrow with required_confirmations = 0 (single oracle mode)
-        let escrow_id = t.escrow_client.create_escrow(&EscrowConfig {
-            buyer: t.buyer.clone(),
-            seller: t.seller.clone(),
-            lender: t.lender.clone(),
-            collateral_id: 1u64,
-            amount: 5000i128,
-            asset: t.token_addr.clone(),
-            required_confirmation: 2u32,
-            expiry_ts: t.env.ledger().timestamp() + 3600,
-            destination_asset: t.token_addr.clone(),
-            min_destination_amount: 5000i128,
-            required_confirmations: 0u32, // Single oracle mode
-            oracle_set: Vec::new(&t.env), // Ignored in single oracle mode
-        });
+    #[test]
+    fn test_create_escrow_ltv_uses_current_valuation() {
+        let t = setup();
+        register_collateral(&t, &test_collateral(&t.env, 2, &t.buyer, 10_000, 0));
+        let registry = MockCollateralRegistryClient::new(&t.env, &t.coll_reg_addr);
+
+        // The price feed now values the collateral at half its realized value
+        registry.set_valuation(&2, &5_000);
+        assert!(t
+            .escrow_client
+            .try_create_escrow(&escrow_config(&t, 2, 4001))
+            .is_err());
+        t.escrow_client.create_escrow(&escrow_config(&t, 2, 4000));
+    }
 
-        // Set single oracle confirmation using the old method
-        set_oracle_confirmation(&t, escrow_id, 2, true);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #26)")]
+    fn test_set_max_ltv_rejects_over_100_percent() {
+        let t = setup();
+        t.escrow_client.set_governance(&Address::generate(&t.env));
 
-        // Release should succeed using backward compatibility
-        t.escrow_client.release_funds_on_confirmation(&escrow_id);
+        t.escrow_client.set_max_ltv(&0, &10001);
+    }
 
-        let escrow = t.escrow_client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Released);
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #28)")]
+    fn test_migrate_requires_newer_version() {
+        let t = setup();
+        t.escrow_client.set_governance(&Address::generate(&t.env));
+        assert_eq!(t.escrow_client.version(), 1);
+
+        t.escrow_client.migrate(&2);
     }
 }
@@ -3,14 +3,33 @@
 //! This contract enables stakeholders to propose and vote on protocol parameter changes.
 //! Features include:
 //! - Proposal creation with target contract and parameter specifications
-//! - Token-weighted voting based on locked balances
-//! - Quorum and majority requirements
-//! - Timelock grace period between proposal passing and execution
+//! - Multi-action proposals invoking allowlisted contract functions
+//! - Token-weighted voting based on locked balances, snapshotted at the ledger
+//!   before proposal creation, with delegation
+//! - Linear, quadratic or conviction-weighted tallies, with an optional
+//!   minimum reputation to vote
+//! - Quorum and majority requirements, with proposal thresholds based on
+//!   voting power
+//! - Explicit lifecycle: Pending -> Active -> Succeeded/Defeated -> Queued ->
+//!   Executed/Vetoed/Expired
+//! - Timelock grace period between queueing and execution, during which a
+//!   guardian multisig can veto
+//! - Guardian-created emergency proposals with a short vote, a higher quorum
+//!   and no timelock
 //! - Cross-contract calls to update risk parameters
+//!
+//! Action proposals call their targets as this contract, so a target's
+//! admin-gated functions (e.g. `ProtocolTreasury::set_fee_bps`) are reachable
+//! once governance is installed as that contract's admin. Protocol contracts
+//! accept `upgrade` and `migrate` only from governance; actions targeting
+//! governance itself with those functions upgrade this contract.
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, IntoVal,
+    Symbol, TryFromVal, Val, Vec,
+};
 
 // ============================================================================
 // Error Types
@@ -45,6 +64,31 @@ pub enum ContractError {
 
     // Math errors
     MathOverflow = 15,
+
+    // Action errors
+    FunctionNotAllowed = 16,
+    InvalidActions = 17,
+
+    // Deposit errors
+    InsufficientBalance = 18,
+    TokensLocked = 19,
+
+    // Sybil resistance errors
+    InsufficientReputation = 20,
+
+    // Lifecycle errors
+    ProposalNotQueued = 21,
+    ProposalAlreadyQueued = 22,
+    ProposalExpired = 23,
+
+    // Guardian errors
+    NotGuardian = 24,
+    AlreadyVetoed = 25,
+    VetoWindowClosed = 26,
+
+    // Upgrade errors
+    InvalidVersion = 27,
+    TimelockRequired = 28,
 }
 
 impl From<ContractError> for soroban_sdk::Error {
@@ -71,7 +115,47 @@ impl TryFrom<soroban_sdk::Error> for ContractError {
 // Data Structures
 // ============================================================================
 
+/// Contract invocation carried by a proposal
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalAction {
+    pub target: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+/// Lifecycle state of a proposal
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    /// Created, voting has not opened yet
+    Pending = 0,
+    /// Voting is open
+    Active = 1,
+    /// Passed quorum and majority, waiting to be queued
+    Succeeded = 2,
+    /// Missed quorum or majority
+    Defeated = 3,
+    /// In the timelock (or executable once it elapsed)
+    Queued = 4,
+    Executed = 5,
+    /// Vetoed by the guardians during the timelock
+    Vetoed = 6,
+    /// Passed but not executed within the grace period
+    Expired = 7,
+    /// Cancelled by the admin
+    Cancelled = 8,
+}
+
 /// Governance proposal
+///
+/// A proposal either changes a single risk parameter (`target_contract`,
+/// `parameter_symbol`, `new_value`) or carries a list of `actions`. Action
+/// proposals record this contract as target, `actions` as parameter symbol
+/// and the number of actions as value.
+///
+/// `status` holds the last recorded transition; states that follow from the
+/// clock and the tally are derived by `get_proposal_state`.
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Proposal {
@@ -80,14 +164,58 @@ pub struct Proposal {
     pub target_contract: Address,
     pub parameter_symbol: Symbol,
     pub new_value: i128,
+    pub actions: Vec<ProposalAction>,
+    /// Ledger at whose end voting power is measured: the one before creation,
+    /// so balances can no longer change once voting opens
+    pub snapshot_ledger: u32,
+    pub voting_mode: VotingMode,
+    /// Fast-tracked: emergency quorum, no timelock
+    pub emergency: bool,
+    pub voting_start_ts: u64,
     pub voting_end_ts: u64,
+    /// Effective (mode-weighted) votes
     pub votes_for: i128,
     pub votes_against: i128,
-    pub executed: bool,
+    /// Voting power committed, before mode weighting
+    pub raw_for: i128,
+    pub raw_against: i128,
+    pub status: ProposalStatus,
+    /// Guardian veto approvals
+    pub vetoes: u32,
+    /// End of the timelock, set when queued
     pub execution_ts: u64,
     pub created_at: u64,
 }
 
+/// How committed voting power translates into vote weight
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VotingMode {
+    /// Weight equals the power committed
+    Linear = 0,
+    /// Power is spent as credits; weight is the square root of the credits
+    Quadratic = 1,
+    /// Weight is multiplied by a conviction level that locks the voter's
+    /// deposit for longer
+    Conviction = 2,
+}
+
+/// Voting power of an account (or the total supply) from a ledger onwards
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub votes: i128,
+}
+
+/// Storage key of a checkpoint history
+#[contracttype]
+#[derive(Clone)]
+enum CheckpointKey {
+    Votes(Address),
+    Supply,
+}
+
 /// Vote record
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -95,7 +223,11 @@ pub struct Vote {
     pub voter: Address,
     pub proposal_id: u64,
     pub support: bool,
+    /// Voting power committed (credits in quadratic mode)
     pub voting_power: i128,
+    /// Weight added to the tally
+    pub effective_power: i128,
+    pub conviction: u32,
     pub timestamp: u64,
 }
 
@@ -103,21 +235,31 @@ pub struct Vote {
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct GovernanceConfig {
-    pub voting_period: u64,     // Duration of voting in seconds
-    pub timelock_period: u64,   // Grace period before execution
-    pub quorum_bps: u32,        // Quorum in basis points (e.g., 1000 = 10%)
-    pub majority_bps: u32,      // Majority threshold in basis points (e.g., 5000 = 50%)
-    pub min_voting_power: i128, // Minimum tokens to create proposal
+    pub voting_delay: u64,            // Delay between creation and voting opening
+    pub voting_period: u64,           // Duration of voting in seconds
+    pub timelock_period: u64,         // Grace period before execution
+    pub grace_period: u64,            // Window to execute a passed proposal
+    pub quorum_bps: u32,              // Quorum in basis points (e.g., 1000 = 10%)
+    pub majority_bps: u32,            // Majority threshold in basis points (e.g., 5000 = 50%)
+    pub min_voting_power: i128,       // Minimum tokens to create proposal
+    pub proposal_threshold_bps: u32,  // Share of locked supply to create proposal
+    pub emergency_voting_period: u64, // Duration of voting on emergency proposals
+    pub emergency_quorum_bps: u32,    // Supporting power emergency proposals need
 }
 
 impl GovernanceConfig {
     pub fn default() -> Self {
         Self {
-            voting_period: 604800,  // 7 days
-            timelock_period: 86400, // 24 hours
-            quorum_bps: 1000,       // 10%
-            majority_bps: 5000,     // 50%
-            min_voting_power: 1000, // 1000 tokens minimum
+            voting_delay: 0,                // Voting opens at creation
+            voting_period: 604800,          // 7 days
+            timelock_period: 86400,         // 24 hours
+            grace_period: 1209600,          // 14 days
+            quorum_bps: 1000,               // 10%
+            majority_bps: 5000,             // 50%
+            min_voting_power: 1000,         // 1000 tokens minimum
+            proposal_threshold_bps: 100,    // 1%
+            emergency_voting_period: 86400, // 24 hours
+            emergency_quorum_bps: 4000,     // 40%
         }
     }
 }
@@ -131,6 +273,27 @@ const EVT_PROPOSAL: Symbol = symbol_short!("proposal");
 const EVT_VOTE: Symbol = symbol_short!("vote");
 const EVT_EXECUTED: Symbol = symbol_short!("executed");
 const EVT_CANCELLED: Symbol = symbol_short!("cancelled");
+const EVT_ALLOWED: Symbol = symbol_short!("fn_allow");
+const EVT_DEPOSIT: Symbol = symbol_short!("deposit");
+const EVT_WITHDRAW: Symbol = symbol_short!("withdraw");
+const EVT_DELEGATE: Symbol = symbol_short!("delegate");
+const EVT_MODE: Symbol = symbol_short!("vote_mode");
+const EVT_QUEUED: Symbol = symbol_short!("queued");
+const EVT_VETO: Symbol = symbol_short!("veto");
+const EVT_VETOED: Symbol = symbol_short!("vetoed");
+const EVT_GUARDIANS: Symbol = symbol_short!("guardians");
+const EVT_EMERGENCY: Symbol = symbol_short!("emergency");
+const EVT_UPGRADED: Symbol = symbol_short!("upgraded");
+const EVT_MIGRATED: Symbol = symbol_short!("migrated");
+
+/// Layout of proposal and deposit storage this code reads; a `migrate` action records it
+const CONTRACT_VERSION: u32 = 1;
+
+/// Highest conviction level; each level doubles the lock of the previous one
+const MAX_CONVICTION: u32 = 6;
+
+/// Maximum number of actions in a single proposal
+const MAX_ACTIONS: u32 = 10;
 
 // ============================================================================
 // Contract Definition
@@ -181,11 +344,6 @@ impl Governance {
             .instance()
             .set(&symbol_short!("prop_cnt"), &0u64);
 
-        // Initialize total voting power (simplified - in production, query from token contract)
-        env.storage()
-            .instance()
-            .set(&symbol_short!("total_pwr"), &1000000i128);
-
         env.events()
             .publish((EVT_INIT,), (admin.clone(), token_contract));
 
@@ -228,66 +386,144 @@ impl Governance {
     ) -> Result<u64, ContractError> {
         proposer.require_auth();
 
-        let config = Self::get_config(env.clone());
-
-        // Check proposer has minimum voting power
-        let voting_power = Self::get_voting_power(&env, &proposer);
-        if voting_power < config.min_voting_power {
+        // Check proposer holds the proposal threshold
+        if Self::get_voting_power(&env, &proposer) < Self::get_proposal_threshold(env.clone())? {
             return Err(ContractError::InsufficientVotingPower);
         }
 
         // Validate parameter and value
         Self::validate_parameter(&parameter_symbol, new_value)?;
 
-        // Get and increment proposal counter
-        let proposal_id: u64 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("prop_cnt"))
-            .unwrap_or(0);
+        let proposal_id = Self::store_proposal(
+            &env,
+            &proposer,
+            target_contract,
+            parameter_symbol.clone(),
+            new_value,
+            Vec::new(&env),
+            false,
+        )?;
 
-        let next_id = proposal_id
-            .checked_add(1)
-            .ok_or(ContractError::MathOverflow)?;
-        env.storage()
-            .instance()
-            .set(&symbol_short!("prop_cnt"), &next_id);
+        env.events().publish(
+            (EVT_PROPOSAL,),
+            (proposal_id, proposer, parameter_symbol, new_value),
+        );
 
-        let current_ts = env.ledger().timestamp();
-        let voting_end_ts = current_ts
-            .checked_add(config.voting_period)
-            .ok_or(ContractError::MathOverflow)?;
+        Ok(proposal_id)
+    }
 
-        let execution_ts = voting_end_ts
-            .checked_add(config.timelock_period)
-            .ok_or(ContractError::MathOverflow)?;
+    /// Create a proposal executing a list of contract invocations
+    ///
+    /// Every action must target an allowlisted function. The actions run in
+    /// order after the timelock, and all of them revert if any one fails.
+    ///
+    /// # Arguments
+    /// * `proposer` - Address creating the proposal
+    /// * `actions` - Invocations to execute (1 to 10)
+    pub fn create_action_proposal(
+        env: Env,
+        proposer: Address,
+        actions: Vec<ProposalAction>,
+    ) -> Result<u64, ContractError> {
+        proposer.require_auth();
 
-        let proposal = Proposal {
-            id: proposal_id,
-            proposer: proposer.clone(),
-            target_contract,
-            parameter_symbol: parameter_symbol.clone(),
-            new_value,
-            voting_end_ts,
-            votes_for: 0,
-            votes_against: 0,
-            executed: false,
-            execution_ts,
-            created_at: current_ts,
-        };
+        if Self::get_voting_power(&env, &proposer) < Self::get_proposal_threshold(env.clone())? {
+            return Err(ContractError::InsufficientVotingPower);
+        }
 
-        env.storage()
-            .persistent()
-            .set(&(symbol_short!("prop"), proposal_id), &proposal);
+        Self::validate_actions(&env, &actions)?;
+
+        let action_count = actions.len();
+        let proposal_id = Self::store_proposal(
+            &env,
+            &proposer,
+            env.current_contract_address(),
+            symbol_short!("actions"),
+            action_count as i128,
+            actions,
+            false,
+        )?;
 
         env.events().publish(
             (EVT_PROPOSAL,),
-            (proposal_id, proposer, parameter_symbol, new_value),
+            (
+                proposal_id,
+                proposer,
+                symbol_short!("actions"),
+                action_count as i128,
+            ),
         );
 
         Ok(proposal_id)
     }
 
+    /// Create an emergency proposal (guardians only)
+    ///
+    /// Emergency proposals vote for `emergency_voting_period` and need
+    /// `emergency_quorum_bps` of the locked supply in supporting power. They
+    /// succeed as soon as that support is reached and skip the timelock, so
+    /// they cannot carry `upgrade` or `migrate` actions.
+    ///
+    /// # Arguments
+    /// * `guardian` - Guardian creating the proposal
+    /// * `actions` - Invocations to execute (1 to 10)
+    pub fn create_emergency_proposal(
+        env: Env,
+        guardian: Address,
+        actions: Vec<ProposalAction>,
+    ) -> Result<u64, ContractError> {
+        guardian.require_auth();
+
+        if !Self::is_guardian(env.clone(), guardian.clone()) {
+            return Err(ContractError::NotGuardian);
+        }
+
+        Self::validate_actions(&env, &actions)?;
+
+        // Code and storage changes always wait out the timelock and veto window
+        for action in actions.iter() {
+            if action.function == symbol_short!("upgrade")
+                || action.function == symbol_short!("migrate")
+            {
+                return Err(ContractError::TimelockRequired);
+            }
+        }
+
+        let action_count = actions.len();
+        let proposal_id = Self::store_proposal(
+            &env,
+            &guardian,
+            env.current_contract_address(),
+            symbol_short!("actions"),
+            action_count as i128,
+            actions,
+            true,
+        )?;
+
+        env.events()
+            .publish((EVT_EMERGENCY,), (proposal_id, guardian, action_count));
+
+        Ok(proposal_id)
+    }
+
+    /// Get the voting power needed to create a proposal
+    ///
+    /// The larger of `min_voting_power` and `proposal_threshold_bps` of the
+    /// currently locked supply.
+    pub fn get_proposal_threshold(env: Env) -> Result<i128, ContractError> {
+        let config = Self::get_config(env.clone());
+        let supply = Self::checkpoints(&env, &CheckpointKey::Supply)
+            .last()
+            .map(|checkpoint| checkpoint.votes)
+            .unwrap_or(0);
+        let relative = supply
+            .checked_mul(config.proposal_threshold_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+
+        Ok(config.min_voting_power.max(relative))
+    }
+
     /// Get a proposal by ID
     pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, ContractError> {
         env.storage()
@@ -296,6 +532,12 @@ impl Governance {
             .ok_or(ContractError::ProposalNotFound)
     }
 
+    /// Get the current lifecycle state of a proposal
+    pub fn get_proposal_state(env: Env, proposal_id: u64) -> Result<ProposalStatus, ContractError> {
+        let proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        Self::proposal_state(&env, &proposal)
+    }
+
     /// Get all proposal IDs (for querying)
     pub fn get_proposal_count(env: Env) -> u64 {
         env.storage()
@@ -310,6 +552,10 @@ impl Governance {
 
     /// Cast a vote on a proposal
     ///
+    /// The vote is weighted by the voter's power at the proposal's snapshot
+    /// ledger, so tokens locked or delegated later do not count. All of that
+    /// power is committed, at conviction 1 in conviction mode.
+    ///
     /// # Arguments
     /// * `proposal_id` - ID of the proposal to vote on
     /// * `voter` - Address of the voter
@@ -319,6 +565,37 @@ impl Governance {
         proposal_id: u64,
         voter: Address,
         support: bool,
+    ) -> Result<(), ContractError> {
+        let proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        let voting_power =
+            Self::get_past_votes(env.clone(), voter.clone(), proposal.snapshot_ledger);
+
+        Self::cast_weighted_vote(env, proposal_id, voter, support, voting_power, 1)
+    }
+
+    /// Cast a vote committing part of the voter's power
+    ///
+    /// The committed power is weighted by the proposal's voting mode:
+    /// - Linear: weight = power
+    /// - Quadratic: power is spent as credits, weight = sqrt(credits)
+    /// - Conviction: weight = power x conviction (0.1x at conviction 0), and
+    ///   `power` of the voter's own deposit stays locked for
+    ///   `timelock_period x 2^(conviction - 1)` after voting ends, so
+    ///   delegated power can only be committed at conviction 0
+    ///
+    /// # Arguments
+    /// * `proposal_id` - ID of the proposal to vote on
+    /// * `voter` - Address of the voter
+    /// * `support` - true for "for", false for "against"
+    /// * `power` - Voting power to commit, up to the voter's power at the snapshot
+    /// * `conviction` - Conviction level 0-6 (conviction mode only)
+    pub fn cast_weighted_vote(
+        env: Env,
+        proposal_id: u64,
+        voter: Address,
+        support: bool,
+        power: i128,
+        conviction: u32,
     ) -> Result<(), ContractError> {
         voter.require_auth();
 
@@ -327,8 +604,11 @@ impl Governance {
 
         // Check voting is still active
         let current_ts = env.ledger().timestamp();
-        if current_ts >= proposal.voting_end_ts {
-            return Err(ContractError::VotingEnded);
+        if Self::proposal_state(&env, &proposal)? != ProposalStatus::Active {
+            if current_ts >= proposal.voting_end_ts {
+                return Err(ContractError::VotingEnded);
+            }
+            return Err(ContractError::ProposalNotActive);
         }
 
         // Check if already voted
@@ -337,18 +617,40 @@ impl Governance {
             return Err(ContractError::AlreadyVoted);
         }
 
-        // Get voting power (weighted by locked token balance)
-        let voting_power = Self::get_voting_power(&env, &voter);
-        if voting_power == 0 {
+        Self::check_reputation(&env, &voter)?;
+
+        // Get voting power (weighted by locked token balance at the snapshot)
+        let available = Self::get_past_votes(env.clone(), voter.clone(), proposal.snapshot_ledger);
+        if power <= 0 || power > available {
             return Err(ContractError::InsufficientVotingPower);
         }
+        if conviction > MAX_CONVICTION {
+            return Err(ContractError::InvalidValue);
+        }
+
+        let effective_power = match proposal.voting_mode {
+            VotingMode::Linear => power,
+            VotingMode::Quadratic => Self::sqrt(power),
+            VotingMode::Conviction => {
+                Self::lock_for_conviction(&env, &voter, &proposal, power, conviction)?;
+                if conviction == 0 {
+                    power / 10
+                } else {
+                    power
+                        .checked_mul(conviction as i128)
+                        .ok_or(ContractError::MathOverflow)?
+                }
+            }
+        };
 
         // Record vote
         let vote = Vote {
             voter: voter.clone(),
             proposal_id,
             support,
-            voting_power,
+            voting_power: power,
+            effective_power,
+            conviction,
             timestamp: current_ts,
         };
 
@@ -358,12 +660,20 @@ impl Governance {
         if support {
             proposal.votes_for = proposal
                 .votes_for
-                .checked_add(voting_power)
+                .checked_add(effective_power)
+                .ok_or(ContractError::MathOverflow)?;
+            proposal.raw_for = proposal
+                .raw_for
+                .checked_add(power)
                 .ok_or(ContractError::MathOverflow)?;
         } else {
             proposal.votes_against = proposal
                 .votes_against
-                .checked_add(voting_power)
+                .checked_add(effective_power)
+                .ok_or(ContractError::MathOverflow)?;
+            proposal.raw_against = proposal
+                .raw_against
+                .checked_add(power)
                 .ok_or(ContractError::MathOverflow)?;
         }
 
@@ -371,8 +681,10 @@ impl Governance {
             .persistent()
             .set(&(symbol_short!("prop"), proposal_id), &proposal);
 
-        env.events()
-            .publish((EVT_VOTE,), (proposal_id, voter, support, voting_power));
+        env.events().publish(
+            (EVT_VOTE,),
+            (proposal_id, voter, support, power, effective_power),
+        );
 
         Ok(())
     }
@@ -387,73 +699,81 @@ impl Governance {
     // Proposal Execution
     // ========================================================================
 
-    /// Execute a passed proposal after timelock period
+    /// Queue a succeeded proposal, starting its timelock
+    ///
+    /// Emergency proposals have no timelock and are executable at once.
     ///
     /// # Arguments
-    /// * `proposal_id` - ID of the proposal to execute
-    pub fn execute_proposal(env: Env, proposal_id: u64) -> Result<(), ContractError> {
+    /// * `proposal_id` - ID of the proposal to queue
+    pub fn queue_proposal(env: Env, proposal_id: u64) -> Result<u64, ContractError> {
         let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
 
-        // Check not already executed
-        if proposal.executed {
-            return Err(ContractError::ProposalAlreadyExecuted);
-        }
-
-        let current_ts = env.ledger().timestamp();
-
-        // Check voting has ended
-        if current_ts < proposal.voting_end_ts {
-            return Err(ContractError::ProposalNotActive);
-        }
-
-        // Check timelock has expired
-        if current_ts < proposal.execution_ts {
-            return Err(ContractError::TimelockNotExpired);
+        let state = Self::proposal_state(&env, &proposal)?;
+        if state != ProposalStatus::Succeeded {
+            return Err(Self::state_error(&env, &proposal, state));
         }
 
         let config = Self::get_config(env.clone());
-
-        // Verify quorum
-        let total_votes = proposal
-            .votes_for
-            .checked_add(proposal.votes_against)
+        let timelock = if proposal.emergency {
+            0
+        } else {
+            config.timelock_period
+        };
+        proposal.execution_ts = env
+            .ledger()
+            .timestamp()
+            .checked_add(timelock)
             .ok_or(ContractError::MathOverflow)?;
+        proposal.status = ProposalStatus::Queued;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("prop"), proposal_id), &proposal);
 
-        let total_voting_power: i128 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("total_pwr"))
-            .unwrap_or(1000000);
+        env.events()
+            .publish((EVT_QUEUED,), (proposal_id, proposal.execution_ts));
 
-        let quorum_required = total_voting_power
-            .checked_mul(config.quorum_bps as i128)
-            .ok_or(ContractError::MathOverflow)?
-            / 10000;
+        Ok(proposal.execution_ts)
+    }
 
-        if total_votes < quorum_required {
-            return Err(ContractError::QuorumNotReached);
-        }
+    /// Execute a queued proposal after its timelock
+    ///
+    /// # Arguments
+    /// * `proposal_id` - ID of the proposal to execute
+    pub fn execute_proposal(env: Env, proposal_id: u64) -> Result<(), ContractError> {
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
 
-        // Verify majority
-        let majority_required = total_votes
-            .checked_mul(config.majority_bps as i128)
-            .ok_or(ContractError::MathOverflow)?
-            / 10000;
+        let state = Self::proposal_state(&env, &proposal)?;
+        if state != ProposalStatus::Queued {
+            return Err(Self::state_error(&env, &proposal, state));
+        }
 
-        if proposal.votes_for < majority_required {
-            return Err(ContractError::MajorityNotReached);
+        // Check timelock has expired
+        if env.ledger().timestamp() < proposal.execution_ts {
+            return Err(ContractError::TimelockNotExpired);
         }
 
-        // Execute cross-contract call to update risk parameters
-        Self::execute_parameter_update(
-            &env,
-            &proposal.target_contract,
-            &proposal.parameter_symbol,
-            proposal.new_value,
-        )?;
+        if proposal.actions.is_empty() {
+            // Execute cross-contract call to update risk parameters
+            Self::execute_parameter_update(
+                &env,
+                &proposal.target_contract,
+                &proposal.parameter_symbol,
+                proposal.new_value,
+            )?;
+        } else {
+            // A failing action aborts the whole invocation, reverting the others
+            for action in proposal.actions.iter() {
+                if action.target == env.current_contract_address() {
+                    // A contract cannot call itself, so run its own upgrades inline
+                    Self::execute_self_action(&env, &action)?;
+                } else {
+                    env.invoke_contract::<Val>(&action.target, &action.function, action.args);
+                }
+            }
+        }
 
         // Mark as executed
-        proposal.executed = true;
+        proposal.status = ProposalStatus::Executed;
         env.storage()
             .persistent()
             .set(&(symbol_short!("prop"), proposal_id), &proposal);
@@ -471,121 +791,844 @@ impl Governance {
     }
 
     /// Check if a proposal has passed (quorum + majority)
+    ///
+    /// Quorum counts the raw power committed against the total supply at the
+    /// snapshot; majority compares the effective votes.
     pub fn has_proposal_passed(env: Env, proposal_id: u64) -> Result<bool, ContractError> {
         let proposal = Self::get_proposal(env.clone(), proposal_id)?;
+        Self::outcome_passed(&env, &proposal)
+    }
 
-        let config = Self::get_config(env.clone());
+    // ========================================================================
+    // Voting Power
+    // ========================================================================
 
-        let total_votes = proposal
-            .votes_for
-            .checked_add(proposal.votes_against)
+    /// Lock governance tokens to gain voting power
+    ///
+    /// The power goes to the account's delegate (the account itself unless
+    /// it delegated).
+    ///
+    /// # Arguments
+    /// * `account` - Address depositing tokens
+    /// * `amount` - Amount of governance tokens to lock
+    pub fn deposit(env: Env, account: Address, amount: i128) -> Result<(), ContractError> {
+        account.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidValue);
+        }
+
+        let token_contract: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("token"))
+            .ok_or(ContractError::Unauthorized)?;
+        token::Client::new(&env, &token_contract).transfer(
+            &account,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let balance = Self::get_deposit(env.clone(), account.clone())
+            .checked_add(amount)
             .ok_or(ContractError::MathOverflow)?;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("deposit"), account.clone()), &balance);
+
+        let delegate = Self::get_delegate(env.clone(), account.clone());
+        Self::adjust_votes(&env, &CheckpointKey::Votes(delegate), amount)?;
+        Self::adjust_votes(&env, &CheckpointKey::Supply, amount)?;
+
+        env.events().publish((EVT_DEPOSIT,), (account, amount));
+
+        Ok(())
+    }
+
+    /// Unlock governance tokens, removing their voting power
+    ///
+    /// Votes already cast keep their weight, as proposals count power at
+    /// their snapshot.
+    ///
+    /// # Arguments
+    /// * `account` - Address withdrawing tokens
+    /// * `amount` - Amount of governance tokens to unlock
+    pub fn withdraw(env: Env, account: Address, amount: i128) -> Result<(), ContractError> {
+        account.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidValue);
+        }
+
+        let balance = Self::get_deposit(env.clone(), account.clone());
+        if amount > balance {
+            return Err(ContractError::InsufficientBalance);
+        }
+        if balance - amount < Self::get_locked_deposit(env.clone(), account.clone()) {
+            return Err(ContractError::TokensLocked);
+        }
+        env.storage().persistent().set(
+            &(symbol_short!("deposit"), account.clone()),
+            &(balance - amount),
+        );
+
+        let delegate = Self::get_delegate(env.clone(), account.clone());
+        Self::adjust_votes(&env, &CheckpointKey::Votes(delegate), -amount)?;
+        Self::adjust_votes(&env, &CheckpointKey::Supply, -amount)?;
 
-        let total_voting_power: i128 = env
+        let token_contract: Address = env
             .storage()
             .instance()
-            .get(&symbol_short!("total_pwr"))
-            .unwrap_or(1000000);
+            .get(&symbol_short!("token"))
+            .ok_or(ContractError::Unauthorized)?;
+        token::Client::new(&env, &token_contract).transfer(
+            &env.current_contract_address(),
+            &account,
+            &amount,
+        );
 
-        let quorum_required = total_voting_power
-            .checked_mul(config.quorum_bps as i128)
-            .ok_or(ContractError::MathOverflow)?
-            / 10000;
+        env.events().publish((EVT_WITHDRAW,), (account, amount));
+
+        Ok(())
+    }
+
+    /// Delegate the voting power of an account's deposit
+    ///
+    /// # Arguments
+    /// * `account` - Address delegating its power
+    /// * `to` - New delegate (the account itself to take the power back)
+    pub fn delegate(env: Env, account: Address, to: Address) -> Result<(), ContractError> {
+        account.require_auth();
+
+        let previous = Self::get_delegate(env.clone(), account.clone());
+        if previous == to {
+            return Ok(());
+        }
+
+        if to == account {
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("delegate"), account.clone()));
+        } else {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("delegate"), account.clone()), &to);
+        }
+
+        let balance = Self::get_deposit(env.clone(), account.clone());
+        if balance > 0 {
+            Self::adjust_votes(&env, &CheckpointKey::Votes(previous.clone()), -balance)?;
+            Self::adjust_votes(&env, &CheckpointKey::Votes(to.clone()), balance)?;
+        }
+
+        env.events()
+            .publish((EVT_DELEGATE,), (account, previous, to));
+
+        Ok(())
+    }
+
+    /// Get the delegate receiving an account's voting power
+    pub fn get_delegate(env: Env, account: Address) -> Address {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("delegate"), account.clone()))
+            .unwrap_or(account)
+    }
+
+    /// Get the governance tokens an account has locked
+    pub fn get_deposit(env: Env, account: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("deposit"), account))
+            .unwrap_or(0)
+    }
+
+    /// Get the current voting power of an account (own and delegated deposits)
+    pub fn get_votes(env: Env, account: Address) -> i128 {
+        Self::get_voting_power(&env, &account)
+    }
+
+    /// Get the voting power of an account as of the end of a ledger
+    pub fn get_past_votes(env: Env, account: Address, ledger: u32) -> i128 {
+        Self::lookup_checkpoint(&env, &CheckpointKey::Votes(account), ledger)
+    }
+
+    /// Get the timestamp until which conviction votes lock an account's deposit
+    pub fn get_vote_lock(env: Env, account: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("vote_lck"), account))
+            .unwrap_or(0)
+    }
+
+    /// Get the part of an account's deposit conviction votes currently lock
+    pub fn get_locked_deposit(env: Env, account: Address) -> i128 {
+        if env.ledger().timestamp() >= Self::get_vote_lock(env.clone(), account.clone()) {
+            return 0;
+        }
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("lock_amt"), account))
+            .unwrap_or(0)
+    }
+
+    /// Get the total locked governance tokens as of the end of a ledger
+    pub fn get_past_total_supply(env: Env, ledger: u32) -> i128 {
+        Self::lookup_checkpoint(&env, &CheckpointKey::Supply, ledger)
+    }
+
+    // ========================================================================
+    // Admin Functions
+    // ========================================================================
+
+    /// Update governance configuration (admin only)
+    pub fn update_config(env: Env, new_config: GovernanceConfig) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("config"), &new_config);
+
+        Ok(())
+    }
+
+    /// Cancel a proposal (admin only, for emergencies)
+    pub fn cancel_proposal(env: Env, proposal_id: u64) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+
+        if proposal.status == ProposalStatus::Executed {
+            return Err(ContractError::ProposalAlreadyExecuted);
+        }
+
+        proposal.status = ProposalStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("prop"), proposal_id), &proposal);
+
+        env.events().publish((EVT_CANCELLED,), (proposal_id,));
+
+        Ok(())
+    }
+
+    /// Set total voting power for testing
+    #[cfg(any(test, feature = "testutils"))]
+    pub fn set_total_voting_power(env: Env, total_power: i128) -> Result<(), ContractError> {
+        Self::write_checkpoint(&env, &CheckpointKey::Supply, total_power);
+        Ok(())
+    }
+
+    /// Set the guardian multisig (admin only)
+    ///
+    /// # Arguments
+    /// * `guardians` - Guardian addresses
+    /// * `threshold` - Approvals needed to veto a queued proposal
+    pub fn set_guardians(
+        env: Env,
+        guardians: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        if threshold == 0 || threshold > guardians.len() {
+            return Err(ContractError::InvalidValue);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("guardians"), &guardians);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("grd_thr"), &threshold);
+
+        env.events()
+            .publish((EVT_GUARDIANS,), (guardians, threshold));
+
+        Ok(())
+    }
+
+    /// Get the guardian addresses
+    pub fn get_guardians(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("guardians"))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the number of guardian approvals needed to veto
+    pub fn get_guardian_threshold(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("grd_thr"))
+            .unwrap_or(0)
+    }
+
+    /// Check if an address is a guardian
+    pub fn is_guardian(env: Env, account: Address) -> bool {
+        Self::get_guardians(env).contains(&account)
+    }
+
+    /// Approve the veto of a queued proposal (guardians only)
+    ///
+    /// The proposal is vetoed once the guardian threshold approves, which
+    /// must happen before its timelock elapses.
+    ///
+    /// # Arguments
+    /// * `guardian` - Guardian approving the veto
+    /// * `proposal_id` - ID of the queued proposal
+    pub fn veto_proposal(
+        env: Env,
+        guardian: Address,
+        proposal_id: u64,
+    ) -> Result<(), ContractError> {
+        guardian.require_auth();
+
+        if !Self::is_guardian(env.clone(), guardian.clone()) {
+            return Err(ContractError::NotGuardian);
+        }
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
+
+        let state = Self::proposal_state(&env, &proposal)?;
+        if state != ProposalStatus::Queued {
+            return Err(Self::state_error(&env, &proposal, state));
+        }
+        if env.ledger().timestamp() >= proposal.execution_ts {
+            return Err(ContractError::VetoWindowClosed);
+        }
+
+        let veto_key = (symbol_short!("veto"), proposal_id, guardian.clone());
+        if env.storage().persistent().has(&veto_key) {
+            return Err(ContractError::AlreadyVetoed);
+        }
+        env.storage().persistent().set(&veto_key, &true);
+
+        proposal.vetoes = proposal
+            .vetoes
+            .checked_add(1)
+            .ok_or(ContractError::MathOverflow)?;
+
+        env.events()
+            .publish((EVT_VETO,), (proposal_id, guardian, proposal.vetoes));
+
+        if proposal.vetoes >= Self::get_guardian_threshold(env.clone()) {
+            proposal.status = ProposalStatus::Vetoed;
+            env.events().publish((EVT_VETOED,), (proposal_id,));
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("prop"), proposal_id), &proposal);
+
+        Ok(())
+    }
+
+    /// Set the voting mode of proposals created from now on (admin only)
+    pub fn set_voting_mode(env: Env, mode: VotingMode) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("vote_mode"), &mode);
+
+        env.events().publish((EVT_MODE,), (mode,));
+
+        Ok(())
+    }
+
+    /// Get the voting mode applied to new proposals
+    pub fn get_voting_mode(env: Env) -> VotingMode {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("vote_mode"))
+            .unwrap_or(VotingMode::Linear)
+    }
+
+    /// Require voters to hold a minimum ReputationRegistry score (admin only)
+    ///
+    /// # Arguments
+    /// * `registry` - ReputationRegistry contract address
+    /// * `min_score` - Minimum score (0-1000) to vote; 0 disables the check
+    pub fn set_min_reputation(
+        env: Env,
+        registry: Address,
+        min_score: u32,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        if min_score > 1000 {
+            return Err(ContractError::InvalidValue);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("rep_reg"), &registry);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("min_rep"), &min_score);
+
+        Ok(())
+    }
+
+    /// Allow or disallow proposals to call a function on a target (admin only)
+    pub fn set_allowed_function(
+        env: Env,
+        target: Address,
+        function: Symbol,
+        allowed: bool,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        let key = (symbol_short!("allow"), target.clone(), function.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events()
+            .publish((EVT_ALLOWED,), (target, function, allowed));
+
+        Ok(())
+    }
+
+    /// Check if proposals may call a function on a target
+    pub fn is_function_allowed(env: Env, target: Address, function: Symbol) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("allow"), target, function))
+            .unwrap_or(false)
+    }
+
+    /// Storage layout version governance's own records are in, moved only by
+    /// an executed `migrate` action
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
+
+    // ========================================================================
+    // Internal Helper Functions
+    // ========================================================================
+
+    /// Assign the next proposal ID and store a new proposal
+    fn store_proposal(
+        env: &Env,
+        proposer: &Address,
+        target_contract: Address,
+        parameter_symbol: Symbol,
+        new_value: i128,
+        actions: Vec<ProposalAction>,
+        emergency: bool,
+    ) -> Result<u64, ContractError> {
+        let config = Self::get_config(env.clone());
+
+        // Get and increment proposal counter
+        let proposal_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("prop_cnt"))
+            .unwrap_or(0);
+
+        let next_id = proposal_id
+            .checked_add(1)
+            .ok_or(ContractError::MathOverflow)?;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("prop_cnt"), &next_id);
+
+        let current_ts = env.ledger().timestamp();
+        let (voting_delay, voting_period) = if emergency {
+            (0, config.emergency_voting_period)
+        } else {
+            (config.voting_delay, config.voting_period)
+        };
+        let voting_start_ts = current_ts
+            .checked_add(voting_delay)
+            .ok_or(ContractError::MathOverflow)?;
+        let voting_end_ts = voting_start_ts
+            .checked_add(voting_period)
+            .ok_or(ContractError::MathOverflow)?;
+
+        let proposal = Proposal {
+            id: proposal_id,
+            proposer: proposer.clone(),
+            target_contract,
+            parameter_symbol,
+            new_value,
+            actions,
+            snapshot_ledger: env.ledger().sequence().saturating_sub(1),
+            voting_mode: Self::get_voting_mode(env.clone()),
+            emergency,
+            voting_start_ts,
+            voting_end_ts,
+            votes_for: 0,
+            votes_against: 0,
+            raw_for: 0,
+            raw_against: 0,
+            status: ProposalStatus::Pending,
+            vetoes: 0,
+            execution_ts: 0,
+            created_at: current_ts,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("prop"), proposal_id), &proposal);
+
+        Ok(proposal_id)
+    }
+
+    /// Check a proposal's quorum and majority
+    ///
+    /// Emergency proposals count only supporting power toward their quorum.
+    fn check_outcome(env: &Env, proposal: &Proposal) -> Result<(), ContractError> {
+        let config = Self::get_config(env.clone());
+
+        // Verify quorum
+        let (raw_votes, quorum_bps) = if proposal.emergency {
+            (proposal.raw_for, config.emergency_quorum_bps)
+        } else {
+            let raw_votes = proposal
+                .raw_for
+                .checked_add(proposal.raw_against)
+                .ok_or(ContractError::MathOverflow)?;
+            (raw_votes, config.quorum_bps)
+        };
+
+        let total_voting_power = Self::get_past_total_supply(env.clone(), proposal.snapshot_ledger);
+
+        let quorum_required = total_voting_power
+            .checked_mul(quorum_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+
+        if raw_votes < quorum_required {
+            return Err(ContractError::QuorumNotReached);
+        }
+
+        // Verify majority
+        let total_votes = proposal
+            .votes_for
+            .checked_add(proposal.votes_against)
+            .ok_or(ContractError::MathOverflow)?;
+
+        let majority_required = total_votes
+            .checked_mul(config.majority_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+
+        if proposal.votes_for < majority_required {
+            return Err(ContractError::MajorityNotReached);
+        }
+
+        Ok(())
+    }
+
+    /// Check a proposal's quorum and majority, treating a miss as `false`
+    fn outcome_passed(env: &Env, proposal: &Proposal) -> Result<bool, ContractError> {
+        match Self::check_outcome(env, proposal) {
+            Ok(()) => Ok(true),
+            Err(ContractError::QuorumNotReached) | Err(ContractError::MajorityNotReached) => {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Derive a proposal's state from its recorded status, the clock and the tally
+    fn proposal_state(env: &Env, proposal: &Proposal) -> Result<ProposalStatus, ContractError> {
+        let config = Self::get_config(env.clone());
+        let current_ts = env.ledger().timestamp();
+
+        match proposal.status {
+            ProposalStatus::Pending => {}
+            ProposalStatus::Queued => {
+                let expires_at = proposal
+                    .execution_ts
+                    .checked_add(config.grace_period)
+                    .ok_or(ContractError::MathOverflow)?;
+                if current_ts >= expires_at {
+                    return Ok(ProposalStatus::Expired);
+                }
+                return Ok(ProposalStatus::Queued);
+            }
+            status => return Ok(status),
+        }
+
+        if current_ts < proposal.voting_start_ts {
+            return Ok(ProposalStatus::Pending);
+        }
 
-        if total_votes < quorum_required {
-            return Ok(false);
+        if current_ts < proposal.voting_end_ts {
+            // Emergency proposals pass as soon as enough power supports them
+            if proposal.emergency && Self::outcome_passed(env, proposal)? {
+                return Ok(ProposalStatus::Succeeded);
+            }
+            return Ok(ProposalStatus::Active);
         }
 
-        let majority_required = total_votes
-            .checked_mul(config.majority_bps as i128)
-            .ok_or(ContractError::MathOverflow)?
-            / 10000;
+        if !Self::outcome_passed(env, proposal)? {
+            return Ok(ProposalStatus::Defeated);
+        }
 
-        Ok(proposal.votes_for >= majority_required)
+        // Passed proposals left unqueued expire like queued ones
+        let expires_at = proposal
+            .voting_end_ts
+            .checked_add(config.grace_period)
+            .ok_or(ContractError::MathOverflow)?;
+        if current_ts >= expires_at {
+            return Ok(ProposalStatus::Expired);
+        }
+
+        Ok(ProposalStatus::Succeeded)
     }
 
-    // ========================================================================
-    // Admin Functions
-    // ========================================================================
+    /// Error for a proposal that is not in the state an operation requires
+    fn state_error(env: &Env, proposal: &Proposal, state: ProposalStatus) -> ContractError {
+        match state {
+            ProposalStatus::Pending | ProposalStatus::Active => ContractError::ProposalNotActive,
+            ProposalStatus::Succeeded => ContractError::ProposalNotQueued,
+            // Report the requirement the proposal missed
+            ProposalStatus::Defeated => Self::check_outcome(env, proposal)
+                .err()
+                .unwrap_or(ContractError::ProposalFailed),
+            ProposalStatus::Queued => ContractError::ProposalAlreadyQueued,
+            ProposalStatus::Executed => ContractError::ProposalAlreadyExecuted,
+            ProposalStatus::Expired => ContractError::ProposalExpired,
+            ProposalStatus::Vetoed | ProposalStatus::Cancelled => ContractError::ProposalFailed,
+        }
+    }
 
-    /// Update governance configuration (admin only)
-    pub fn update_config(env: Env, new_config: GovernanceConfig) -> Result<(), ContractError> {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .ok_or(ContractError::Unauthorized)?;
+    /// Run an action targeting this contract (`upgrade` or `migrate`)
+    fn execute_self_action(env: &Env, action: &ProposalAction) -> Result<(), ContractError> {
+        let arg = action.args.get(0).ok_or(ContractError::InvalidActions)?;
+
+        if action.function == symbol_short!("upgrade") {
+            let new_wasm_hash =
+                BytesN::<32>::try_from_val(env, &arg).map_err(|_| ContractError::InvalidActions)?;
+            env.deployer()
+                .update_current_contract_wasm(new_wasm_hash.clone());
+            env.events().publish((EVT_UPGRADED,), (new_wasm_hash,));
+        } else if action.function == symbol_short!("migrate") {
+            let version =
+                u32::try_from_val(env, &arg).map_err(|_| ContractError::InvalidActions)?;
+            let current = Self::version(env.clone());
+            if version <= current || version > CONTRACT_VERSION {
+                return Err(ContractError::InvalidVersion);
+            }
+            env.storage()
+                .instance()
+                .set(&symbol_short!("version"), &version);
+            env.events().publish((EVT_MIGRATED,), (current, version));
+        } else {
+            return Err(ContractError::FunctionNotAllowed);
+        }
 
-        admin.require_auth();
+        Ok(())
+    }
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("config"), &new_config);
+    /// Check a proposal's actions are within bounds and allowlisted
+    fn validate_actions(env: &Env, actions: &Vec<ProposalAction>) -> Result<(), ContractError> {
+        if actions.is_empty() || actions.len() > MAX_ACTIONS {
+            return Err(ContractError::InvalidActions);
+        }
+        for action in actions.iter() {
+            if !Self::is_function_allowed(env.clone(), action.target, action.function) {
+                return Err(ContractError::FunctionNotAllowed);
+            }
+        }
 
         Ok(())
     }
 
-    /// Cancel a proposal (admin only, for emergencies)
-    pub fn cancel_proposal(env: Env, proposal_id: u64) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Reject voters below the configured ReputationRegistry score
+    fn check_reputation(env: &Env, voter: &Address) -> Result<(), ContractError> {
+        let min_score: u32 = env
             .storage()
             .instance()
-            .get(&symbol_short!("admin"))
-            .ok_or(ContractError::Unauthorized)?;
-
-        admin.require_auth();
-
-        let mut proposal = Self::get_proposal(env.clone(), proposal_id)?;
-
-        if proposal.executed {
-            return Err(ContractError::ProposalAlreadyExecuted);
+            .get(&symbol_short!("min_rep"))
+            .unwrap_or(0);
+        if min_score == 0 {
+            return Ok(());
         }
 
-        // Mark as executed to prevent execution
-        proposal.executed = true;
-        env.storage()
-            .persistent()
-            .set(&(symbol_short!("prop"), proposal_id), &proposal);
-
-        env.events().publish((EVT_CANCELLED,), (proposal_id,));
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("rep_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+        let score_args: Vec<Val> = Vec::from_array(env, [voter.into_val(env)]);
+        let score: u32 = env.invoke_contract(
+            &registry,
+            &Symbol::new(env, "get_reputation_score"),
+            score_args,
+        );
+        if score < min_score {
+            return Err(ContractError::InsufficientReputation);
+        }
 
         Ok(())
     }
 
-    /// Set total voting power (admin only)
-    pub fn set_total_voting_power(env: Env, total_power: i128) -> Result<(), ContractError> {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .ok_or(ContractError::Unauthorized)?;
+    /// Lock `power` of the voter's own deposit for a conviction vote
+    ///
+    /// Overlapping locks merge into one covering the largest amount until the
+    /// latest expiry.
+    fn lock_for_conviction(
+        env: &Env,
+        voter: &Address,
+        proposal: &Proposal,
+        power: i128,
+        conviction: u32,
+    ) -> Result<(), ContractError> {
+        if conviction == 0 {
+            return Ok(());
+        }
+        if Self::get_deposit(env.clone(), voter.clone()) < power {
+            return Err(ContractError::InsufficientBalance);
+        }
+        let locked = Self::get_locked_deposit(env.clone(), voter.clone());
 
-        admin.require_auth();
+        let config = Self::get_config(env.clone());
+        let lock_period = config
+            .timelock_period
+            .checked_mul(1u64 << (conviction - 1))
+            .ok_or(ContractError::MathOverflow)?;
+        let lock_until = proposal
+            .voting_end_ts
+            .checked_add(lock_period)
+            .ok_or(ContractError::MathOverflow)?;
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("total_pwr"), &total_power);
+        if lock_until > Self::get_vote_lock(env.clone(), voter.clone()) {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("vote_lck"), voter.clone()), &lock_until);
+        }
+        if power > locked {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("lock_amt"), voter.clone()), &power);
+        }
 
         Ok(())
     }
 
-    // ========================================================================
-    // Internal Helper Functions
-    // ========================================================================
+    /// Integer square root (rounded down)
+    fn sqrt(n: i128) -> i128 {
+        if n < 2 {
+            return n;
+        }
+        let mut x = n / 2;
+        let mut y = (x + n / x) / 2;
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+        x
+    }
 
-    /// Get voting power for an address
-    /// In production, this would query the token contract for locked balance
+    /// Get the current voting power of an address
     fn get_voting_power(env: &Env, voter: &Address) -> i128 {
-        // For testing, use stored test data
-        let key = (symbol_short!("vp"), voter.clone());
-        env.storage().persistent().get(&key).unwrap_or(100)
+        Self::checkpoints(env, &CheckpointKey::Votes(voter.clone()))
+            .last()
+            .map(|checkpoint| checkpoint.votes)
+            .unwrap_or(0)
     }
 
     /// Set voting power for testing
     #[cfg(any(test, feature = "testutils"))]
     pub fn set_voting_power(env: Env, voter: Address, power: i128) {
-        let key = (symbol_short!("vp"), voter);
-        env.storage().persistent().set(&key, &power);
+        Self::write_checkpoint(&env, &CheckpointKey::Votes(voter), power);
+    }
+
+    fn checkpoints(env: &Env, key: &CheckpointKey) -> Vec<Checkpoint> {
+        env.storage().persistent().get(key).unwrap_or(Vec::new(env))
+    }
+
+    /// Record a new value for the current ledger
+    fn write_checkpoint(env: &Env, key: &CheckpointKey, votes: i128) {
+        let ledger = env.ledger().sequence();
+        let mut checkpoints = Self::checkpoints(env, key);
+        let checkpoint = Checkpoint { ledger, votes };
+        match checkpoints.last() {
+            Some(last) if last.ledger == ledger => {
+                checkpoints.set(checkpoints.len() - 1, checkpoint);
+            }
+            _ => checkpoints.push_back(checkpoint),
+        }
+        env.storage().persistent().set(key, &checkpoints);
+    }
+
+    fn adjust_votes(env: &Env, key: &CheckpointKey, delta: i128) -> Result<(), ContractError> {
+        let current = Self::checkpoints(env, key)
+            .last()
+            .map(|checkpoint| checkpoint.votes)
+            .unwrap_or(0);
+        let votes = current
+            .checked_add(delta)
+            .ok_or(ContractError::MathOverflow)?;
+        Self::write_checkpoint(env, key, votes);
+        Ok(())
+    }
+
+    /// Binary search for the value in force at the end of `ledger`
+    fn lookup_checkpoint(env: &Env, key: &CheckpointKey, ledger: u32) -> i128 {
+        let checkpoints = Self::checkpoints(env, key);
+        let mut low = 0;
+        let mut high = checkpoints.len();
+        while low < high {
+            let mid = (low + high) / 2;
+            if checkpoints.get_unchecked(mid).ledger > ledger {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        if low == 0 {
+            0
+        } else {
+            checkpoints.get_unchecked(low - 1).votes
+        }
     }
 
     /// Validate parameter symbol and value
@@ -665,10 +1708,110 @@ impl Governance {
 mod test {
     use super::*;
     use soroban_sdk::{
+        contract, contractimpl,
         testutils::{Address as _, Ledger as _},
-        Env,
+        Env, IntoVal,
     };
 
+    /// Stand-in for a protocol contract with admin-gated setters
+    #[contract]
+    pub struct MockTarget;
+
+    #[contractimpl]
+    impl MockTarget {
+        pub fn set_owner(env: Env, owner: Address) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("owner"), &owner);
+        }
+
+        pub fn set_fee(env: Env, fee_bps: u32) {
+            let owner: Address = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("owner"))
+                .unwrap();
+            owner.require_auth();
+            if fee_bps > 1000 {
+                panic!("fee too high");
+            }
+            env.storage()
+                .instance()
+                .set(&symbol_short!("fee"), &fee_bps);
+        }
+
+        pub fn set_pause(env: Env, paused: bool) {
+            let owner: Address = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("owner"))
+                .unwrap();
+            owner.require_auth();
+            env.storage()
+                .instance()
+                .set(&symbol_short!("paused"), &paused);
+        }
+
+        pub fn fee(env: Env) -> u32 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("fee"))
+                .unwrap_or(0)
+        }
+
+        pub fn paused(env: Env) -> bool {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("paused"))
+                .unwrap_or(false)
+        }
+    }
+
+    /// Stand-in for the ReputationRegistry score lookup
+    #[contract]
+    pub struct MockReputation;
+
+    #[contractimpl]
+    impl MockReputation {
+        pub fn set_score(env: Env, user: Address, score: u32) {
+            env.storage().persistent().set(&user, &score);
+        }
+
+        pub fn get_reputation_score(env: Env, user: Address) -> u32 {
+            env.storage().persistent().get(&user).unwrap_or(0)
+        }
+    }
+
+    fn fee_action(env: &Env, target: &Address, fee_bps: u32) -> ProposalAction {
+        ProposalAction {
+            target: target.clone(),
+            function: symbol_short!("set_fee"),
+            args: Vec::from_array(env, [fee_bps.into_val(env)]),
+        }
+    }
+
+    /// Governance owning a MockTarget whose `set_fee` is allowlisted
+    fn setup_actions(env: &Env) -> (GovernanceClient<'_>, MockTargetClient<'_>, Address) {
+        env.mock_all_auths();
+        let governance_id = env.register_contract(None, Governance);
+        let governance = GovernanceClient::new(env, &governance_id);
+        let target = MockTargetClient::new(env, &env.register_contract(None, MockTarget));
+
+        governance.initialize(
+            &Address::generate(env),
+            &Address::generate(env),
+            &Address::generate(env),
+        );
+        governance.set_total_voting_power(&100000);
+        governance.set_allowed_function(&target.address, &symbol_short!("set_fee"), &true);
+        target.set_owner(&governance_id);
+
+        let proposer = Address::generate(env);
+        governance.set_voting_power(&proposer, &60000);
+
+        (governance, target, proposer)
+    }
+
     fn setup_env() -> (Env, Address, Address, Address) {
         let env = Env::default();
         let admin = Address::generate(&env);
@@ -774,7 +1917,7 @@ mod test {
             assert_eq!(proposal.new_value, 7500);
             assert_eq!(proposal.votes_for, 0);
             assert_eq!(proposal.votes_against, 0);
-            assert!(!proposal.executed);
+            assert_eq!(proposal.status, ProposalStatus::Pending);
         });
     }
 
@@ -1160,13 +2303,15 @@ mod test {
 
             // Advance time past voting period and timelock
             env.ledger()
-                .set_timestamp(env.ledger().timestamp() + 604800 + 86400 + 1);
+                .set_timestamp(env.ledger().timestamp() + 604800 + 1);
+            Governance::queue_proposal(env.clone(), proposal_id).unwrap();
+            env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
 
             let result = Governance::execute_proposal(env.clone(), proposal_id);
             assert!(result.is_ok());
 
             let proposal = Governance::get_proposal(env.clone(), proposal_id).unwrap();
-            assert!(proposal.executed);
+            assert_eq!(proposal.status, ProposalStatus::Executed);
         });
     }
 
@@ -1207,6 +2352,7 @@ mod test {
             // Advance time past voting but not timelock
             env.ledger()
                 .set_timestamp(env.ledger().timestamp() + 604801);
+            Governance::queue_proposal(env.clone(), proposal_id).unwrap();
 
             let result = Governance::execute_proposal(env.clone(), proposal_id);
             assert_eq!(result, Err(ContractError::TimelockNotExpired));
@@ -1338,7 +2484,9 @@ mod test {
             Governance::cast_vote(env.clone(), proposal_id, voter1.clone(), true).unwrap();
 
             env.ledger()
-                .set_timestamp(env.ledger().timestamp() + 604800 + 86400 + 1);
+                .set_timestamp(env.ledger().timestamp() + 604800 + 1);
+            Governance::queue_proposal(env.clone(), proposal_id).unwrap();
+            env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
 
             Governance::execute_proposal(env.clone(), proposal_id).unwrap();
 
@@ -1416,6 +2564,7 @@ mod test {
                 quorum_bps: 1500,        // 15%
                 majority_bps: 6000,      // 60%
                 min_voting_power: 2000,
+                ..GovernanceConfig::default()
             };
 
             let result = Governance::update_config(env.clone(), new_config.clone());
@@ -1461,11 +2610,610 @@ mod test {
             assert!(result.is_ok());
 
             let proposal = Governance::get_proposal(env.clone(), proposal_id).unwrap();
-            assert!(proposal.executed); // Marked as executed to prevent execution
+            assert_eq!(proposal.status, ProposalStatus::Cancelled);
         });
     }
 
     // ========================================================================
     // Parameter Validation Tests
     // ========================================================================
+
+    // ========================================================================
+    // Action Proposal Tests
+    // ========================================================================
+
+    #[test]
+    fn test_action_proposal_executes_calls() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+        governance.set_allowed_function(&target.address, &symbol_short!("set_pause"), &true);
+
+        let actions = Vec::from_array(
+            &env,
+            [
+                fee_action(&env, &target.address, 250),
+                ProposalAction {
+                    target: target.address.clone(),
+                    function: symbol_short!("set_pause"),
+                    args: Vec::from_array(&env, [true.into_val(&env)]),
+                },
+            ],
+        );
+        let proposal_id = governance.create_action_proposal(&proposer, &actions);
+
+        let proposal = governance.get_proposal(&proposal_id);
+        assert_eq!(proposal.actions.len(), 2);
+        assert_eq!(proposal.new_value, 2);
+
+        governance.cast_vote(&proposal_id, &proposer, &true);
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + 604800 + 1);
+        governance.queue_proposal(&proposal_id);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+        governance.execute_proposal(&proposal_id);
+
+        assert_eq!(target.fee(), 250);
+        assert!(target.paused());
+        assert_eq!(
+            governance.get_proposal_state(&proposal_id),
+            ProposalStatus::Executed
+        );
+    }
+
+    #[test]
+    fn test_action_proposal_requires_allowlisted_function() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+
+        let paused = ProposalAction {
+            target: target.address.clone(),
+            function: symbol_short!("set_pause"),
+            args: Vec::from_array(&env, [true.into_val(&env)]),
+        };
+        env.as_contract(&governance.address, || {
+            assert_eq!(
+                Governance::create_action_proposal(
+                    env.clone(),
+                    proposer.clone(),
+                    Vec::from_array(&env, [paused]),
+                ),
+                Err(ContractError::FunctionNotAllowed)
+            );
+        });
+        env.as_contract(&governance.address, || {
+            assert_eq!(
+                Governance::create_action_proposal(env.clone(), proposer.clone(), Vec::new(&env)),
+                Err(ContractError::InvalidActions)
+            );
+        });
+    }
+
+    #[test]
+    fn test_action_proposal_reverts_all_on_failure() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+
+        // The second action is rejected by the target
+        let actions = Vec::from_array(
+            &env,
+            [
+                fee_action(&env, &target.address, 250),
+                fee_action(&env, &target.address, 5000),
+            ],
+        );
+        let proposal_id = governance.create_action_proposal(&proposer, &actions);
+        governance.cast_vote(&proposal_id, &proposer, &true);
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + 604800 + 1);
+        governance.queue_proposal(&proposal_id);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+
+        assert!(governance.try_execute_proposal(&proposal_id).is_err());
+        assert_eq!(target.fee(), 0);
+        assert_eq!(
+            governance.get_proposal_state(&proposal_id),
+            ProposalStatus::Queued
+        );
+    }
+
+    // ========================================================================
+    // Voting Power Tests
+    // ========================================================================
+
+    /// Governance over a real token, with two funded holders
+    fn setup_token_voting(env: &Env) -> (GovernanceClient<'_>, Address, Address) {
+        env.mock_all_auths();
+        let token_admin = Address::generate(env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        let governance = GovernanceClient::new(env, &env.register_contract(None, Governance));
+        governance.initialize(
+            &Address::generate(env),
+            &token.address(),
+            &Address::generate(env),
+        );
+
+        let alice = Address::generate(env);
+        let bob = Address::generate(env);
+        let minter = token::StellarAssetClient::new(env, &token.address());
+        minter.mint(&alice, &5000);
+        minter.mint(&bob, &5000);
+
+        (governance, alice, bob)
+    }
+
+    fn next_ledger(env: &Env) {
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+    }
+
+    #[test]
+    fn test_voting_power_snapshotted_at_creation() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.deposit(&alice, &5000);
+        next_ledger(&env);
+        let proposal_id = governance.create_proposal(
+            &alice,
+            &Address::generate(&env),
+            &symbol_short!("liq_thr"),
+            &7500,
+        );
+        let snapshot = governance.get_proposal(&proposal_id).snapshot_ledger;
+
+        // Tokens locked after the snapshot carry no weight on this proposal
+        next_ledger(&env);
+        governance.deposit(&bob, &5000);
+        assert_eq!(governance.get_votes(&bob), 5000);
+        assert!(governance.try_cast_vote(&proposal_id, &bob, &true).is_err());
+
+        governance.cast_vote(&proposal_id, &alice, &true);
+        assert_eq!(governance.get_proposal(&proposal_id).votes_for, 5000);
+        assert_eq!(governance.get_past_total_supply(&snapshot), 5000);
+        assert!(governance.has_proposal_passed(&proposal_id));
+
+        // Withdrawing returns the tokens but not the cast vote
+        governance.withdraw(&alice, &5000);
+        assert_eq!(governance.get_votes(&alice), 0);
+        assert_eq!(governance.get_proposal(&proposal_id).votes_for, 5000);
+    }
+
+    #[test]
+    fn test_same_ledger_deposits_cannot_vote_twice() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.deposit(&bob, &1000);
+        next_ledger(&env);
+        let proposal_id = liq_proposal(&env, &governance, &bob);
+
+        // Deposit, vote, withdraw and redeposit elsewhere, all in the
+        // proposal's ledger: none of it reaches the snapshot
+        governance.deposit(&alice, &5000);
+        assert!(governance
+            .try_cast_vote(&proposal_id, &alice, &true)
+            .is_err());
+        governance.withdraw(&alice, &5000);
+        governance.deposit(&bob, &4000);
+        governance.cast_vote(&proposal_id, &bob, &true);
+
+        let proposal = governance.get_proposal(&proposal_id);
+        assert_eq!(proposal.votes_for, 1000);
+        assert_eq!(
+            governance.get_past_total_supply(&proposal.snapshot_ledger),
+            1000
+        );
+    }
+
+    #[test]
+    fn test_delegation_checkpoints() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.deposit(&alice, &3000);
+        governance.deposit(&bob, &2000);
+        let before = env.ledger().sequence();
+
+        next_ledger(&env);
+        governance.delegate(&alice, &bob);
+        assert_eq!(governance.get_delegate(&alice), bob);
+        assert_eq!(governance.get_votes(&alice), 0);
+        assert_eq!(governance.get_votes(&bob), 5000);
+        assert_eq!(governance.get_past_votes(&alice, &before), 3000);
+        assert_eq!(governance.get_past_votes(&bob, &before), 2000);
+
+        // Further deposits follow the delegation
+        next_ledger(&env);
+        governance.deposit(&alice, &1000);
+        assert_eq!(governance.get_votes(&bob), 6000);
+
+        next_ledger(&env);
+        governance.delegate(&alice, &alice);
+        assert_eq!(governance.get_votes(&alice), 4000);
+        assert_eq!(governance.get_votes(&bob), 2000);
+        assert_eq!(governance.get_past_votes(&bob, &(before + 2)), 6000);
+        assert_eq!(governance.get_past_total_supply(&before), 5000);
+        assert_eq!(governance.get_past_total_supply(&(before + 3)), 6000);
+    }
+
+    fn liq_proposal(env: &Env, governance: &GovernanceClient, proposer: &Address) -> u64 {
+        governance.create_proposal(
+            proposer,
+            &Address::generate(env),
+            &symbol_short!("liq_thr"),
+            &7500,
+        )
+    }
+
+    #[test]
+    fn test_quadratic_voting_weight() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.set_voting_mode(&VotingMode::Quadratic);
+        governance.deposit(&alice, &3600);
+        governance.deposit(&bob, &400);
+        next_ledger(&env);
+        let proposal_id = liq_proposal(&env, &governance, &alice);
+
+        // Credits are capped by the snapshot power
+        assert!(governance
+            .try_cast_weighted_vote(&proposal_id, &bob, &false, &401, &0)
+            .is_err());
+
+        governance.cast_vote(&proposal_id, &alice, &true);
+        governance.cast_weighted_vote(&proposal_id, &bob, &false, &400, &0);
+
+        let proposal = governance.get_proposal(&proposal_id);
+        assert_eq!(proposal.voting_mode, VotingMode::Quadratic);
+        assert_eq!(proposal.votes_for, 60);
+        assert_eq!(proposal.votes_against, 20);
+        assert_eq!(proposal.raw_for, 3600);
+        assert_eq!(proposal.raw_against, 400);
+
+        let vote = governance.get_vote(&proposal_id, &bob).unwrap();
+        assert_eq!(vote.voting_power, 400);
+        assert_eq!(vote.effective_power, 20);
+        assert!(governance.has_proposal_passed(&proposal_id));
+    }
+
+    #[test]
+    fn test_conviction_vote_locks_deposit() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.set_voting_mode(&VotingMode::Conviction);
+        governance.deposit(&alice, &1000);
+        governance.deposit(&bob, &4000);
+        next_ledger(&env);
+        let proposal_id = liq_proposal(&env, &governance, &bob);
+
+        assert!(governance
+            .try_cast_weighted_vote(&proposal_id, &alice, &true, &1000, &7)
+            .is_err());
+
+        // 1000 at conviction 6 outweighs 4000 at conviction 0 (0.1x)
+        governance.cast_weighted_vote(&proposal_id, &alice, &true, &1000, &6);
+        governance.cast_weighted_vote(&proposal_id, &bob, &false, &4000, &0);
+
+        let proposal = governance.get_proposal(&proposal_id);
+        assert_eq!(proposal.votes_for, 6000);
+        assert_eq!(proposal.votes_against, 400);
+
+        let config = governance.get_config();
+        let lock_until = proposal.voting_end_ts + config.timelock_period * 32;
+        assert_eq!(governance.get_vote_lock(&alice), lock_until);
+        assert_eq!(governance.get_vote_lock(&bob), 0);
+
+        // Conviction 0 leaves the deposit free, higher levels lock it
+        governance.withdraw(&bob, &4000);
+        assert!(governance.try_withdraw(&alice, &1000).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = lock_until);
+        governance.withdraw(&alice, &1000);
+        assert_eq!(governance.get_deposit(&alice), 0);
+    }
+
+    #[test]
+    fn test_conviction_locks_committed_own_deposit() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+
+        governance.set_voting_mode(&VotingMode::Conviction);
+        governance.deposit(&alice, &1000);
+        governance.deposit(&bob, &3000);
+        governance.delegate(&bob, &alice);
+        next_ledger(&env);
+        let proposal_id = liq_proposal(&env, &governance, &alice);
+
+        // Delegated power has no deposit of the voter's to lock
+        assert!(governance
+            .try_cast_weighted_vote(&proposal_id, &alice, &true, &4000, &1)
+            .is_err());
+
+        governance.cast_weighted_vote(&proposal_id, &alice, &true, &600, &2);
+        assert_eq!(governance.get_locked_deposit(&alice), 600);
+
+        // Only the committed amount stays locked
+        assert!(governance.try_withdraw(&alice, &401).is_err());
+        governance.withdraw(&alice, &400);
+        assert_eq!(governance.get_deposit(&alice), 600);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = governance.get_vote_lock(&alice));
+        assert_eq!(governance.get_locked_deposit(&alice), 0);
+        governance.withdraw(&alice, &600);
+    }
+
+    #[test]
+    fn test_minimum_reputation_to_vote() {
+        let env = Env::default();
+        let (governance, alice, bob) = setup_token_voting(&env);
+        let registry =
+            MockReputationClient::new(&env, &env.register_contract(None, MockReputation));
+
+        governance.deposit(&alice, &2000);
+        governance.deposit(&bob, &2000);
+        next_ledger(&env);
+        let proposal_id = liq_proposal(&env, &governance, &alice);
+
+        assert!(governance
+            .try_set_min_reputation(&registry.address, &1001)
+            .is_err());
+        governance.set_min_reputation(&registry.address, &500);
+        registry.set_score(&alice, &650);
+        registry.set_score(&bob, &499);
+
+        governance.cast_vote(&proposal_id, &alice, &true);
+        assert!(governance
+            .try_cast_vote(&proposal_id, &bob, &false)
+            .is_err());
+
+        // Disabling the floor lets fresh accounts vote again
+        governance.set_min_reputation(&registry.address, &0);
+        governance.cast_vote(&proposal_id, &bob, &false);
+        assert_eq!(governance.get_proposal(&proposal_id).votes_against, 2000);
+    }
+
+    #[test]
+    fn test_proposal_lifecycle_states() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+        governance.update_config(&GovernanceConfig {
+            voting_delay: 3600,
+            ..GovernanceConfig::default()
+        });
+
+        let actions = Vec::from_array(&env, [fee_action(&env, &target.address, 250)]);
+        let passing = governance.create_action_proposal(&proposer, &actions);
+        let failing = governance.create_action_proposal(&proposer, &actions);
+        assert_eq!(
+            governance.get_proposal_state(&passing),
+            ProposalStatus::Pending
+        );
+        assert!(governance
+            .try_cast_vote(&passing, &proposer, &true)
+            .is_err());
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert_eq!(
+            governance.get_proposal_state(&passing),
+            ProposalStatus::Active
+        );
+        governance.cast_vote(&passing, &proposer, &true);
+        governance.cast_vote(&failing, &proposer, &false);
+
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + 604800);
+        assert_eq!(
+            governance.get_proposal_state(&passing),
+            ProposalStatus::Succeeded
+        );
+        assert_eq!(
+            governance.get_proposal_state(&failing),
+            ProposalStatus::Defeated
+        );
+        assert!(governance.try_queue_proposal(&failing).is_err());
+        assert!(governance.try_execute_proposal(&passing).is_err());
+
+        let eta = governance.queue_proposal(&passing);
+        assert_eq!(eta, env.ledger().timestamp() + 86400);
+        assert_eq!(
+            governance.get_proposal_state(&passing),
+            ProposalStatus::Queued
+        );
+
+        // Left unexecuted past the grace period, the proposal expires
+        env.ledger().set_timestamp(eta + 1209600);
+        assert_eq!(
+            governance.get_proposal_state(&passing),
+            ProposalStatus::Expired
+        );
+        assert!(governance.try_execute_proposal(&passing).is_err());
+        assert_eq!(target.fee(), 0);
+    }
+
+    #[test]
+    fn test_proposal_threshold_scales_with_supply() {
+        let env = Env::default();
+        let (governance, _target, proposer) = setup_actions(&env);
+        let small_holder = Address::generate(&env);
+        governance.set_voting_power(&small_holder, &2000);
+
+        // 1% of 100000 is below the 1000 token minimum
+        assert_eq!(governance.get_proposal_threshold(), 1000);
+        governance.create_proposal(
+            &small_holder,
+            &Address::generate(&env),
+            &symbol_short!("liq_thr"),
+            &7500,
+        );
+
+        governance.set_total_voting_power(&1000000);
+        assert_eq!(governance.get_proposal_threshold(), 10000);
+        assert!(governance
+            .try_create_proposal(
+                &small_holder,
+                &Address::generate(&env),
+                &symbol_short!("liq_thr"),
+                &7500,
+            )
+            .is_err());
+        governance.create_proposal(
+            &proposer,
+            &Address::generate(&env),
+            &symbol_short!("liq_thr"),
+            &7500,
+        );
+    }
+
+    #[test]
+    fn test_guardian_veto_within_timelock() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+        let guardians = Vec::from_array(
+            &env,
+            [
+                Address::generate(&env),
+                Address::generate(&env),
+                Address::generate(&env),
+            ],
+        );
+        assert!(governance.try_set_guardians(&guardians, &4).is_err());
+        governance.set_guardians(&guardians, &2);
+
+        let actions = Vec::from_array(&env, [fee_action(&env, &target.address, 250)]);
+        let vetoed = governance.create_action_proposal(&proposer, &actions);
+        let late = governance.create_action_proposal(&proposer, &actions);
+        governance.cast_vote(&vetoed, &proposer, &true);
+        governance.cast_vote(&late, &proposer, &true);
+
+        // Only queued proposals can be vetoed
+        assert!(governance
+            .try_veto_proposal(&guardians.get(0).unwrap(), &vetoed)
+            .is_err());
+
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + 604800);
+        governance.queue_proposal(&vetoed);
+        governance.queue_proposal(&late);
+
+        assert!(governance
+            .try_veto_proposal(&Address::generate(&env), &vetoed)
+            .is_err());
+        governance.veto_proposal(&guardians.get(0).unwrap(), &vetoed);
+        assert!(governance
+            .try_veto_proposal(&guardians.get(0).unwrap(), &vetoed)
+            .is_err());
+        assert_eq!(
+            governance.get_proposal_state(&vetoed),
+            ProposalStatus::Queued
+        );
+
+        governance.veto_proposal(&guardians.get(1).unwrap(), &vetoed);
+        let proposal = governance.get_proposal(&vetoed);
+        assert_eq!(proposal.vetoes, 2);
+        assert_eq!(proposal.status, ProposalStatus::Vetoed);
+
+        // Once the timelock elapsed the veto window is closed
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+        assert!(governance.try_execute_proposal(&vetoed).is_err());
+        assert!(governance
+            .try_veto_proposal(&guardians.get(2).unwrap(), &late)
+            .is_err());
+        governance.execute_proposal(&late);
+        assert_eq!(target.fee(), 250);
+    }
+
+    #[test]
+    fn test_emergency_proposal_fast_track() {
+        let env = Env::default();
+        let (governance, target, proposer) = setup_actions(&env);
+        let guardian = Address::generate(&env);
+        governance.set_guardians(&Vec::from_array(&env, [guardian.clone()]), &1);
+
+        let actions = Vec::from_array(&env, [fee_action(&env, &target.address, 500)]);
+        assert!(governance
+            .try_create_emergency_proposal(&proposer, &actions)
+            .is_err());
+        let proposal_id = governance.create_emergency_proposal(&guardian, &actions);
+
+        let proposal = governance.get_proposal(&proposal_id);
+        assert!(proposal.emergency);
+        assert_eq!(proposal.voting_end_ts, proposal.created_at + 86400);
+
+        // 30% support is above the regular quorum but below the emergency one
+        let voter = Address::generate(&env);
+        governance.set_voting_power(&voter, &30000);
+        governance.cast_vote(&proposal_id, &voter, &true);
+        assert_eq!(
+            governance.get_proposal_state(&proposal_id),
+            ProposalStatus::Active
+        );
+
+        governance.cast_vote(&proposal_id, &proposer, &true);
+        assert_eq!(
+            governance.get_proposal_state(&proposal_id),
+            ProposalStatus::Succeeded
+        );
+
+        // No timelock: executable in the same ledger
+        assert_eq!(
+            governance.queue_proposal(&proposal_id),
+            env.ledger().timestamp()
+        );
+        governance.execute_proposal(&proposal_id);
+        assert_eq!(target.fee(), 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #28)")]
+    fn test_emergency_proposal_cannot_upgrade() {
+        let env = Env::default();
+        let (governance, target, _proposer) = setup_actions(&env);
+        let guardian = Address::generate(&env);
+        governance.set_guardians(&Vec::from_array(&env, [guardian.clone()]), &1);
+        governance.set_allowed_function(&target.address, &symbol_short!("upgrade"), &true);
+
+        let actions = Vec::from_array(
+            &env,
+            [ProposalAction {
+                target: target.address.clone(),
+                function: symbol_short!("upgrade"),
+                args: Vec::from_array(&env, [BytesN::from_array(&env, &[0u8; 32]).into_val(&env)]),
+            }],
+        );
+        governance.create_emergency_proposal(&guardian, &actions);
+    }
+
+    #[test]
+    fn test_self_migration_requires_supported_version() {
+        let env = Env::default();
+        let (governance, _target, proposer) = setup_actions(&env);
+        governance.set_allowed_function(&governance.address, &symbol_short!("migrate"), &true);
+        assert_eq!(governance.version(), 1);
+
+        let actions = Vec::from_array(
+            &env,
+            [ProposalAction {
+                target: governance.address.clone(),
+                function: symbol_short!("migrate"),
+                args: Vec::from_array(&env, [2u32.into_val(&env)]),
+            }],
+        );
+        let proposal_id = governance.create_action_proposal(&proposer, &actions);
+        governance.cast_vote(&proposal_id, &proposer, &true);
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + 604800 + 1);
+        governance.queue_proposal(&proposal_id);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+
+        // This code only knows version 1, so the migration is rejected
+        env.as_contract(&governance.address, || {
+            assert_eq!(
+                Governance::execute_proposal(env.clone(), proposal_id),
+                Err(ContractError::InvalidVersion)
+            );
+        });
+        assert_eq!(governance.version(), 1);
+    }
 }
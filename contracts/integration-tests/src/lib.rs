@@ -0,0 +1,7 @@
+//! Cross-contract integration tests for StelloVault
+//!
+//! This crate has no code of its own. The tests under `tests/` deploy the
+//! production contracts side by side in one Soroban environment and exercise
+//! the calls they make to each other.
+
+#![no_std]
@@ -0,0 +1,211 @@
+//! Shared deployment for the integration tests.
+
+#![allow(dead_code)]
+
+use collateral_registry::{CollateralRegistry, CollateralRegistryClient};
+use escrow_manager::{EscrowConfig, EscrowManager, EscrowManagerClient};
+use lending_pool::{LendingPool, LendingPoolClient};
+use loan_management::{LoanManagement, LoanManagementClient};
+use oracle_adapter::{OracleAdapter, OracleAdapterClient};
+use risk_assessment::{RiskAssessment, RiskAssessmentClient};
+use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, Vec};
+
+/// Core contracts deployed and wired to each other.
+pub struct Protocol<'a> {
+    pub env: Env,
+    pub admin: Address,
+    pub governance: Address,
+    pub valuation_oracle: Address,
+    pub token: Address,
+    pub collateral: CollateralRegistryClient<'a>,
+    pub escrow: EscrowManagerClient<'a>,
+    pub oracle: OracleAdapterClient<'a>,
+    pub loans: LoanManagementClient<'a>,
+    pub risk: RiskAssessmentClient<'a>,
+}
+
+pub fn deploy() -> Protocol<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let governance = Address::generate(&env);
+    let valuation_oracle = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+
+    let coll_addr = env.register(CollateralRegistry, ());
+    let escrow_addr = env.register(EscrowManager, ());
+    let oracle_addr = env.register(OracleAdapter, ());
+    let loans_addr = env.register(LoanManagement, ());
+    let risk_addr = env.register(RiskAssessment, ());
+
+    let collateral = CollateralRegistryClient::new(&env, &coll_addr);
+    let escrow = EscrowManagerClient::new(&env, &escrow_addr);
+    let oracle = OracleAdapterClient::new(&env, &oracle_addr);
+    let loans = LoanManagementClient::new(&env, &loans_addr);
+    let risk = RiskAssessmentClient::new(&env, &risk_addr);
+
+    collateral.initialize(&admin);
+    collateral.set_escrow_manager(&escrow_addr);
+    collateral.set_valuation_oracle(&valuation_oracle);
+    collateral.set_liquidation_engine(&risk_addr);
+
+    escrow.initialize(&admin, &coll_addr, &oracle_addr, &loans_addr, &treasury);
+
+    // Required for releases: the escrow manager marks confirmations consumed
+    oracle.initialize(&admin);
+    oracle.set_consumers(&Vec::from_array(&env, [escrow_addr.clone()]));
+
+    loans.initialize(&admin);
+    loans.update_total_liquidity(&1_000_000);
+    loans.set_loan_asset(&token);
+    loans.set_risk_engine(&risk_addr);
+
+    risk.initialize(&admin, &governance, &coll_addr, &loans_addr, &escrow_addr);
+
+    // Leak lifetimes for test convenience
+    let collateral = unsafe {
+        core::mem::transmute::<CollateralRegistryClient<'_>, CollateralRegistryClient<'static>>(
+            collateral,
+        )
+    };
+    let escrow = unsafe {
+        core::mem::transmute::<EscrowManagerClient<'_>, EscrowManagerClient<'static>>(escrow)
+    };
+    let oracle = unsafe {
+        core::mem::transmute::<OracleAdapterClient<'_>, OracleAdapterClient<'static>>(oracle)
+    };
+    let loans = unsafe {
+        core::mem::transmute::<LoanManagementClient<'_>, LoanManagementClient<'static>>(loans)
+    };
+    let risk = unsafe {
+        core::mem::transmute::<RiskAssessmentClient<'_>, RiskAssessmentClient<'static>>(risk)
+    };
+
+    Protocol {
+        env,
+        admin,
+        governance,
+        valuation_oracle,
+        token,
+        collateral,
+        escrow,
+        oracle,
+        loans,
+        risk,
+    }
+}
+
+/// Parties and identifiers of one financed trade.
+pub struct Position {
+    pub borrower: Address,
+    pub lender: Address,
+    pub seller: Address,
+    pub collateral_id: u64,
+    pub escrow_id: u64,
+    pub loan_id: u64,
+    pub interest_rate: u32,
+}
+
+/// Register collateral, escrow `amount` against it and issue a matching loan.
+pub fn open_position(p: &Protocol, face_value: i128, amount: i128, seed: u8) -> Position {
+    let (borrower, lender, seller, collateral_id, escrow_id) =
+        open_trade(p, face_value, amount, seed);
+
+    let (loan_id, interest_rate) =
+        p.loans
+            .issue_loan(&escrow_id, &borrower, &lender, &amount, &(86_400 * 30));
+
+    Position {
+        borrower,
+        lender,
+        seller,
+        collateral_id,
+        escrow_id,
+        loan_id,
+        interest_rate,
+    }
+}
+
+/// Deploy a lending pool for the protocol's loan asset and register it.
+pub fn deploy_pool(p: &Protocol) -> LendingPoolClient<'static> {
+    let pool_addr = p.env.register(LendingPool, ());
+    let pool = LendingPoolClient::new(&p.env, &pool_addr);
+    pool.initialize(&p.admin, &p.token, &p.loans.address);
+    p.loans.set_lending_pool(&pool_addr);
+    p.loans.set_escrow_manager(&p.escrow.address);
+
+    unsafe { core::mem::transmute::<LendingPoolClient<'_>, LendingPoolClient<'static>>(pool) }
+}
+
+/// Mint `amount` to a new lender and deposit it into the pool.
+pub fn fund_pool(p: &Protocol, pool: &LendingPoolClient, amount: i128) -> Address {
+    let lender = Address::generate(&p.env);
+    token::StellarAssetClient::new(&p.env, &p.token).mint(&lender, &amount);
+    pool.deposit(&lender, &amount);
+    lender
+}
+
+/// Like [`open_position`], but the loan is drawn from the registered pool.
+pub fn open_pool_position(p: &Protocol, face_value: i128, amount: i128, seed: u8) -> Position {
+    let (borrower, _, seller, collateral_id, escrow_id) = open_trade(p, face_value, amount, seed);
+
+    let (loan_id, interest_rate) =
+        p.loans
+            .issue_pool_loan(&escrow_id, &borrower, &amount, &(86_400 * 30));
+
+    Position {
+        borrower,
+        lender: p.loans.get_lending_pool().unwrap(),
+        seller,
+        collateral_id,
+        escrow_id,
+        loan_id,
+        interest_rate,
+    }
+}
+
+/// Register collateral and escrow `amount` against it, funded by a trade lender.
+pub fn open_trade(
+    p: &Protocol,
+    face_value: i128,
+    amount: i128,
+    seed: u8,
+) -> (Address, Address, Address, u64, u64) {
+    let env = &p.env;
+    let borrower = Address::generate(env);
+    let lender = Address::generate(env);
+    let seller = Address::generate(env);
+
+    token::StellarAssetClient::new(env, &p.token).mint(&lender, &amount);
+
+    let collateral_id = p.collateral.register_collateral(
+        &borrower,
+        &face_value,
+        &(env.ledger().timestamp() + 86_400 * 90),
+        &BytesN::from_array(env, &[seed; 32]),
+        &0,
+    );
+
+    let escrow_id = p.escrow.create_escrow(&EscrowConfig {
+        buyer: borrower.clone(),
+        seller: seller.clone(),
+        lender: lender.clone(),
+        collateral_id,
+        amount,
+        asset: p.token.clone(),
+        required_confirmation: 2,
+        expiry_ts: env.ledger().timestamp() + 86_400 * 30,
+        destination_asset: p.token.clone(),
+        min_destination_amount: amount,
+        required_confirmations: 0,
+        oracle_set: Vec::new(env),
+    });
+
+    (borrower, lender, seller, collateral_id, escrow_id)
+}
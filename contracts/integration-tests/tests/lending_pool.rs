@@ -0,0 +1,138 @@
+//! Pool-funded loans: LendingPool liquidity backing LoanManagement
+//! issuance, repayment, utilization and liquidation.
+
+mod common;
+
+use common::{deploy, deploy_pool, fund_pool, open_pool_position, open_trade};
+use loan_management::LoanStatus;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, token, Address};
+
+#[test]
+fn test_pool_loan_draws_from_pool() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    fund_pool(&p, &pool, 100_000);
+
+    let pos = open_pool_position(&p, 80_000, 40_000, 1);
+
+    let tok = token::Client::new(&p.env, &p.token);
+    assert_eq!(tok.balance(&pos.borrower), 40_000);
+
+    let loan = p.loans.get_loan(&pos.loan_id).unwrap();
+    assert_eq!(loan.lender, pool.address);
+
+    let stats = pool.get_pool_stats();
+    assert_eq!(stats.available, 60_000);
+    assert_eq!(stats.total_borrowed, 40_000);
+
+    // Utilization now reflects the pool rather than the admin-set liquidity
+    assert_eq!(p.loans.get_utilization_stats(), (100_000, 40_000, 4000));
+}
+
+#[test]
+fn test_repayment_with_interest_accrues_to_shares() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    let lender = fund_pool(&p, &pool, 100_000);
+    let pos = open_pool_position(&p, 80_000, 40_000, 2);
+
+    p.env.ledger().with_mut(|li| li.timestamp += 86_400 * 15);
+    let due = p.loans.get_total_due(&pos.loan_id);
+    let interest = due - 40_000;
+    assert!(interest > 0);
+
+    token::StellarAssetClient::new(&p.env, &p.token).mint(&pos.borrower, &interest);
+    p.loans.repay_loan(&pos.loan_id, &due);
+
+    assert_eq!(
+        p.loans.get_loan(&pos.loan_id).unwrap().status,
+        LoanStatus::Repaid
+    );
+
+    let stats = pool.get_pool_stats();
+    assert_eq!(stats.total_borrowed, 0);
+    assert_eq!(stats.total_assets, 100_000 + interest);
+
+    // The only lender redeems every share for principal plus interest
+    assert_eq!(pool.withdraw(&lender, &100_000), 100_000 + interest);
+}
+
+#[test]
+fn test_rate_follows_pool_utilization() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    fund_pool(&p, &pool, 100_000);
+    let borrower = Address::generate(&p.env);
+
+    let idle_rate = p.loans.get_dynamic_rate(&borrower, &10_000);
+    open_pool_position(&p, 160_000, 80_000, 3);
+    let busy_rate = p.loans.get_dynamic_rate(&borrower, &10_000);
+
+    assert!(busy_rate > idle_rate);
+}
+
+#[test]
+fn test_pool_loan_cannot_exceed_pool_cash() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    fund_pool(&p, &pool, 10_000);
+    let borrower = Address::generate(&p.env);
+
+    assert!(p
+        .loans
+        .try_issue_pool_loan(&1, &borrower, &10_001, &86_400)
+        .is_err());
+}
+
+#[test]
+fn test_pool_loan_requires_escrow_party() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    fund_pool(&p, &pool, 100_000);
+    let (borrower, _, _, _, escrow_id) = open_trade(&p, 80_000, 40_000, 5);
+
+    // A stranger cannot draw against someone else's escrow
+    let stranger = Address::generate(&p.env);
+    assert!(p
+        .loans
+        .try_issue_pool_loan(&escrow_id, &stranger, &40_000, &86_400)
+        .is_err());
+
+    // Nor can the borrower draw more than the escrow's LTV-capped amount
+    assert!(p
+        .loans
+        .try_issue_pool_loan(&escrow_id, &borrower, &40_001, &86_400)
+        .is_err());
+    assert_eq!(pool.get_pool_stats().available, 100_000);
+}
+
+#[test]
+fn test_liquidated_pool_loan_settles_into_pool() {
+    let p = deploy();
+    let pool = deploy_pool(&p);
+    fund_pool(&p, &pool, 100_000);
+    let pos = open_pool_position(&p, 80_000, 40_000, 4);
+
+    let loan = p.loans.get_loan(&pos.loan_id).unwrap();
+    p.env
+        .ledger()
+        .with_mut(|li| li.timestamp = loan.deadline + 1);
+    let debt = p.loans.get_total_due(&pos.loan_id);
+    p.loans.mark_default(&pos.loan_id);
+
+    let bidder = Address::generate(&p.env);
+    let price = p.risk.get_auction_price(&pos.escrow_id);
+    token::StellarAssetClient::new(&p.env, &p.token).mint(&bidder, &price);
+    p.risk.bid_auction(&pos.escrow_id, &bidder, &price);
+
+    assert_eq!(
+        p.loans.get_loan(&pos.loan_id).unwrap().status,
+        LoanStatus::Liquidated
+    );
+
+    // Auction proceeds replace the written-off principal
+    let stats = pool.get_pool_stats();
+    assert_eq!(stats.total_borrowed, 0);
+    assert_eq!(stats.available, 60_000 + debt);
+    assert_eq!(stats.total_assets, 60_000 + debt);
+}
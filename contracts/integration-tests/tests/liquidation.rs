@@ -0,0 +1,124 @@
+//! Default → Dutch auction → settlement across LoanManagement,
+//! RiskAssessment and CollateralRegistry.
+
+mod common;
+
+use common::{deploy, open_position, Position, Protocol};
+use loan_management::LoanStatus;
+use risk_assessment::AuctionStatus;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, token, Address};
+
+fn default_position(p: &Protocol, pos: &Position) -> i128 {
+    let loan = p.loans.get_loan(&pos.loan_id).unwrap();
+    p.env
+        .ledger()
+        .with_mut(|li| li.timestamp = loan.deadline + 1);
+
+    let debt = p.loans.get_total_due(&pos.loan_id);
+    p.loans.mark_default(&pos.loan_id);
+    debt
+}
+
+#[test]
+fn test_mark_default_opens_auction() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 1);
+
+    let debt = default_position(&p, &pos);
+
+    assert_eq!(
+        p.loans.get_loan(&pos.loan_id).unwrap().status,
+        LoanStatus::Defaulted
+    );
+
+    // The auction is keyed by position (escrow) and floors at the debt at default
+    let auction = p.risk.get_auction(&pos.escrow_id).unwrap();
+    assert_eq!(auction.status, AuctionStatus::Active);
+    assert_eq!(auction.collateral_value, 10_000);
+    assert_eq!(auction.debt_floor, debt);
+    assert!(debt > 5_000);
+}
+
+#[test]
+fn test_auction_settles_loan_collateral_and_surplus() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 2);
+    let debt = default_position(&p, &pos);
+
+    let bidder = Address::generate(&p.env);
+    let price = p.risk.get_auction_price(&pos.escrow_id);
+    token::StellarAssetClient::new(&p.env, &p.token).mint(&bidder, &price);
+
+    let state = p.risk.bid_auction(&pos.escrow_id, &bidder, &price);
+    assert_eq!(state.status, AuctionStatus::Settled);
+    assert_eq!(state.winner, Some(bidder.clone()));
+
+    // Loan closed as liquidated
+    assert_eq!(
+        p.loans.get_loan(&pos.loan_id).unwrap().status,
+        LoanStatus::Liquidated
+    );
+
+    // Collateral handed to the winner and released
+    let collateral = p.collateral.get_collateral(&pos.collateral_id).unwrap();
+    assert_eq!(collateral.owner, bidder);
+    assert!(!collateral.locked);
+
+    // Lender made whole, borrower receives the surplus after the auction fee
+    let tok = token::Client::new(&p.env, &p.token);
+    assert_eq!(tok.balance(&pos.lender), debt);
+    assert_eq!(state.debt_covered, debt);
+    assert_eq!(state.borrower_surplus, price - debt - state.auction_fee);
+    assert_eq!(tok.balance(&pos.borrower), state.borrower_surplus);
+}
+
+#[test]
+fn test_second_bid_rejected_after_settlement() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 3);
+    default_position(&p, &pos);
+
+    let bidder = Address::generate(&p.env);
+    let price = p.risk.get_auction_price(&pos.escrow_id);
+    token::StellarAssetClient::new(&p.env, &p.token).mint(&bidder, &(price * 2));
+    p.risk.bid_auction(&pos.escrow_id, &bidder, &price);
+
+    assert!(p
+        .risk
+        .try_bid_auction(&pos.escrow_id, &bidder, &price)
+        .is_err());
+}
+
+#[test]
+fn test_mark_default_without_risk_engine_only_flags_loan() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 4);
+
+    // A loan manager with no risk engine registered
+    let fresh = p.env.register(loan_management::LoanManagement, ());
+    let loans = loan_management::LoanManagementClient::new(&p.env, &fresh);
+    loans.initialize(&p.admin);
+    loans.update_total_liquidity(&100_000);
+    let (loan_id, _) = loans.issue_loan(&pos.escrow_id, &pos.borrower, &pos.lender, &1_000, &60);
+
+    p.env.ledger().with_mut(|li| li.timestamp += 61);
+    loans.mark_default(&loan_id);
+
+    assert_eq!(
+        loans.get_loan(&loan_id).unwrap().status,
+        LoanStatus::Defaulted
+    );
+    assert!(p.risk.get_auction(&pos.escrow_id).is_none());
+}
+
+#[test]
+fn test_default_auction_only_from_loan_manager() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 5);
+
+    p.env.mock_auths(&[]);
+    assert!(p
+        .risk
+        .try_start_default_auction(&pos.escrow_id, &1)
+        .is_err());
+}
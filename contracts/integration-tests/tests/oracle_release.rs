@@ -0,0 +1,109 @@
+//! Escrow releases driven by signed OracleAdapter confirmations, including
+//! the consumed flag EscrowManager sets on the confirmations it used.
+
+mod common;
+
+use common::{deploy, open_trade, Protocol};
+use ed25519_dalek::{Signer, SigningKey};
+use escrow_manager::EscrowStatus;
+use soroban_sdk::{
+    contract, contractimpl, testutils::Address as _, token, xdr::ToXdr, Address, Bytes, BytesN,
+    Env, Vec,
+};
+
+/// Treasury stand-in charging no protocol fee
+#[contract]
+pub struct ZeroFeeTreasury;
+
+#[contractimpl]
+impl ZeroFeeTreasury {
+    pub fn get_fee_bps(_env: Env) -> u32 {
+        0
+    }
+}
+
+/// Register an oracle with the adapter and return it with its signing key
+fn add_oracle(p: &Protocol, seed: u8) -> (Address, SigningKey) {
+    let oracle = Address::generate(&p.env);
+    let key = SigningKey::from_bytes(&[seed; 32]);
+    p.oracle.add_oracle(
+        &oracle,
+        &BytesN::from_array(&p.env, &key.verifying_key().to_bytes()),
+    );
+    (oracle, key)
+}
+
+/// Sign and submit the oracle's confirmation of `event_type` for an escrow
+fn confirm(p: &Protocol, oracle: &Address, key: &SigningKey, escrow_id: u64, event_type: u32) {
+    let env = &p.env;
+    let escrow_bytes = Bytes::from_slice(env, &escrow_id.to_be_bytes());
+    let result = Bytes::from_slice(env, b"delivered");
+    let nonce = p.oracle.get_nonce(oracle);
+    let expiry = env.ledger().timestamp() + 3600;
+
+    // Same layout as OracleAdapter::create_message
+    let mut message = p.oracle.address.clone().to_xdr(env);
+    message.append(&Bytes::from_slice(env, &escrow_bytes.len().to_be_bytes()));
+    message.append(&escrow_bytes);
+    message.append(&Bytes::from_slice(env, &event_type.to_be_bytes()));
+    message.append(&Bytes::from_slice(env, &nonce.to_be_bytes()));
+    message.append(&Bytes::from_slice(env, &expiry.to_be_bytes()));
+    message.append(&result);
+    let digest = env.crypto().sha256(&message).to_array();
+    let signature = BytesN::from_array(env, &key.sign(&digest).to_bytes());
+
+    p.oracle.confirm_event(
+        oracle,
+        &escrow_bytes,
+        &event_type,
+        &result,
+        &nonce,
+        &expiry,
+        &signature,
+    );
+}
+
+#[test]
+fn test_release_consumes_confirmation() {
+    let p = deploy();
+    p.escrow.set_treasury(&p.env.register(ZeroFeeTreasury, ()));
+    let (oracle, key) = add_oracle(&p, 1);
+    let (_, _, seller, _, escrow_id) = open_trade(&p, 80_000, 40_000, 1);
+
+    confirm(&p, &oracle, &key, escrow_id, 2);
+    p.escrow.release_funds_on_confirmation(&escrow_id);
+
+    assert_eq!(
+        p.escrow.get_escrow(&escrow_id).unwrap().status,
+        EscrowStatus::Released
+    );
+    let tok = token::Client::new(&p.env, &p.token);
+    assert_eq!(tok.balance(&seller), 40_000);
+
+    // The confirmation the release relied on can no longer be revoked
+    let escrow_bytes = Bytes::from_slice(&p.env, &escrow_id.to_be_bytes());
+    assert!(p
+        .oracle
+        .try_revoke_confirmation(&oracle, &escrow_bytes, &2)
+        .is_err());
+}
+
+#[test]
+fn test_release_requires_escrow_manager_as_consumer() {
+    let p = deploy();
+    p.escrow.set_treasury(&p.env.register(ZeroFeeTreasury, ()));
+    let (oracle, key) = add_oracle(&p, 2);
+    let (_, _, _, _, escrow_id) = open_trade(&p, 80_000, 40_000, 2);
+    confirm(&p, &oracle, &key, escrow_id, 2);
+
+    // Without the setup step the adapter refuses to mark the confirmation
+    p.oracle.set_consumers(&Vec::new(&p.env));
+    assert!(p
+        .escrow
+        .try_release_funds_on_confirmation(&escrow_id)
+        .is_err());
+    assert_eq!(
+        p.escrow.get_escrow(&escrow_id).unwrap().status,
+        EscrowStatus::Active
+    );
+}
@@ -0,0 +1,99 @@
+//! RiskAssessment reading positions from the live LoanManagement,
+//! CollateralRegistry and EscrowManager contracts.
+
+mod common;
+
+use common::{deploy, open_position};
+use loan_management::LoanStatus;
+use risk_assessment::PositionRisk;
+
+#[test]
+fn test_position_data_loaded_from_contracts() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 1);
+
+    let data = p.risk.get_position_data(&pos.escrow_id);
+    assert_eq!(data.escrow_id, pos.escrow_id);
+    assert_eq!(data.loan_id, pos.loan_id);
+    assert_eq!(data.collateral_id, pos.collateral_id);
+    assert_eq!(data.borrower, pos.borrower);
+    assert_eq!(data.lender, pos.lender);
+    assert_eq!(data.collateral_value, 10_000);
+    assert_eq!(data.interest_rate, pos.interest_rate);
+
+    let interest = 5_000 * pos.interest_rate as i128 / 10_000;
+    assert_eq!(data.debt_amount, 5_000 + interest);
+
+    let loan = p.loans.get_loan(&pos.loan_id).unwrap();
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(data.deadline, loan.deadline);
+}
+
+#[test]
+fn test_health_factor_uses_registry_valuation() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 2);
+
+    let params = p.risk.get_risk_parameters();
+    let debt = 5_000 + 5_000 * pos.interest_rate as i128 / 10_000;
+    let expected = (10_000 * params.liquidation_threshold as i128 / debt) as u32;
+    assert_eq!(p.risk.calculate_health_factor(&pos.escrow_id), expected);
+    assert_eq!(
+        p.risk.get_position_risk(&pos.escrow_id),
+        PositionRisk::Healthy
+    );
+    assert!(!p.risk.is_liquidatable(&pos.escrow_id));
+
+    // A valuation drop in the registry is picked up on the next read
+    p.collateral.update_valuation(&pos.collateral_id, &4_000);
+    assert_eq!(
+        p.risk.get_position_risk(&pos.escrow_id),
+        PositionRisk::Liquidatable
+    );
+    assert!(p.risk.is_liquidatable(&pos.escrow_id));
+}
+
+#[test]
+fn test_positions_are_independent() {
+    let p = deploy();
+    let healthy = open_position(&p, 20_000, 5_000, 3);
+    let risky = open_position(&p, 10_000, 5_000, 4);
+
+    // Collateral revalued below the debt after the escrow was opened
+    p.collateral.update_valuation(&risky.collateral_id, &6_000);
+
+    assert_eq!(
+        p.risk
+            .get_position_data(&healthy.escrow_id)
+            .collateral_value,
+        20_000
+    );
+    assert!(!p.risk.is_liquidatable(&healthy.escrow_id));
+    assert!(p.risk.is_liquidatable(&risky.escrow_id));
+}
+
+#[test]
+fn test_start_auction_on_live_position() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 5);
+    p.collateral.update_valuation(&pos.collateral_id, &4_000);
+
+    let auction = p.risk.start_auction(&pos.escrow_id);
+    assert_eq!(auction.collateral_value, 4_000);
+    assert_eq!(
+        p.loans.get_loan(&pos.loan_id).unwrap().status,
+        LoanStatus::Active
+    );
+}
+
+#[test]
+fn test_escrow_without_loan_is_not_a_position() {
+    let p = deploy();
+    let pos = open_position(&p, 10_000, 5_000, 6);
+
+    assert!(p.risk.try_calculate_health_factor(&pos.escrow_id).is_ok());
+    assert!(p
+        .risk
+        .try_calculate_health_factor(&(pos.escrow_id + 100))
+        .is_err());
+}
@@ -0,0 +1,70 @@
+//! Contract upgrades carried out by an executed governance proposal.
+
+mod common;
+
+use common::deploy;
+use governance::{Governance, GovernanceClient, ProposalAction, ProposalStatus};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, Address, Bytes, IntoVal, Vec,
+};
+
+/// Smallest module the host accepts as contract code: the Wasm header and a
+/// 30-byte `contractenvmetav0` custom section whose single entry declares
+/// protocol 20 (no pre-release) as the interface version. It exports nothing.
+const EMPTY_CONTRACT_WASM: &[u8] = b"\0asm\x01\0\0\0\
+    \0\x1e\x11contractenvmetav0\
+    \0\0\0\0\0\0\0\x14\0\0\0\0";
+
+#[test]
+fn test_governance_proposal_upgrades_escrow_manager() {
+    let p = deploy();
+    let env = &p.env;
+
+    let governance = GovernanceClient::new(env, &env.register(Governance, ()));
+    governance.initialize(&p.admin, &p.token, &p.risk.address);
+    governance.set_allowed_function(&p.escrow.address, &symbol_short!("upgrade"), &true);
+    p.escrow.set_governance(&governance.address);
+
+    let voter = Address::generate(env);
+    token::StellarAssetClient::new(env, &p.token).mint(&voter, &10_000);
+    governance.deposit(&voter, &10_000);
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(Bytes::from_slice(env, EMPTY_CONTRACT_WASM));
+    let actions = Vec::from_array(
+        env,
+        [ProposalAction {
+            target: p.escrow.address.clone(),
+            function: symbol_short!("upgrade"),
+            args: Vec::from_array(env, [wasm_hash.into_val(env)]),
+        }],
+    );
+    let proposal_id = governance.create_action_proposal(&voter, &actions);
+    governance.cast_vote(&proposal_id, &voter, &true);
+
+    let config = governance.get_config();
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + config.voting_period + 1);
+    governance.queue_proposal(&proposal_id);
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + config.timelock_period);
+
+    // From here on only real authorizations count: the admin cannot upgrade,
+    // while governance's own call authorizes it as the direct invoker
+    env.set_auths(&[]);
+    assert!(p.escrow.try_upgrade(&wasm_hash).is_err());
+
+    governance.execute_proposal(&proposal_id);
+
+    assert!(env.events().all().iter().any(|(contract, topics, _)| {
+        contract == p.escrow.address && topics == (symbol_short!("upgraded"),).into_val(env)
+    }));
+    assert_eq!(
+        governance.get_proposal_state(&proposal_id),
+        ProposalStatus::Executed
+    );
+}
@@ -0,0 +1,616 @@
+//! Lending Pool Contract for StelloVault
+//!
+//! Lenders deposit the loan asset and receive pool shares. The loan
+//! management contract draws loans from the pool and routes repayments
+//! back into it, so interest accrues to share value. Pool balances are the
+//! source of truth for protocol liquidity and utilization.
+//!
+//! Idle cash is tracked in the pool's own books rather than read from its
+//! token balance, so tokens sent to the pool directly cannot move the share
+//! price.
+
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Unauthorized = 1,
+    AlreadyInitialized = 2,
+    ZeroAmount = 3,
+    InsufficientShares = 4,
+    InsufficientLiquidity = 5,
+    MathOverflow = 6,
+    PoolInsolvent = 7,
+}
+
+impl From<soroban_sdk::Error> for ContractError {
+    fn from(_: soroban_sdk::Error) -> Self {
+        ContractError::Unauthorized
+    }
+}
+
+impl From<&ContractError> for soroban_sdk::Error {
+    fn from(err: &ContractError) -> Self {
+        soroban_sdk::Error::from_contract_error(*err as u32)
+    }
+}
+
+/// Snapshot of pool balances
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolStats {
+    /// Idle cash plus principal currently lent out
+    pub total_assets: i128,
+    /// Principal currently lent out
+    pub total_borrowed: i128,
+    /// Idle cash that can be borrowed or withdrawn
+    pub available: i128,
+    pub total_shares: i128,
+}
+
+#[contract]
+pub struct LendingPool;
+
+#[contractimpl]
+impl LendingPool {
+    /// Initialize the pool
+    ///
+    /// # Arguments
+    /// * `admin` - Administrator address
+    /// * `asset` - Token lent out by the pool
+    /// * `loan_manager` - Loan management contract allowed to draw funds
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        asset: Address,
+        loan_manager: Address,
+    ) -> Result<(), ContractError> {
+        if env.storage().instance().has(&symbol_short!("admin")) {
+            return Err(ContractError::AlreadyInitialized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("asset"), &asset);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("loan_mgr"), &loan_manager);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("tot_shr"), &0i128);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("borrowed"), &0i128);
+        env.storage().instance().set(&symbol_short!("cash"), &0i128);
+
+        env.events()
+            .publish((symbol_short!("pool_init"),), (admin, asset, loan_manager));
+
+        Ok(())
+    }
+
+    /// Deposit the pool asset and receive shares
+    ///
+    /// Shares are minted at the current share price so existing lenders keep
+    /// the interest accrued so far.
+    ///
+    /// # Returns
+    /// Number of shares minted
+    pub fn deposit(env: Env, lender: Address, amount: i128) -> Result<i128, ContractError> {
+        lender.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let stats = Self::get_pool_stats(env.clone())?;
+        let shares = if stats.total_shares == 0 {
+            amount
+        } else {
+            if stats.total_assets == 0 {
+                return Err(ContractError::PoolInsolvent);
+            }
+            amount
+                .checked_mul(stats.total_shares)
+                .ok_or(ContractError::MathOverflow)?
+                / stats.total_assets
+        };
+
+        if shares <= 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let asset = Self::asset(&env)?;
+        token::Client::new(&env, &asset).transfer(
+            &lender,
+            &env.current_contract_address(),
+            &amount,
+        );
+        Self::set_cash(&env, stats.available + amount);
+
+        Self::set_balance(
+            &env,
+            &lender,
+            Self::balance(env.clone(), lender.clone()) + shares,
+        );
+        env.storage()
+            .instance()
+            .set(&symbol_short!("tot_shr"), &(stats.total_shares + shares));
+
+        env.events()
+            .publish((symbol_short!("pool_dep"),), (lender, amount, shares));
+
+        Ok(shares)
+    }
+
+    /// Burn shares and withdraw their value in the pool asset
+    ///
+    /// Only idle cash can be withdrawn; funds lent out return as loans are repaid.
+    ///
+    /// # Returns
+    /// Amount of the pool asset paid out
+    pub fn withdraw(env: Env, lender: Address, shares: i128) -> Result<i128, ContractError> {
+        lender.require_auth();
+
+        if shares <= 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let balance = Self::balance(env.clone(), lender.clone());
+        if balance < shares {
+            return Err(ContractError::InsufficientShares);
+        }
+
+        let stats = Self::get_pool_stats(env.clone())?;
+        let amount = shares
+            .checked_mul(stats.total_assets)
+            .ok_or(ContractError::MathOverflow)?
+            / stats.total_shares;
+
+        if amount > stats.available {
+            return Err(ContractError::InsufficientLiquidity);
+        }
+
+        Self::set_balance(&env, &lender, balance - shares);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("tot_shr"), &(stats.total_shares - shares));
+        Self::set_cash(&env, stats.available - amount);
+
+        if amount > 0 {
+            let asset = Self::asset(&env)?;
+            token::Client::new(&env, &asset).transfer(
+                &env.current_contract_address(),
+                &lender,
+                &amount,
+            );
+        }
+
+        env.events()
+            .publish((symbol_short!("pool_wd"),), (lender, amount, shares));
+
+        Ok(amount)
+    }
+
+    /// Transfer pool shares between accounts
+    pub fn transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let from_balance = Self::balance(env.clone(), from.clone());
+        if from_balance < amount {
+            return Err(ContractError::InsufficientShares);
+        }
+
+        Self::set_balance(&env, &from, from_balance - amount);
+        Self::set_balance(&env, &to, Self::balance(env.clone(), to.clone()) + amount);
+
+        env.events()
+            .publish((symbol_short!("shr_xfer"),), (from, to, amount));
+
+        Ok(())
+    }
+
+    /// Lend pool funds to a borrower (loan manager only)
+    pub fn draw(env: Env, borrower: Address, amount: i128) -> Result<(), ContractError> {
+        let loan_manager = Self::loan_manager(&env)?;
+        loan_manager.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let stats = Self::get_pool_stats(env.clone())?;
+        if amount > stats.available {
+            return Err(ContractError::InsufficientLiquidity);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("borrowed"), &(stats.total_borrowed + amount));
+        Self::set_cash(&env, stats.available - amount);
+
+        let asset = Self::asset(&env)?;
+        token::Client::new(&env, &asset).transfer(
+            &env.current_contract_address(),
+            &borrower,
+            &amount,
+        );
+
+        env.events()
+            .publish((symbol_short!("pool_drw"),), (borrower, amount));
+
+        Ok(())
+    }
+
+    /// Record a repayment already transferred into the pool (loan manager only)
+    ///
+    /// # Arguments
+    /// * `principal` - Principal retired by the repayment
+    /// * `interest` - Interest paid to the pool
+    pub fn record_repayment(
+        env: Env,
+        principal: i128,
+        interest: i128,
+    ) -> Result<(), ContractError> {
+        let loan_manager = Self::loan_manager(&env)?;
+        loan_manager.require_auth();
+
+        Self::reduce_borrowed(&env, principal);
+        Self::add_cash(&env, principal.max(0) + interest.max(0));
+
+        env.events()
+            .publish((symbol_short!("pool_rep"),), (principal, interest));
+
+        Ok(())
+    }
+
+    /// Remove principal of a liquidated loan from the pool's books (loan manager only)
+    ///
+    /// # Arguments
+    /// * `principal` - Principal still outstanding on the loan
+    /// * `recovered` - Auction proceeds already paid into the pool
+    ///
+    /// Any shortfall between the two is absorbed by share value.
+    pub fn write_off(env: Env, principal: i128, recovered: i128) -> Result<(), ContractError> {
+        let loan_manager = Self::loan_manager(&env)?;
+        loan_manager.require_auth();
+
+        Self::reduce_borrowed(&env, principal);
+        Self::add_cash(&env, recovered.max(0));
+
+        env.events()
+            .publish((symbol_short!("pool_wof"),), (principal, recovered));
+
+        Ok(())
+    }
+
+    /// Get current pool balances
+    pub fn get_pool_stats(env: Env) -> Result<PoolStats, ContractError> {
+        let available: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("cash"))
+            .unwrap_or(0);
+        let total_borrowed: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("borrowed"))
+            .unwrap_or(0);
+        let total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("tot_shr"))
+            .unwrap_or(0);
+
+        Ok(PoolStats {
+            total_assets: available + total_borrowed,
+            total_borrowed,
+            available,
+            total_shares,
+        })
+    }
+
+    /// Value of `shares` in the pool asset at the current share price
+    pub fn convert_to_assets(env: Env, shares: i128) -> Result<i128, ContractError> {
+        let stats = Self::get_pool_stats(env)?;
+        if stats.total_shares == 0 {
+            return Ok(shares);
+        }
+        Ok(shares
+            .checked_mul(stats.total_assets)
+            .ok_or(ContractError::MathOverflow)?
+            / stats.total_shares)
+    }
+
+    /// Share balance of an account
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("shares"), id))
+            .unwrap_or(0)
+    }
+
+    /// Total shares outstanding
+    pub fn total_shares(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("tot_shr"))
+            .unwrap_or(0)
+    }
+
+    /// Replace the loan management contract (admin only)
+    pub fn set_loan_manager(env: Env, loan_manager: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("loan_mgr"), &loan_manager);
+
+        env.events()
+            .publish((symbol_short!("lm_set"),), (loan_manager,));
+
+        Ok(())
+    }
+
+    /// Get the pool asset
+    pub fn get_asset(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("asset"))
+    }
+
+    fn asset(env: &Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("asset"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    fn loan_manager(env: &Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("loan_mgr"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    fn set_balance(env: &Env, id: &Address, shares: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("shares"), id.clone()), &shares);
+    }
+
+    fn set_cash(env: &Env, cash: i128) {
+        env.storage().instance().set(&symbol_short!("cash"), &cash);
+    }
+
+    fn add_cash(env: &Env, amount: i128) {
+        let cash: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("cash"))
+            .unwrap_or(0);
+        Self::set_cash(env, cash + amount);
+    }
+
+    fn reduce_borrowed(env: &Env, principal: i128) {
+        let borrowed: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("borrowed"))
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &symbol_short!("borrowed"),
+            &borrowed.saturating_sub(principal.max(0)).max(0),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    struct TestEnv {
+        env: Env,
+        client: LendingPoolClient<'static>,
+        token: token::Client<'static>,
+        token_admin: token::StellarAssetClient<'static>,
+    }
+
+    fn setup() -> TestEnv {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let loan_manager = Address::generate(&env);
+        let issuer = Address::generate(&env);
+        let asset = env.register_stellar_asset_contract_v2(issuer).address();
+
+        let contract_id = env.register(LendingPool, ());
+        let client = LendingPoolClient::new(&env, &contract_id);
+        client.initialize(&admin, &asset, &loan_manager);
+
+        let token = token::Client::new(&env, &asset);
+        let token_admin = token::StellarAssetClient::new(&env, &asset);
+
+        // Leak to get 'static lifetime for tests
+        let (client, token, token_admin) = unsafe {
+            (
+                core::mem::transmute::<LendingPoolClient<'_>, LendingPoolClient<'static>>(client),
+                core::mem::transmute::<token::Client<'_>, token::Client<'static>>(token),
+                core::mem::transmute::<
+                    token::StellarAssetClient<'_>,
+                    token::StellarAssetClient<'static>,
+                >(token_admin),
+            )
+        };
+
+        TestEnv {
+            env,
+            client,
+            token,
+            token_admin,
+        }
+    }
+
+    fn fund(t: &TestEnv, amount: i128) -> Address {
+        let lender = Address::generate(&t.env);
+        t.token_admin.mint(&lender, &amount);
+        lender
+    }
+
+    #[test]
+    fn test_first_deposit_mints_one_to_one() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+
+        assert_eq!(t.client.deposit(&lender, &1000), 1000);
+        assert_eq!(t.client.balance(&lender), 1000);
+        assert_eq!(t.client.total_shares(), 1000);
+
+        let stats = t.client.get_pool_stats();
+        assert_eq!(stats.total_assets, 1000);
+        assert_eq!(stats.available, 1000);
+        assert_eq!(stats.total_borrowed, 0);
+    }
+
+    #[test]
+    fn test_draw_and_repay_accrue_share_value() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        let borrower = Address::generate(&t.env);
+        t.client.deposit(&lender, &1000);
+
+        t.client.draw(&borrower, &600);
+        let stats = t.client.get_pool_stats();
+        assert_eq!(stats.available, 400);
+        assert_eq!(stats.total_borrowed, 600);
+        assert_eq!(stats.total_assets, 1000);
+
+        // Borrower repays principal plus 60 interest into the pool
+        t.token_admin.mint(&borrower, &60);
+        t.token.transfer(&borrower, &t.client.address, &660);
+        t.client.record_repayment(&600, &60);
+
+        let stats = t.client.get_pool_stats();
+        assert_eq!(stats.total_borrowed, 0);
+        assert_eq!(stats.total_assets, 1060);
+        assert_eq!(t.client.convert_to_assets(&1000), 1060);
+
+        // A later depositor buys in at the higher share price
+        let late = fund(&t, 530);
+        assert_eq!(t.client.deposit(&late, &530), 500);
+
+        assert_eq!(t.client.withdraw(&lender, &1000), 1060);
+        assert_eq!(t.token.balance(&lender), 1060);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #5)")]
+    fn test_withdraw_limited_to_idle_cash() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        t.client.deposit(&lender, &1000);
+        t.client.draw(&Address::generate(&t.env), &600);
+
+        t.client.withdraw(&lender, &500);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #5)")]
+    fn test_draw_exceeding_liquidity() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        t.client.deposit(&lender, &1000);
+
+        t.client.draw(&Address::generate(&t.env), &1001);
+    }
+
+    #[test]
+    fn test_write_off_reduces_share_value() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        t.client.deposit(&lender, &1000);
+        t.client.draw(&Address::generate(&t.env), &400);
+
+        t.client.write_off(&400, &0);
+
+        assert_eq!(t.client.get_pool_stats().total_assets, 600);
+        assert_eq!(t.client.convert_to_assets(&1000), 600);
+    }
+
+    #[test]
+    fn test_write_off_counts_recovered_proceeds() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        t.client.deposit(&lender, &1000);
+        let borrower = Address::generate(&t.env);
+        t.client.draw(&borrower, &400);
+
+        // Auction proceeds of 300 arrive before the write-off
+        t.token_admin.mint(&borrower, &300);
+        t.token.transfer(&borrower, &t.client.address, &300);
+        t.client.write_off(&400, &300);
+
+        let stats = t.client.get_pool_stats();
+        assert_eq!(stats.available, 900);
+        assert_eq!(stats.total_assets, 900);
+    }
+
+    #[test]
+    fn test_donation_does_not_move_share_price() {
+        let t = setup();
+        let attacker = fund(&t, 10_001);
+        t.client.deposit(&attacker, &1);
+
+        // Tokens sent straight to the pool are not counted as assets
+        t.token.transfer(&attacker, &t.client.address, &10_000);
+        assert_eq!(t.client.get_pool_stats().total_assets, 1);
+
+        let victim = fund(&t, 5000);
+        assert_eq!(t.client.deposit(&victim, &5000), 5000);
+        assert_eq!(t.client.withdraw(&victim, &5000), 5000);
+    }
+
+    #[test]
+    fn test_share_transfer() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        let other = Address::generate(&t.env);
+        t.client.deposit(&lender, &1000);
+
+        t.client.transfer(&lender, &other, &250);
+
+        assert_eq!(t.client.balance(&lender), 750);
+        assert_eq!(t.client.balance(&other), 250);
+        assert_eq!(t.client.withdraw(&other, &250), 250);
+    }
+
+    #[test]
+    fn test_only_loan_manager_can_draw() {
+        let t = setup();
+        let lender = fund(&t, 1000);
+        t.client.deposit(&lender, &1000);
+        let borrower = Address::generate(&t.env);
+
+        t.env.mock_auths(&[]);
+        assert!(t.client.try_draw(&borrower, &100).is_err());
+    }
+}
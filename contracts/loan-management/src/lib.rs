@@ -5,7 +5,13 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Val};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, IntoVal,
+    Symbol, Val, Vec,
+};
+
+/// Layout of loan and schedule records this code was written against
+const CONTRACT_VERSION: u32 = 1;
 
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -30,6 +36,15 @@ pub enum ContractError {
     InvalidRateParameters = 9,
     RiskEngineNotSet = 10,
     MathOverflow = 11,
+    AssetNotSet = 12,
+    InvalidSchedule = 13,
+    PoolNotSet = 14,
+    InsufficientLiquidity = 15,
+    EscrowManagerNotSet = 16,
+    EscrowNotFound = 17,
+    EscrowNotActive = 18,
+    ExceedsEscrowAmount = 19,
+    InvalidVersion = 20,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -95,6 +110,113 @@ pub struct Loan {
     pub last_repayment_ts: u64,
 }
 
+/// Lifecycle of a single installment
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InstallmentStatus {
+    Pending = 0,
+    Paid = 1,
+    /// Not fully paid within the grace period after its due date
+    Missed = 2,
+}
+
+/// One tranche of a loan's principal and the date it falls due
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InstallmentTerm {
+    pub due_ts: u64,
+    pub amount: i128,
+}
+
+/// Repayment progress of one scheduled installment
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Installment {
+    pub due_ts: u64,
+    /// Principal due in this installment
+    pub amount: i128,
+    pub paid: i128,
+    pub status: InstallmentStatus,
+}
+
+/// Late-payment rules applied to installment loans
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InstallmentPolicy {
+    /// Seconds after `due_ts` before an unpaid installment counts as missed
+    pub grace_period: u64,
+    /// Number of outstanding missed installments that puts the loan in default
+    pub max_missed: u32,
+}
+
+impl Default for InstallmentPolicy {
+    fn default() -> Self {
+        Self {
+            grace_period: 259_200, // 3 days
+            max_missed: 2,
+        }
+    }
+}
+
+/// Balances reported by the lending pool (mirror of `LendingPool::PoolStats`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolStats {
+    pub total_assets: i128,
+    pub total_borrowed: i128,
+    pub available: i128,
+    pub total_shares: i128,
+}
+
+/// Escrow status (mirror of `EscrowManager::EscrowStatus`)
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Active = 0,
+    Released = 1,
+    Refunded = 2,
+    Disputed = 3,
+    Resolved = 4,
+}
+
+/// `Escrow` exactly as returned by `EscrowManager::get_escrow`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowRecord {
+    pub id: u64,
+    pub buyer: Address,
+    pub seller: Address,
+    pub lender: Address,
+    pub collateral_id: u64,
+    pub amount: i128,
+    pub asset: Address,
+    pub required_confirmation: u32,
+    pub status: EscrowStatus,
+    pub expiry_ts: u64,
+    pub created_at: u64,
+    pub destination_asset: Address,
+    pub min_destination_amount: i128,
+    pub required_confirmations: u32,
+    pub oracle_set: Vec<Address>,
+}
+
+/// Outstanding balance and payoff projection for a loan
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RepaymentSchedule {
+    pub loan_id: u64,
+    pub status: LoanStatus,
+    pub principal_remaining: i128,
+    pub accrued_interest: i128,
+    /// Amount that closes the loan if paid now
+    pub payoff_amount: i128,
+    /// Amount that closes the loan if paid at the deadline
+    pub payoff_at_deadline: i128,
+    pub principal_repaid: i128,
+    pub interest_repaid: i128,
+    pub deadline: u64,
+}
+
 #[contract]
 pub struct LoanManagement;
 
@@ -201,28 +323,27 @@ impl LoanManagement {
         }
 
         let risk_contract = risk_engine.unwrap();
-        
+
         // For borrower-specific risk assessment, we need to find the borrower's position
         // Since RiskAssessment::get_position_risk requires a position_id (escrow_id),
         // we'll check if the borrower has any existing positions
         // For now, we'll use a simplified approach: query the borrower's overall risk
-        
+
         // Create arguments for the risk assessment call
         // We'll implement a borrower-specific risk function in RiskAssessment
-        let args: soroban_sdk::Vec<Val> = soroban_sdk::Vec::new(env);
-        args.push_back(borrower.clone().into());
-        
+        let args: Vec<Val> = Vec::from_array(env, [borrower.into_val(env)]);
+
         // Try to call get_borrower_risk_factor function on RiskAssessment
         // If that function doesn't exist, fall back to default
-        let risk_factor_result: Result<u32, soroban_sdk::Error> = env.try_invoke_contract(
+        let risk_factor_result = env.try_invoke_contract::<u32, soroban_sdk::Error>(
             &risk_contract,
             &Symbol::new(env, "get_borrower_risk_factor"),
             args,
         );
 
         match risk_factor_result {
-            Ok(risk_factor) => Ok(risk_factor),
-            Err(_) => {
+            Ok(Ok(risk_factor)) => Ok(risk_factor),
+            _ => {
                 // If the function doesn't exist or call fails, use default risk factor
                 // In a production environment, you might want to handle this differently
                 Ok(1) // Default to Warning level
@@ -234,17 +355,7 @@ impl LoanManagement {
     ///
     /// Utilization = (total_borrowed / total_liquidity) * 10000
     fn calculate_utilization(env: &Env, new_loan_amount: i128) -> Result<u32, ContractError> {
-        let total_liquidity: i128 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("tot_liq"))
-            .unwrap_or(0);
-
-        let total_borrowed: i128 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("tot_bor"))
-            .unwrap_or(0);
+        let (total_liquidity, total_borrowed) = Self::liquidity_and_borrowed(env);
 
         // If no liquidity, return 0 utilization
         if total_liquidity == 0 {
@@ -273,7 +384,39 @@ impl LoanManagement {
         Ok(utilization_u32)
     }
 
+    /// Total liquidity and borrowed principal used for utilization
+    ///
+    /// Read from the lending pool's balances when one is registered, otherwise
+    /// from the admin-maintained `tot_liq` and the loans tracked here.
+    fn liquidity_and_borrowed(env: &Env) -> (i128, i128) {
+        if let Some(pool) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("pool"))
+        {
+            let stats: PoolStats =
+                env.invoke_contract(&pool, &Symbol::new(env, "get_pool_stats"), Vec::new(env));
+            return (stats.total_assets, stats.total_borrowed);
+        }
+
+        let total_liquidity: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("tot_liq"))
+            .unwrap_or(0);
+
+        let total_borrowed: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("tot_bor"))
+            .unwrap_or(0);
+
+        (total_liquidity, total_borrowed)
+    }
+
     /// Update total liquidity (callable by admin or governance)
+    ///
+    /// Ignored for utilization once a lending pool is registered.
     pub fn update_total_liquidity(env: Env, new_liquidity: i128) -> Result<(), ContractError> {
         let admin: Address = env
             .storage()
@@ -343,17 +486,7 @@ impl LoanManagement {
 
     /// Get protocol utilization statistics
     pub fn get_utilization_stats(env: Env) -> (i128, i128, u32) {
-        let total_liquidity: i128 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("tot_liq"))
-            .unwrap_or(0);
-
-        let total_borrowed: i128 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("tot_bor"))
-            .unwrap_or(0);
+        let (total_liquidity, total_borrowed) = Self::liquidity_and_borrowed(&env);
 
         let utilization_bps = if total_liquidity > 0 {
             let util = (total_borrowed * 10000) / total_liquidity;
@@ -390,6 +523,162 @@ impl LoanManagement {
     ) -> Result<(u64, u32), ContractError> {
         lender.require_auth();
 
+        let deadline = env
+            .ledger()
+            .timestamp()
+            .checked_add(duration)
+            .ok_or(ContractError::MathOverflow)?;
+
+        Self::create_loan(&env, escrow_id, borrower, lender, amount, deadline)
+    }
+
+    /// Issue a new loan funded by the lending pool
+    ///
+    /// The pool is recorded as the loan's lender and transfers the principal
+    /// to the borrower; repayments flow back into the pool. The escrow must be
+    /// active in the registered EscrowManager with the borrower as its buyer
+    /// or seller, and the loan cannot exceed the escrowed amount, which
+    /// EscrowManager has already capped by the collateral's value and LTV.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - The unique identifier of the escrowed collateral
+    /// * `borrower` - Address of the borrower
+    /// * `amount` - Loan amount, at most the escrowed amount and the pool's idle cash
+    /// * `duration` - Duration in seconds
+    ///
+    /// # Returns
+    /// Loan ID and calculated interest rate
+    pub fn issue_pool_loan(
+        env: Env,
+        escrow_id: u64,
+        borrower: Address,
+        amount: i128,
+        duration: u64,
+    ) -> Result<(u64, u32), ContractError> {
+        borrower.require_auth();
+
+        let pool: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("pool"))
+            .ok_or(ContractError::PoolNotSet)?;
+
+        if amount <= 0 {
+            return Err(ContractError::InsufficientAmount);
+        }
+
+        let stats: PoolStats =
+            env.invoke_contract(&pool, &Symbol::new(&env, "get_pool_stats"), Vec::new(&env));
+        if amount > stats.available {
+            return Err(ContractError::InsufficientLiquidity);
+        }
+
+        let escrow = Self::fetch_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowNotActive);
+        }
+        if borrower != escrow.buyer && borrower != escrow.seller {
+            return Err(ContractError::Unauthorized);
+        }
+        if amount > escrow.amount {
+            return Err(ContractError::ExceedsEscrowAmount);
+        }
+
+        let deadline = env
+            .ledger()
+            .timestamp()
+            .checked_add(duration)
+            .ok_or(ContractError::MathOverflow)?;
+
+        let result = Self::create_loan(
+            &env,
+            escrow_id,
+            borrower.clone(),
+            pool.clone(),
+            amount,
+            deadline,
+        )?;
+
+        let draw_args: Vec<Val> =
+            Vec::from_array(&env, [borrower.into_val(&env), amount.into_val(&env)]);
+        env.invoke_contract::<()>(&pool, &Symbol::new(&env, "draw"), draw_args);
+
+        Ok(result)
+    }
+
+    /// Issue a new loan repaid in installments
+    ///
+    /// The loan amount is the sum of the installment amounts and its deadline
+    /// is the last due date plus the installment policy's grace period, so
+    /// the final installment gets the same grace as the others. Principal
+    /// repayments are applied to installments in due-date order.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - The unique identifier of the escrowed collateral
+    /// * `borrower` - Address of the borrower
+    /// * `lender` - Address of the lender
+    /// * `schedule` - Installments with strictly increasing future due dates
+    ///
+    /// # Returns
+    /// Loan ID and calculated interest rate
+    pub fn issue_loan_with_schedule(
+        env: Env,
+        escrow_id: u64,
+        borrower: Address,
+        lender: Address,
+        schedule: Vec<InstallmentTerm>,
+    ) -> Result<(u64, u32), ContractError> {
+        lender.require_auth();
+
+        if schedule.is_empty() {
+            return Err(ContractError::InvalidSchedule);
+        }
+
+        let mut installments: Vec<Installment> = Vec::new(&env);
+        let mut amount: i128 = 0;
+        let mut last_due = env.ledger().timestamp();
+        for term in schedule.iter() {
+            if term.amount <= 0 || term.due_ts <= last_due {
+                return Err(ContractError::InvalidSchedule);
+            }
+            amount = amount
+                .checked_add(term.amount)
+                .ok_or(ContractError::MathOverflow)?;
+            last_due = term.due_ts;
+            installments.push_back(Installment {
+                due_ts: term.due_ts,
+                amount: term.amount,
+                paid: 0,
+                status: InstallmentStatus::Pending,
+            });
+        }
+
+        let grace_period = Self::get_installment_policy(env.clone()).grace_period;
+        let deadline = last_due.saturating_add(grace_period);
+        let (loan_id, interest_rate) =
+            Self::create_loan(&env, escrow_id, borrower, lender, amount, deadline)?;
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("sched"), loan_id), &installments);
+
+        env.events().publish(
+            (symbol_short!("loan_sch"),),
+            (loan_id, installments.len(), last_due),
+        );
+
+        Ok((loan_id, interest_rate))
+    }
+
+    /// Shared bookkeeping for a newly issued loan
+    fn create_loan(
+        env: &Env,
+        escrow_id: u64,
+        borrower: Address,
+        lender: Address,
+        amount: i128,
+        deadline: u64,
+    ) -> Result<(u64, u32), ContractError> {
         // Prevent multiple loans per escrow
         let escrow_key = (symbol_short!("escrow"), escrow_id);
         if env.storage().persistent().has(&escrow_key) {
@@ -406,9 +695,6 @@ impl LoanManagement {
             .unwrap_or(1);
 
         let current_ts = env.ledger().timestamp();
-        let deadline = current_ts
-            .checked_add(duration)
-            .ok_or(ContractError::MathOverflow)?;
 
         let loan = Loan {
             id: loan_id,
@@ -462,7 +748,10 @@ impl LoanManagement {
 
     /// Repay an active loan (supports partial repayments)
     ///
-    /// Payment is applied first to accrued interest, then to principal.
+    /// Payment is applied first to accrued interest, then to principal, and
+    /// transferred from the borrower to the lender in full. The protocol fee
+    /// on the principal portion is charged to the borrower on top. Only what
+    /// is owed is pulled: any overpayment stays with the borrower.
     /// Loan transitions to Repaid only when the full principal is paid off.
     pub fn repay_loan(env: Env, loan_id: u64, amount: i128) -> Result<(), ContractError> {
         let mut loan: Loan = env
@@ -486,19 +775,14 @@ impl LoanManagement {
             return Err(ContractError::DeadlinePassed);
         }
 
-        // Calculate total repayment: principal + interest
-        let interest = (loan.amount * (loan.interest_rate as i128)) / 10000;
-        let total_due = loan.amount + interest;
+        let asset: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("loan_ast"))
+            .ok_or(ContractError::AssetNotSet)?;
 
-        // Calculate accrued interest since last repayment
-        let seconds_per_year: u64 = 31_557_600;
-        let elapsed = current_ts - loan.last_repayment_ts;
         let principal_remaining = loan.amount - loan.principal_repaid;
-
-        let interest_accrued = (principal_remaining * (loan.interest_rate as i128) * (elapsed as i128))
-            / ((seconds_per_year as i128) * 10000);
-
-        let interest_outstanding = interest_accrued;
+        let interest_outstanding = Self::accrued_interest(&loan, current_ts);
 
         // Apply payment: interest first, then principal
         let mut remaining_payment = amount;
@@ -520,53 +804,53 @@ impl LoanManagement {
         };
         loan.principal_repaid += principal_payment;
 
-        // Update last repayment timestamp
-        loan.last_repayment_ts = current_ts;
+        // Anything beyond interest + remaining principal is never pulled
+        let applied = interest_payment + principal_payment;
+        let refunded = amount - applied;
+
+        // Advance the accrual clock only as far as the interest paid covers,
+        // so unpaid interest keeps accruing into the next payment
+        loan.last_repayment_ts = if interest_payment < interest_outstanding {
+            let elapsed = current_ts - loan.last_repayment_ts;
+            let covered = (elapsed as i128 * interest_payment) / interest_outstanding;
+            loan.last_repayment_ts + covered as u64
+        } else {
+            current_ts
+        };
 
         // Check if fully repaid
         if loan.principal_repaid >= loan.amount {
             loan.status = LoanStatus::Repaid;
         }
 
-        // Calculate protocol fee if treasury is configured
-        let treasury_opt: Option<Address> =
-            env.storage().instance().get(&symbol_short!("treasury"));
-
-        let protocol_fee = if treasury_opt.is_some() && principal_payment > 0 {
-            // Query fee_bps from ProtocolTreasury
-            let treasury = treasury_opt.as_ref().unwrap();
-            let fee_bps_args: soroban_sdk::Vec<Val> = soroban_sdk::Vec::new(&env);
-            let fee_bps: u32 = env.invoke_contract(
-                treasury,
-                &Symbol::new(&env, "get_fee_bps"),
-                fee_bps_args,
-            );
-            // Calculate fee on the principal payment only (not interest)
-            let fee_amount = (principal_payment * fee_bps as i128) / 10000;
-            
-            // Record the fee deposit in treasury
-            // Note: In a full implementation, the actual token transfer would happen
-            // before this call, either by the borrower or automatically
-            let deposit_args: soroban_sdk::Vec<Val> = soroban_sdk::Vec::from_array(
+        // Collect protocol fee on the principal portion if treasury is configured
+        let protocol_fee = Self::collect_protocol_fee(&env, &loan, &asset, principal_payment)?;
+
+        // The full applied payment goes to the lender
+        if applied > 0 {
+            token::Client::new(&env, &asset).transfer(&loan.borrower, &loan.lender, &applied);
+        }
+
+        env.storage().persistent().set(&loan_id, &loan);
+
+        if principal_payment > 0 {
+            Self::apply_to_installments(&env, loan_id, principal_payment, current_ts);
+        }
+
+        if Self::is_pool_loan(&env, &loan) {
+            let repay_args: Vec<Val> = Vec::from_array(
                 &env,
                 [
-                    loan.lender.into_val(&env), // Asset address (simplified - using lender as proxy)
-                    fee_amount.into_val(&env),
+                    principal_payment.into_val(&env),
+                    interest_payment.into_val(&env),
                 ],
             );
-            env.invoke_contract(
-                &treasury,
-                &Symbol::new(&env, "deposit_fee"),
-                deposit_args,
+            env.invoke_contract::<()>(
+                &loan.lender,
+                &Symbol::new(&env, "record_repayment"),
+                repay_args,
             );
-            
-            fee_amount
-        } else {
-            0i128
-        };
-
-        loan.status = LoanStatus::Repaid;
-        env.storage().persistent().set(&loan_id, &loan);
+        }
 
         // Update total borrowed (decrease by principal paid)
         if principal_payment > 0 {
@@ -582,12 +866,180 @@ impl LoanManagement {
         }
 
         // Emit LoanRepaid event including protocol fee
-        env.events()
-            .publish((symbol_short!("loan_rep"),), (loan_id, amount, protocol_fee));
+        env.events().publish(
+            (symbol_short!("loan_rep"),),
+            (loan_id, applied, protocol_fee),
+        );
+
+        if refunded > 0 {
+            env.events()
+                .publish((symbol_short!("loan_rfd"),), (loan_id, refunded));
+        }
+
+        if loan.status == LoanStatus::Repaid {
+            env.events()
+                .publish((symbol_short!("loan_cls"),), (loan_id,));
+        }
 
         Ok(())
     }
 
+    /// Allocate a principal payment to the earliest unpaid installments
+    fn apply_to_installments(env: &Env, loan_id: u64, principal_payment: i128, now: u64) {
+        let key = (symbol_short!("sched"), loan_id);
+        let mut installments: Vec<Installment> = match env.storage().persistent().get(&key) {
+            Some(installments) => installments,
+            None => return,
+        };
+
+        let mut remaining = principal_payment;
+        for i in 0..installments.len() {
+            if remaining <= 0 {
+                break;
+            }
+            let mut inst = installments.get(i).unwrap();
+            if inst.status == InstallmentStatus::Paid {
+                continue;
+            }
+            let outstanding = inst.amount - inst.paid;
+            let payment = if remaining >= outstanding {
+                outstanding
+            } else {
+                remaining
+            };
+            remaining -= payment;
+            inst.paid += payment;
+
+            if inst.paid >= inst.amount {
+                let late = inst.status == InstallmentStatus::Missed || now > inst.due_ts;
+                inst.status = InstallmentStatus::Paid;
+                env.events()
+                    .publish((symbol_short!("inst_paid"),), (loan_id, i, late));
+            }
+            installments.set(i, inst);
+        }
+
+        env.storage().persistent().set(&key, &installments);
+    }
+
+    /// Mark unpaid installments past their grace period as missed.
+    /// Returns the number of installments currently in the missed state.
+    fn refresh_installments(env: &Env, loan_id: u64) -> u32 {
+        let key = (symbol_short!("sched"), loan_id);
+        let mut installments: Vec<Installment> = match env.storage().persistent().get(&key) {
+            Some(installments) => installments,
+            None => return 0,
+        };
+
+        let policy = Self::get_installment_policy(env.clone());
+        let now = env.ledger().timestamp();
+        let mut missed: u32 = 0;
+        let mut changed = false;
+
+        for i in 0..installments.len() {
+            let mut inst = installments.get(i).unwrap();
+            match inst.status {
+                InstallmentStatus::Paid => {}
+                InstallmentStatus::Missed => missed += 1,
+                InstallmentStatus::Pending => {
+                    if now > inst.due_ts.saturating_add(policy.grace_period) {
+                        inst.status = InstallmentStatus::Missed;
+                        env.events().publish(
+                            (symbol_short!("inst_miss"),),
+                            (loan_id, i, inst.amount - inst.paid),
+                        );
+                        installments.set(i, inst);
+                        missed += 1;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        if changed {
+            env.storage().persistent().set(&key, &installments);
+        }
+
+        missed
+    }
+
+    /// Load an escrow from the registered EscrowManager
+    fn fetch_escrow(env: &Env, escrow_id: u64) -> Result<EscrowRecord, ContractError> {
+        let escrow_mgr: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("esc_mgr"))
+            .ok_or(ContractError::EscrowManagerNotSet)?;
+
+        let args: Vec<Val> = Vec::from_array(env, [escrow_id.into_val(env)]);
+        env.invoke_contract::<Option<EscrowRecord>>(
+            &escrow_mgr,
+            &Symbol::new(env, "get_escrow"),
+            args,
+        )
+        .ok_or(ContractError::EscrowNotFound)
+    }
+
+    /// Whether the loan was funded by the registered lending pool
+    fn is_pool_loan(env: &Env, loan: &Loan) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("pool"))
+            .is_some_and(|pool| pool == loan.lender)
+    }
+
+    /// Interest accrued on the outstanding principal since the last repayment
+    fn accrued_interest(loan: &Loan, now: u64) -> i128 {
+        let seconds_per_year: u64 = 31_557_600;
+        let elapsed = now.saturating_sub(loan.last_repayment_ts);
+        let principal_remaining = loan.amount - loan.principal_repaid;
+
+        (principal_remaining * (loan.interest_rate as i128) * (elapsed as i128))
+            / ((seconds_per_year as i128) * 10000)
+    }
+
+    /// Transfer the protocol fee on a principal payment from the borrower
+    /// into the treasury and record it there. Returns the fee collected.
+    fn collect_protocol_fee(
+        env: &Env,
+        loan: &Loan,
+        asset: &Address,
+        principal_payment: i128,
+    ) -> Result<i128, ContractError> {
+        let treasury: Address = match env.storage().instance().get(&symbol_short!("treasury")) {
+            Some(treasury) => treasury,
+            None => return Ok(0),
+        };
+
+        if principal_payment <= 0 {
+            return Ok(0);
+        }
+
+        let fee_bps: u32 =
+            env.invoke_contract(&treasury, &Symbol::new(env, "get_fee_bps"), Vec::new(env));
+
+        // Fee is charged on the principal payment only (not interest)
+        let fee_amount = (principal_payment * fee_bps as i128) / 10000;
+        if fee_amount <= 0 {
+            return Ok(0);
+        }
+
+        // Move the fee first; the treasury only records fees it actually holds
+        token::Client::new(env, asset).transfer(&loan.borrower, &treasury, &fee_amount);
+
+        let deposit_args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                env.current_contract_address().into_val(env),
+                asset.into_val(env),
+                fee_amount.into_val(env),
+            ],
+        );
+        env.invoke_contract::<()>(&treasury, &Symbol::new(env, "deposit_fee"), deposit_args);
+
+        Ok(fee_amount)
+    }
+
     /// Get total amount currently due on a loan (principal remaining + accrued interest)
     pub fn get_total_due(env: Env, loan_id: u64) -> Result<i128, ContractError> {
         let loan: Loan = env
@@ -600,20 +1052,61 @@ impl LoanManagement {
             return Ok(0);
         }
 
-        let seconds_per_year: u64 = 31_557_600;
-        let current_ts = env.ledger().timestamp();
-        let elapsed = current_ts - loan.last_repayment_ts;
         let principal_remaining = loan.amount - loan.principal_repaid;
-
-        let interest_accrued = (principal_remaining * (loan.interest_rate as i128) * (elapsed as i128))
-            / ((seconds_per_year as i128) * 10000);
+        let interest_accrued = Self::accrued_interest(&loan, env.ledger().timestamp());
 
         Ok(principal_remaining + interest_accrued)
     }
 
+    /// Get the remaining principal, accrued interest and payoff amounts for a loan
+    ///
+    /// `payoff_at_deadline` assumes no further payments until the deadline.
+    /// Closed loans report zero outstanding.
+    pub fn get_repayment_schedule(
+        env: Env,
+        loan_id: u64,
+    ) -> Result<RepaymentSchedule, ContractError> {
+        let loan: Loan = env
+            .storage()
+            .persistent()
+            .get(&loan_id)
+            .ok_or(ContractError::LoanNotFound)?;
+
+        let (principal_remaining, accrued_interest, interest_to_deadline) =
+            if loan.status == LoanStatus::Active {
+                let now = env.ledger().timestamp();
+                (
+                    loan.amount - loan.principal_repaid,
+                    Self::accrued_interest(&loan, now),
+                    Self::accrued_interest(&loan, loan.deadline.max(now)),
+                )
+            } else {
+                (0, 0, 0)
+            };
+
+        Ok(RepaymentSchedule {
+            loan_id,
+            status: loan.status,
+            principal_remaining,
+            accrued_interest,
+            payoff_amount: principal_remaining + accrued_interest,
+            payoff_at_deadline: principal_remaining + interest_to_deadline,
+            principal_repaid: loan.principal_repaid,
+            interest_repaid: loan.interest_repaid,
+            deadline: loan.deadline,
+        })
+    }
+
     /// Mark a loan as defaulted if the deadline has passed
+    ///
+    /// Installment loans can also be defaulted before the deadline once the
+    /// number of missed installments reaches the policy's `max_missed`.
+    ///
+    /// If a risk engine is registered, a Dutch auction for the loan's
+    /// collateral is opened in the same call, with the debt outstanding at
+    /// default as its floor. The auction settles back via `mark_liquidated`.
     pub fn mark_default(env: Env, loan_id: u64) -> Result<(), ContractError> {
-        let mut loan: Loan = env
+        let loan: Loan = env
             .storage()
             .persistent()
             .get(&loan_id)
@@ -625,19 +1118,114 @@ impl LoanManagement {
 
         let current_ts = env.ledger().timestamp();
         if current_ts <= loan.deadline {
-            return Err(ContractError::DeadlineNotPassed);
+            let missed = Self::refresh_installments(&env, loan_id);
+            let policy = Self::get_installment_policy(env.clone());
+            if missed == 0 || missed < policy.max_missed {
+                return Err(ContractError::DeadlineNotPassed);
+            }
+        }
+
+        Self::default_loan(&env, loan)
+    }
+
+    /// Re-evaluate an installment loan against its schedule
+    ///
+    /// Installments unpaid past their grace period are marked missed. If the
+    /// number of missed installments reaches `max_missed`, the loan is
+    /// defaulted. Callable by anyone (keepers).
+    ///
+    /// # Returns
+    /// Number of installments currently missed
+    pub fn check_installments(env: Env, loan_id: u64) -> Result<u32, ContractError> {
+        let loan: Loan = env
+            .storage()
+            .persistent()
+            .get(&loan_id)
+            .ok_or(ContractError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(ContractError::LoanNotActive);
         }
 
+        let missed = Self::refresh_installments(&env, loan_id);
+        let policy = Self::get_installment_policy(env.clone());
+        if missed > 0 && missed >= policy.max_missed {
+            Self::default_loan(&env, loan)?;
+        }
+
+        Ok(missed)
+    }
+
+    /// Get the installment schedule of a loan (empty for bullet loans)
+    pub fn get_installments(env: Env, loan_id: u64) -> Vec<Installment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("sched"), loan_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the grace period and missed-installment limit (admin only)
+    pub fn set_installment_policy(
+        env: Env,
+        grace_period: u64,
+        max_missed: u32,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+        admin.require_auth();
+
+        if max_missed == 0 {
+            return Err(ContractError::InvalidSchedule);
+        }
+
+        let policy = InstallmentPolicy {
+            grace_period,
+            max_missed,
+        };
+        env.storage()
+            .instance()
+            .set(&symbol_short!("inst_pol"), &policy);
+
+        env.events()
+            .publish((symbol_short!("pol_set"),), (grace_period, max_missed));
+
+        Ok(())
+    }
+
+    /// Get the installment policy
+    pub fn get_installment_policy(env: Env) -> InstallmentPolicy {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("inst_pol"))
+            .unwrap_or_default()
+    }
+
+    /// Move an active loan into default and hand its collateral to the risk engine
+    fn default_loan(env: &Env, mut loan: Loan) -> Result<(), ContractError> {
+        // Snapshot the outstanding debt before the loan stops accruing
+        let debt = Self::get_total_due(env.clone(), loan.id)?;
+
         loan.status = LoanStatus::Defaulted;
-        env.storage().persistent().set(&loan_id, &loan);
+        env.storage().persistent().set(&loan.id, &loan);
 
         // Emit LoanDefaulted event
         env.events()
-            .publish((symbol_short!("loan_def"),), (loan_id,));
+            .publish((symbol_short!("loan_def"),), (loan.id,));
 
-        // Trigger collateral liquidation (logic would go here)
-        // For this task, we emit the event and update status.
-        // Actual liquidation might involve calling another contract.
+        // Hand the collateral to the risk engine for liquidation
+        let risk_engine: Option<Address> = env.storage().instance().get(&symbol_short!("risk_eng"));
+        if let Some(risk_engine) = risk_engine {
+            let auction_args: Vec<Val> =
+                Vec::from_array(env, [loan.escrow_id.into_val(env), debt.into_val(env)]);
+            env.invoke_contract::<Val>(
+                &risk_engine,
+                &Symbol::new(env, "start_default_auction"),
+                auction_args,
+            );
+        }
 
         Ok(())
     }
@@ -647,6 +1235,7 @@ impl LoanManagement {
     /// # Arguments
     /// * `loan_id` - The loan ID to mark as liquidated
     /// * `liquidator` - Address of the liquidator who executed the liquidation
+    /// * `recovered` - Proceeds the liquidator paid to the lender
     ///
     /// # Authorization
     /// Only callable by the registered risk engine contract
@@ -654,6 +1243,7 @@ impl LoanManagement {
         env: Env,
         loan_id: u64,
         liquidator: Address,
+        recovered: i128,
     ) -> Result<(), ContractError> {
         // Verify caller is the risk engine
         let risk_engine: Address = env
@@ -670,96 +1260,370 @@ impl LoanManagement {
             .get(&loan_id)
             .ok_or(ContractError::LoanNotFound)?;
 
-        if loan.status != LoanStatus::Active {
+        // Undercollateralised loans are liquidated while Active, defaulted
+        // ones once their collateral auction settles
+        if loan.status != LoanStatus::Active && loan.status != LoanStatus::Defaulted {
             return Err(ContractError::LoanNotActive);
         }
 
         loan.status = LoanStatus::Liquidated;
         env.storage().persistent().set(&loan_id, &loan);
 
-        // Emit LoanLiquidated event
+        // Auction proceeds were paid to the pool; drop the principal from its
+        // books and credit what was recovered
+        if Self::is_pool_loan(&env, &loan) {
+            let principal_remaining = loan.amount - loan.principal_repaid;
+            let write_off_args: Vec<Val> = Vec::from_array(
+                &env,
+                [principal_remaining.into_val(&env), recovered.into_val(&env)],
+            );
+            env.invoke_contract::<()>(
+                &loan.lender,
+                &Symbol::new(&env, "write_off"),
+                write_off_args,
+            );
+        }
+
+        // Emit LoanLiquidated event
+        env.events()
+            .publish((symbol_short!("loan_liq"),), (loan_id, liquidator));
+
+        Ok(())
+    }
+
+    /// Set the risk engine contract address
+    ///
+    /// # Arguments
+    /// * `risk_engine` - Address of the risk assessment contract
+    ///
+    /// # Authorization
+    /// Only callable by admin
+    pub fn set_risk_engine(env: Env, risk_engine: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("risk_eng"), &risk_engine);
+
+        // Emit RiskEngineSet event
+        env.events()
+            .publish((symbol_short!("risk_set"),), (risk_engine,));
+
+        Ok(())
+    }
+
+    /// Get the registered risk engine address
+    pub fn get_risk_engine(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("risk_eng"))
+    }
+
+    /// Get loan details
+    pub fn get_loan(env: Env, loan_id: u64) -> Option<Loan> {
+        env.storage().persistent().get(&loan_id)
+    }
+
+    /// Set the ProtocolTreasury address. Admin only.
+    pub fn set_treasury(env: Env, treasury: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("treasury"), &treasury);
+
+        env.events()
+            .publish((symbol_short!("trs_set"),), (treasury,));
+
+        Ok(())
+    }
+
+    /// Get the registered treasury address.
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("treasury"))
+    }
+
+    /// Set the token loans are denominated and repaid in. Admin only.
+    pub fn set_loan_asset(env: Env, asset: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("loan_ast"), &asset);
+
+        env.events().publish((symbol_short!("ast_set"),), (asset,));
+
+        Ok(())
+    }
+
+    /// Get the token loans are denominated and repaid in.
+    pub fn get_loan_asset(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("loan_ast"))
+    }
+
+    /// Register the lending pool that funds pool loans and reports liquidity (admin only)
+    pub fn set_lending_pool(env: Env, pool: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&symbol_short!("pool"), &pool);
+
+        env.events().publish((symbol_short!("pool_set"),), (pool,));
+
+        Ok(())
+    }
+
+    /// Get the registered lending pool
+    pub fn get_lending_pool(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("pool"))
+    }
+
+    /// Register the EscrowManager whose escrows back pool loans (admin only)
+    pub fn set_escrow_manager(env: Env, escrow_manager: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("esc_mgr"), &escrow_manager);
+
+        env.events()
+            .publish((symbol_short!("esc_set"),), (escrow_manager,));
+
+        Ok(())
+    }
+
+    /// Get the registered EscrowManager
+    pub fn get_escrow_manager(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("esc_mgr"))
+    }
+
+    /// Get loan ID for an escrow
+    pub fn get_loan_id_by_escrow(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+    }
+
+    /// Set the governance contract allowed to upgrade the loan manager
+    ///
+    /// The admin sets it once; afterwards only the current governance can
+    /// replace it.
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&symbol_short!("gov"));
+        match current {
+            Some(current) => current.require_auth(),
+            None => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("admin"))
+                    .ok_or(ContractError::Unauthorized)?;
+                admin.require_auth();
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
         env.events()
-            .publish((symbol_short!("loan_liq"),), (loan_id, liquidator));
+            .publish((symbol_short!("gov_set"),), (governance,));
 
         Ok(())
     }
 
-    /// Set the risk engine contract address
-    ///
-    /// # Arguments
-    /// * `risk_engine` - Address of the risk assessment contract
-    ///
-    /// # Authorization
-    /// Only callable by admin
-    pub fn set_risk_engine(env: Env, risk_engine: Address) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Swap the loan manager's code for an uploaded Wasm (governance only)
+    /// Outstanding loans and their repayment schedules keep accruing under the
+    /// new code; nothing is settled as part of the upgrade
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
             .storage()
             .instance()
-            .get(&symbol_short!("admin"))
+            .get(&symbol_short!("gov"))
             .ok_or(ContractError::Unauthorized)?;
 
-        admin.require_auth();
+        governance.require_auth();
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("risk_eng"), &risk_engine);
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
 
-        // Emit RiskEngineSet event
         env.events()
-            .publish((symbol_short!("risk_set"),), (risk_engine,));
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
 
         Ok(())
     }
 
-    /// Get the registered risk engine address
-    pub fn get_risk_engine(env: Env) -> Option<Address> {
-        env.storage().instance().get(&symbol_short!("risk_eng"))
-    }
-
-    /// Get loan details
-    pub fn get_loan(env: Env, loan_id: u64) -> Option<Loan> {
-        env.storage().persistent().get(&loan_id)
-    }
-
-    /// Set the ProtocolTreasury address. Admin only.
-    pub fn set_treasury(env: Env, treasury: Address) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Record that loans and schedules have been moved to `version` (governance only)
+    /// Fails for versions at or below the stored one, or newer than this code
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
             .storage()
             .instance()
-            .get(&symbol_short!("admin"))
+            .get(&symbol_short!("gov"))
             .ok_or(ContractError::Unauthorized)?;
 
-        admin.require_auth();
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
 
         env.storage()
             .instance()
-            .set(&symbol_short!("treasury"), &treasury);
+            .set(&symbol_short!("version"), &version);
 
         env.events()
-            .publish((symbol_short!("trs_set"),), (treasury,));
+            .publish((symbol_short!("migrated"),), (current, version));
 
         Ok(())
     }
 
-    /// Get the registered treasury address.
-    pub fn get_treasury(env: Env) -> Option<Address> {
-        env.storage().instance().get(&symbol_short!("treasury"))
-    }
-
-    /// Get loan ID for an escrow
-    pub fn get_loan_id_by_escrow(env: Env, escrow_id: u64) -> Option<u64> {
+    /// Storage layout version loans and schedules are kept in
+    pub fn version(env: Env) -> u32 {
         env.storage()
-            .persistent()
-            .get(&(symbol_short!("escrow"), escrow_id))
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Env};
+    use soroban_sdk::{contract, testutils::Address as _, testutils::Ledger as _, Env};
+
+    #[contract]
+    pub struct MockTreasury;
+
+    #[contractimpl]
+    impl MockTreasury {
+        pub fn get_fee_bps(_env: Env) -> u32 {
+            100 // 1%
+        }
+
+        pub fn deposit_fee(env: Env, depositor: Address, asset: Address, amount: i128) {
+            depositor.require_auth();
+            let key = (symbol_short!("fees"), asset);
+            let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(current + amount));
+        }
+
+        pub fn get_total_fees(env: Env, asset: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("fees"), asset))
+                .unwrap_or(0)
+        }
+    }
+
+    #[contract]
+    pub struct MockPool;
+
+    #[contractimpl]
+    impl MockPool {
+        pub fn set_stats(env: Env, total_assets: i128, total_borrowed: i128) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("stats"), &(total_assets, total_borrowed));
+        }
 
-    fn setup_env() -> (Env, LoanManagementClient<'static>, Address, Address, Address) {
+        pub fn get_pool_stats(env: Env) -> PoolStats {
+            let (total_assets, total_borrowed): (i128, i128) = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("stats"))
+                .unwrap_or((0, 0));
+            PoolStats {
+                total_assets,
+                total_borrowed,
+                available: total_assets - total_borrowed,
+                total_shares: total_assets,
+            }
+        }
+    }
+
+    #[contract]
+    pub struct MockEscrowManager;
+
+    #[contractimpl]
+    impl MockEscrowManager {
+        pub fn set_escrow(env: Env, escrow: EscrowRecord) {
+            env.storage().persistent().set(&escrow.id, &escrow);
+        }
+
+        pub fn get_escrow(env: Env, escrow_id: u64) -> Option<EscrowRecord> {
+            env.storage().persistent().get(&escrow_id)
+        }
+    }
+
+    /// Register a funded mock pool and an escrow mock holding one active escrow
+    fn setup_pool_escrow(
+        env: &Env,
+        client: &LoanManagementClient,
+        buyer: &Address,
+        amount: i128,
+    ) -> MockEscrowManagerClient<'static> {
+        let pool_id = env.register(MockPool, ());
+        MockPoolClient::new(env, &pool_id).set_stats(&50_000, &0);
+        client.set_lending_pool(&pool_id);
+
+        let escrow_mgr = MockEscrowManagerClient::new(env, &env.register(MockEscrowManager, ()));
+        client.set_escrow_manager(&escrow_mgr.address);
+
+        let asset = client.get_loan_asset().unwrap();
+        escrow_mgr.set_escrow(&EscrowRecord {
+            id: 1,
+            buyer: buyer.clone(),
+            seller: Address::generate(env),
+            lender: Address::generate(env),
+            collateral_id: 1,
+            amount,
+            asset: asset.clone(),
+            required_confirmation: 2,
+            status: EscrowStatus::Active,
+            expiry_ts: env.ledger().timestamp() + 3600,
+            created_at: env.ledger().timestamp(),
+            destination_asset: asset,
+            min_destination_amount: amount,
+            required_confirmations: 0,
+            oracle_set: Vec::new(env),
+        });
+        escrow_mgr
+    }
+
+    fn setup_env() -> (
+        Env,
+        LoanManagementClient<'static>,
+        Address,
+        Address,
+        Address,
+    ) {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -772,6 +1636,14 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&100_000);
 
+        // Loan asset with enough borrower funds for any repayment in these tests
+        let token_admin = Address::generate(&env);
+        let token_addr = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        token::StellarAssetClient::new(&env, &token_addr).mint(&borrower, &1_000_000);
+        client.set_loan_asset(&token_addr);
+
         // Leak to get 'static lifetime for tests
         let client = unsafe {
             core::mem::transmute::<LoanManagementClient<'_>, LoanManagementClient<'static>>(client)
@@ -878,6 +1750,37 @@ mod test {
         assert_eq!(loan.principal_repaid, 0);
     }
 
+    #[test]
+    fn test_partial_interest_payments_keep_unpaid_interest() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &10000, &31_557_600);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp += 31_557_600;
+        });
+
+        let interest_1yr = (10000i128 * interest_rate as i128) / 10000;
+        let payment = interest_1yr / 4;
+
+        // Two payments that each cover only part of the accrued interest
+        client.repay_loan(&loan_id, &payment);
+        assert_eq!(
+            client.get_total_due(&loan_id),
+            10000 + interest_1yr - payment
+        );
+        client.repay_loan(&loan_id, &payment);
+        assert_eq!(
+            client.get_total_due(&loan_id),
+            10000 + interest_1yr - 2 * payment
+        );
+
+        let loan = client.get_loan(&loan_id).unwrap();
+        assert_eq!(loan.interest_repaid, 2 * payment);
+        assert_eq!(loan.principal_repaid, 0);
+    }
+
     #[test]
     fn test_multiple_partial_repayments() {
         let (env, client, _admin, borrower, lender) = setup_env();
@@ -1090,7 +1993,7 @@ mod test {
         client.set_risk_engine(&risk_engine);
 
         let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
-        client.mark_liquidated(&loan_id, &liquidator);
+        client.mark_liquidated(&loan_id, &liquidator, &0);
 
         let loan = client.get_loan(&loan_id).unwrap();
         assert_eq!(loan.status, LoanStatus::Liquidated);
@@ -1106,7 +2009,7 @@ mod test {
         let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
 
         // Should fail - no risk engine set
-        client.mark_liquidated(&loan_id, &liquidator);
+        client.mark_liquidated(&loan_id, &liquidator, &0);
     }
 
     #[test]
@@ -1125,7 +2028,7 @@ mod test {
         client.repay_loan(&loan_id, &1000);
 
         // Should fail - loan is already repaid
-        client.mark_liquidated(&loan_id, &liquidator);
+        client.mark_liquidated(&loan_id, &liquidator, &0);
     }
 
     #[test]
@@ -1252,6 +2155,13 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
+        let token_admin = Address::generate(&env);
+        let token_addr = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        token::StellarAssetClient::new(&env, &token_addr).mint(&borrower, &2000);
+        client.set_loan_asset(&token_addr);
+
         // Issue first loan
         let (loan_id_1, _) = client.issue_loan(&1, &borrower, &lender, &2000, &3600);
 
@@ -1350,4 +2260,373 @@ mod test {
         // base_rate (200) + risk_premium * risk_factor (100 * 1) = 300
         assert_eq!(rate, 300);
     }
+
+    #[test]
+    fn test_repay_transfers_protocol_fee_to_treasury() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let token_addr = client.get_loan_asset().unwrap();
+
+        let treasury_addr = env.register(MockTreasury, ());
+        client.set_treasury(&treasury_addr);
+
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        client.repay_loan(&loan_id, &1000);
+
+        // 1% of the 1000 principal payment is charged to the borrower on top
+        let token = token::Client::new(&env, &token_addr);
+        assert_eq!(token.balance(&treasury_addr), 10);
+        assert_eq!(token.balance(&lender), 1000);
+        assert_eq!(token.balance(&borrower), 1_000_000 - 1010);
+        let treasury = MockTreasuryClient::new(&env, &treasury_addr);
+        assert_eq!(treasury.get_total_fees(&token_addr), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #12)")]
+    fn test_repay_requires_loan_asset() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let contract_id = env.register(LoanManagement, ());
+        let client = LoanManagementClient::new(&env, &contract_id);
+        client.initialize(&admin);
+        client.update_total_liquidity(&100_000);
+
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        client.repay_loan(&loan_id, &1000);
+    }
+
+    #[test]
+    fn test_repay_moves_tokens_to_lender() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let token = token::Client::new(&env, &client.get_loan_asset().unwrap());
+
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &5000, &3600);
+        client.repay_loan(&loan_id, &2000);
+
+        assert_eq!(token.balance(&lender), 2000);
+        assert_eq!(token.balance(&borrower), 1_000_000 - 2000);
+    }
+
+    #[test]
+    fn test_small_payment_does_not_close_loan() {
+        let (_env, client, _admin, borrower, lender) = setup_env();
+
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &5000, &3600);
+        client.repay_loan(&loan_id, &1);
+
+        let loan = client.get_loan(&loan_id).unwrap();
+        assert_eq!(loan.status, LoanStatus::Active);
+        assert_eq!(loan.principal_repaid, 1);
+        assert_eq!(client.get_total_due(&loan_id), 4999);
+    }
+
+    #[test]
+    fn test_overpayment_is_refunded() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let token = token::Client::new(&env, &client.get_loan_asset().unwrap());
+
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        client.repay_loan(&loan_id, &99_999);
+
+        // Only the 1000 owed leaves the borrower's account
+        assert_eq!(token.balance(&borrower), 1_000_000 - 1000);
+        assert_eq!(token.balance(&lender), 1000);
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Repaid
+        );
+    }
+
+    #[test]
+    fn test_get_repayment_schedule() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &10000, &(31_557_600 * 2));
+
+        // At issuance: nothing accrued, payoff at deadline is two years of interest
+        let schedule = client.get_repayment_schedule(&loan_id);
+        assert_eq!(schedule.principal_remaining, 10000);
+        assert_eq!(schedule.accrued_interest, 0);
+        assert_eq!(schedule.payoff_amount, 10000);
+        let interest_2yr = (10000i128 * interest_rate as i128 * 2) / 10000;
+        assert_eq!(schedule.payoff_at_deadline, 10000 + interest_2yr);
+
+        // After a year and a partial payment
+        env.ledger().with_mut(|li| {
+            li.timestamp += 31_557_600;
+        });
+        let interest_1yr = (10000i128 * interest_rate as i128) / 10000;
+        let schedule = client.get_repayment_schedule(&loan_id);
+        assert_eq!(schedule.accrued_interest, interest_1yr);
+        assert_eq!(schedule.payoff_amount, 10000 + interest_1yr);
+
+        client.repay_loan(&loan_id, &(interest_1yr + 4000));
+        let schedule = client.get_repayment_schedule(&loan_id);
+        assert_eq!(schedule.status, LoanStatus::Active);
+        assert_eq!(schedule.principal_remaining, 6000);
+        assert_eq!(schedule.accrued_interest, 0);
+        assert_eq!(schedule.payoff_amount, 6000);
+        assert_eq!(schedule.principal_repaid, 4000);
+        assert_eq!(schedule.interest_repaid, interest_1yr);
+
+        // Paid off: nothing outstanding
+        client.repay_loan(&loan_id, &6000);
+        let schedule = client.get_repayment_schedule(&loan_id);
+        assert_eq!(schedule.status, LoanStatus::Repaid);
+        assert_eq!(schedule.payoff_amount, 0);
+        assert_eq!(schedule.payoff_at_deadline, 0);
+    }
+
+    fn three_installments(env: &Env) -> Vec<InstallmentTerm> {
+        let now = env.ledger().timestamp();
+        Vec::from_array(
+            env,
+            [
+                InstallmentTerm {
+                    due_ts: now + 1000,
+                    amount: 300,
+                },
+                InstallmentTerm {
+                    due_ts: now + 2000,
+                    amount: 300,
+                },
+                InstallmentTerm {
+                    due_ts: now + 3000,
+                    amount: 400,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_issue_loan_with_schedule() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let schedule = three_installments(&env);
+
+        let (loan_id, _) = client.issue_loan_with_schedule(&1, &borrower, &lender, &schedule);
+
+        let loan = client.get_loan(&loan_id).unwrap();
+        assert_eq!(loan.amount, 1000);
+        let grace_period = client.get_installment_policy().grace_period;
+        assert_eq!(
+            loan.deadline,
+            env.ledger().timestamp() + 3000 + grace_period
+        );
+
+        let installments = client.get_installments(&loan_id);
+        assert_eq!(installments.len(), 3);
+        assert_eq!(installments.get(2).unwrap().amount, 400);
+        assert_eq!(
+            installments.get(0).unwrap().status,
+            InstallmentStatus::Pending
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #13)")]
+    fn test_schedule_must_be_increasing() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let now = env.ledger().timestamp();
+        let schedule = Vec::from_array(
+            &env,
+            [
+                InstallmentTerm {
+                    due_ts: now + 2000,
+                    amount: 500,
+                },
+                InstallmentTerm {
+                    due_ts: now + 1000,
+                    amount: 500,
+                },
+            ],
+        );
+
+        client.issue_loan_with_schedule(&1, &borrower, &lender, &schedule);
+    }
+
+    #[test]
+    fn test_repayment_fills_installments_in_order() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        let (loan_id, _) =
+            client.issue_loan_with_schedule(&1, &borrower, &lender, &three_installments(&env));
+
+        client.repay_loan(&loan_id, &450);
+
+        let installments = client.get_installments(&loan_id);
+        let first = installments.get(0).unwrap();
+        let second = installments.get(1).unwrap();
+        assert_eq!(first.status, InstallmentStatus::Paid);
+        assert_eq!(first.paid, 300);
+        assert_eq!(second.status, InstallmentStatus::Pending);
+        assert_eq!(second.paid, 150);
+    }
+
+    #[test]
+    fn test_missed_installments_default_loan() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        client.set_installment_policy(&100, &2);
+        let (loan_id, _) =
+            client.issue_loan_with_schedule(&1, &borrower, &lender, &three_installments(&env));
+
+        // First installment late but within grace: nothing missed yet
+        env.ledger().with_mut(|li| {
+            li.timestamp += 1050;
+        });
+        assert_eq!(client.check_installments(&loan_id), 0);
+
+        // One missed installment is tolerated
+        env.ledger().with_mut(|li| {
+            li.timestamp += 100;
+        });
+        assert_eq!(client.check_installments(&loan_id), 1);
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Active
+        );
+        assert!(client.try_mark_default(&loan_id).is_err());
+
+        // Second missed installment reaches the limit before the deadline
+        env.ledger().with_mut(|li| {
+            li.timestamp += 1000;
+        });
+        assert_eq!(client.check_installments(&loan_id), 2);
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Defaulted
+        );
+    }
+
+    #[test]
+    fn test_late_payment_cures_missed_installment() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        client.set_installment_policy(&100, &2);
+        let (loan_id, _) =
+            client.issue_loan_with_schedule(&1, &borrower, &lender, &three_installments(&env));
+
+        env.ledger().with_mut(|li| {
+            li.timestamp += 1200;
+        });
+        assert_eq!(client.check_installments(&loan_id), 1);
+        assert_eq!(
+            client.get_installments(&loan_id).get(0).unwrap().status,
+            InstallmentStatus::Missed
+        );
+
+        // Paying the overdue principal clears the miss
+        let interest = client.get_total_due(&loan_id) - 1000;
+        client.repay_loan(&loan_id, &(interest + 300));
+        assert_eq!(
+            client.get_installments(&loan_id).get(0).unwrap().status,
+            InstallmentStatus::Paid
+        );
+
+        env.ledger().with_mut(|li| {
+            li.timestamp += 1000;
+        });
+        assert_eq!(client.check_installments(&loan_id), 1);
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Active
+        );
+    }
+
+    #[test]
+    fn test_last_installment_payable_within_grace() {
+        let (env, client, _admin, borrower, lender) = setup_env();
+        client.set_installment_policy(&100, &2);
+        let (loan_id, _) =
+            client.issue_loan_with_schedule(&1, &borrower, &lender, &three_installments(&env));
+
+        // Past the last due date but still inside its grace period
+        env.ledger().with_mut(|li| {
+            li.timestamp += 3050;
+        });
+        let total_due = client.get_total_due(&loan_id);
+        client.repay_loan(&loan_id, &total_due);
+
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Repaid
+        );
+    }
+
+    #[test]
+    fn test_utilization_reads_pool_balances() {
+        let (env, client, _admin, _borrower, _lender) = setup_env();
+        let pool_id = env.register(MockPool, ());
+        MockPoolClient::new(&env, &pool_id).set_stats(&50_000, &20_000);
+
+        client.set_lending_pool(&pool_id);
+
+        // The admin-set liquidity of 100_000 no longer applies
+        let (liquidity, borrowed, utilization) = client.get_utilization_stats();
+        assert_eq!(liquidity, 50_000);
+        assert_eq!(borrowed, 20_000);
+        assert_eq!(utilization, 4000);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #15)")]
+    fn test_pool_loan_limited_to_available_liquidity() {
+        let (env, client, _admin, borrower, _lender) = setup_env();
+        let pool_id = env.register(MockPool, ());
+        MockPoolClient::new(&env, &pool_id).set_stats(&50_000, &20_000);
+        client.set_lending_pool(&pool_id);
+
+        client.issue_pool_loan(&1, &borrower, &30_001, &3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #14)")]
+    fn test_pool_loan_requires_pool() {
+        let (_env, client, _admin, borrower, _lender) = setup_env();
+
+        client.issue_pool_loan(&1, &borrower, &1000, &3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #1)")]
+    fn test_pool_loan_requires_escrow_party() {
+        let (env, client, _admin, borrower, _lender) = setup_env();
+        setup_pool_escrow(&env, &client, &borrower, 10_000);
+
+        // Someone else cannot draw pool cash against the borrower's escrow
+        let stranger = Address::generate(&env);
+        client.issue_pool_loan(&1, &stranger, &1000, &3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #17)")]
+    fn test_pool_loan_requires_escrow() {
+        let (env, client, _admin, borrower, _lender) = setup_env();
+        setup_pool_escrow(&env, &client, &borrower, 10_000);
+
+        client.issue_pool_loan(&2, &borrower, &1000, &3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #19)")]
+    fn test_pool_loan_capped_by_escrow_amount() {
+        let (env, client, _admin, borrower, _lender) = setup_env();
+        setup_pool_escrow(&env, &client, &borrower, 10_000);
+
+        client.issue_pool_loan(&1, &borrower, &10_001, &3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #18)")]
+    fn test_pool_loan_requires_active_escrow() {
+        let (env, client, _admin, borrower, _lender) = setup_env();
+        let escrow_mgr = setup_pool_escrow(&env, &client, &borrower, 10_000);
+        let mut escrow = escrow_mgr.get_escrow(&1).unwrap();
+        escrow.status = EscrowStatus::Released;
+        escrow_mgr.set_escrow(&escrow);
+
+        client.issue_pool_loan(&1, &borrower, &1000, &3600);
+    }
 }
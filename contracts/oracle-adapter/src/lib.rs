@@ -7,8 +7,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env,
-    Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address,
+    Bytes, BytesN, Env, IntoVal, Map, Symbol, Val, Vec,
 };
 
 #[contracterror]
@@ -24,11 +24,26 @@ pub enum ContractError {
     InvalidEventType = 8,
     ConsensusNotMet = 9,
     InvalidThreshold = 10,
+    SignatureExpired = 11,
+    InvalidNonce = 12,
+    BondingNotConfigured = 13,
+    InsufficientBond = 14,
+    NotBonded = 15,
+    UnbondingLocked = 16,
+    InvalidAmount = 17,
+    ConfirmationNotFound = 18,
+    FeedNotConfigured = 19,
+    PriceUnavailable = 20,
+    StalePrice = 21,
+    ConfirmationConsumed = 22,
+    AlreadySlashed = 23,
+    InvalidVersion = 24,
+    AlreadyConsumed = 25,
 }
 
 /// Event types for oracle confirmations
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EventType {
     Shipment = 1,
     Delivery = 2,
@@ -47,6 +62,10 @@ pub struct ConfirmationData {
     pub oracle: Address,
     pub timestamp: u64,
     pub verified: bool,
+    /// Timestamp after which the confirmation no longer counts (0 = never)
+    pub expires_at: u64,
+    /// Set once an escrow has released funds on this confirmation
+    pub consumed: bool,
 }
 
 /// Contract data structure for storage
@@ -58,12 +77,118 @@ pub struct ContractData {
     pub oracles: Vec<Address>,
 }
 
+/// Bonding parameters for staked oracles
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BondConfig {
+    /// Token oracles stake
+    pub token: Address,
+    /// Minimum active bond to stay registered
+    pub min_bond: i128,
+    /// Seconds between an unbond request and the withdrawal
+    pub unbonding_period: u64,
+    /// ProtocolTreasury receiving `treasury_bps` of every slash; the adapter
+    /// must be registered there as a fee depositor
+    pub treasury: Address,
+    /// Treasury share of slashed funds, in basis points (remainder goes to the harmed party)
+    pub treasury_bps: u32,
+}
+
+/// Stake held for a bonded oracle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bond {
+    /// Active stake backing confirmations
+    pub amount: i128,
+    /// Stake queued for withdrawal; still slashable until withdrawn
+    pub unbonding: i128,
+    /// Timestamp at which `unbonding` can be withdrawn
+    pub unbond_at: u64,
+}
+
+/// A slash applied to an oracle for a confirmation proven wrong
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlashRecord {
+    pub escrow_id: Bytes,
+    pub event_type: u32,
+    /// Total amount taken from the bond
+    pub amount: i128,
+    /// Bond (active + unbonding) before the slash
+    pub bond_before: i128,
+    pub harmed: Address,
+    pub slashed_by: Address,
+    pub timestamp: u64,
+}
+
+/// Aggregation parameters for a price feed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeedConfig {
+    /// Distinct oracles whose submissions must agree before a price is published
+    pub min_sources: u32,
+    /// Submissions further than this from the median, in basis points, are discarded
+    pub max_deviation_bps: u32,
+    /// Seconds after which a submission or the published price is stale
+    pub heartbeat: u64,
+}
+
+/// An oracle's latest value for a feed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSubmission {
+    pub value: i128,
+    pub timestamp: u64,
+}
+
+/// Aggregated price published for a feed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Median of the submissions within the deviation bound
+    pub value: i128,
+    pub updated_at: u64,
+    /// Number of submissions the median was taken over
+    pub sources: u32,
+}
+
 /// Event symbols
 const ORACLE_ADDED: Symbol = symbol_short!("orc_add");
 const ORACLE_REMOVED: Symbol = symbol_short!("orc_rem");
 const ORACLE_CONFIRMED: Symbol = symbol_short!("confirmed");
 const INITIALIZED: Symbol = symbol_short!("init");
 const CONFIRMING_ORACLES: Symbol = symbol_short!("conf_orc");
+const CONFIRMATION_REVOKED: Symbol = symbol_short!("conf_rev");
+const UPGRADED: Symbol = symbol_short!("upgraded");
+const MIGRATED: Symbol = symbol_short!("migrated");
+
+/// Storage keys for the confirmation lifecycle
+const CONFIRMATION_TTL: Symbol = symbol_short!("conf_ttl");
+const CONSUMERS: Symbol = symbol_short!("consumers");
+
+/// Storage key prefixes for per-oracle signing state
+const ORACLE_KEY: Symbol = symbol_short!("orc_key");
+const ORACLE_NONCE: Symbol = symbol_short!("orc_nonce");
+const NONCE_USED: Symbol = symbol_short!("nonce_use");
+
+/// Storage keys for oracle bonding
+const BOND_CONFIG: Symbol = symbol_short!("bond_cfg");
+const SLASHERS: Symbol = symbol_short!("slashers");
+const BOND: Symbol = symbol_short!("bond");
+const SLASHES: Symbol = symbol_short!("slashes");
+const SLASHED: Symbol = symbol_short!("slashed");
+
+/// Storage keys for price feeds
+const FEED_CONFIG: Symbol = symbol_short!("feed_cfg");
+const PRICE_SUBMISSIONS: Symbol = symbol_short!("price_sub");
+const PRICE: Symbol = symbol_short!("price");
+
+/// Storage keys for upgrades
+const GOVERNANCE: Symbol = symbol_short!("gov");
+const VERSION: Symbol = symbol_short!("version");
+
+/// Storage layout of oracle and feed records this code reads; `migrate` records it
+const CONTRACT_VERSION: u32 = 1;
 
 /// Main contract for oracle adapter operations
 #[contract]
@@ -105,10 +230,15 @@ impl OracleAdapter {
     ///
     /// # Arguments
     /// * `oracle` - The oracle address to add
+    /// * `public_key` - ed25519 public key the oracle signs confirmations with
     ///
     /// # Events
     /// Emits `ORACLE_ADDED` event
-    pub fn add_oracle(env: Env, oracle: Address) -> Result<(), ContractError> {
+    pub fn add_oracle(
+        env: Env,
+        oracle: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), ContractError> {
         Self::check_admin(&env)?;
 
         let mut contract_data = Self::get_contract_data(&env)?;
@@ -120,6 +250,9 @@ impl OracleAdapter {
 
         // Add oracle to registry
         contract_data.oracles.push_back(oracle.clone());
+        env.storage()
+            .persistent()
+            .set(&(ORACLE_KEY, oracle.clone()), &public_key);
 
         // Save updated data
         env.storage()
@@ -142,58 +275,41 @@ impl OracleAdapter {
     pub fn remove_oracle(env: Env, oracle: Address) -> Result<(), ContractError> {
         Self::check_admin(&env)?;
 
-        let mut contract_data = Self::get_contract_data(&env)?;
-
-        // Find and remove oracle
-        let mut found = false;
-        let mut new_oracles = Vec::new(&env);
-
-        for existing_oracle in contract_data.oracles.iter() {
-            if existing_oracle != oracle {
-                new_oracles.push_back(existing_oracle);
-            } else {
-                found = true;
-            }
-        }
-
-        if !found {
+        if !Self::deregister(&env, &oracle)? {
             return Err(ContractError::OracleNotRegistered);
         }
 
-        contract_data.oracles = new_oracles;
-
-        // Save updated data
-        env.storage()
-            .instance()
-            .set(&symbol_short!("data"), &contract_data);
-
-        // Note: Stale confirmations from removed oracles are automatically filtered
-        // by check_consensus which validates against current registration state.
-        // This prevents removed oracles from ever contributing to consensus.
-
-        // Emit event
-        env.events().publish((ORACLE_REMOVED,), (oracle,));
-
         Ok(())
     }
 
     /// Confirm an event with oracle signature verification
     ///
+    /// The oracle signs the message built by `create_message` with its
+    /// registered ed25519 key, so anyone (e.g. a relayer) may submit the
+    /// confirmation on its behalf. An invalid signature traps the call.
+    ///
     /// # Arguments
+    /// * `oracle` - The oracle that signed the confirmation
     /// * `escrow_id` - The escrow ID to confirm
-    /// * `event_type` - Type of event (1=Shipment, 2=Delivery, 3=Quality, 4=Custom)
+    /// * `event_type` - Type of event (1=Shipment, 2=Delivery, 3=Quality, 4=Custom, 5=Valuation)
     /// * `result` - The confirmation result data
-    /// * `signature` - Oracle signature for verification
+    /// * `nonce` - Any nonce the oracle has not used yet; submissions may land
+    ///   out of order (see `is_nonce_used`)
+    /// * `expiry` - Ledger timestamp after which the signature is no longer accepted
+    /// * `signature` - ed25519 signature over the canonical message
     ///
     /// # Events
     /// Emits `ORACLE_CONFIRMED` event
+    #[allow(clippy::too_many_arguments)]
     pub fn confirm_event(
         env: Env,
         oracle: Address,
         escrow_id: Bytes,
         event_type: u32,
         result: Bytes,
-        signature: Bytes,
+        nonce: u64,
+        expiry: u64,
+        signature: BytesN<64>,
     ) -> Result<(), ContractError> {
         let contract_data = Self::get_contract_data(&env)?;
 
@@ -207,34 +323,54 @@ impl OracleAdapter {
             return Err(ContractError::InvalidEventType);
         }
 
-        // Check if confirmation already exists (prevent replay)
-        let confirmation_key = (escrow_id.clone(), oracle.clone());
+        // One confirmation per (escrow, oracle, event type) - prevent replay
+        let confirmation_key = (escrow_id.clone(), oracle.clone(), event_type);
         if env.storage().persistent().has(&confirmation_key) {
             return Err(ContractError::ConfirmationAlreadyExists);
         }
 
-        // Check if oracle already recorded for this escrow (defense-in-depth)
-        let mut confirming_oracles = Self::get_confirming_oracles(&env, &escrow_id);
+        // Check if oracle already recorded for this event (defense-in-depth)
+        let mut confirming_oracles = Self::get_confirming_oracles(&env, &escrow_id, event_type);
         for existing_oracle in confirming_oracles.iter() {
             if existing_oracle == oracle {
                 return Err(ContractError::ConfirmationAlreadyExists);
             }
         }
 
+        if env.ledger().timestamp() > expiry {
+            return Err(ContractError::SignatureExpired);
+        }
+
+        let used_key = (NONCE_USED, oracle.clone(), nonce);
+        if env.storage().persistent().has(&used_key) {
+            return Err(ContractError::InvalidNonce);
+        }
+
         // Create message for signature verification
-        let message = Self::create_message(&env, &escrow_id, event_type, &result);
+        let message = Self::create_message(&env, &escrow_id, event_type, &result, nonce, expiry);
 
         // Verify signature
         Self::verify_signature(&env, &message, &signature, &oracle)?;
 
+        // Consume the nonce so the signed message cannot be submitted again
+        env.storage().persistent().set(&used_key, &true);
+        let nonce_key = (ORACLE_NONCE, oracle.clone());
+        if nonce >= Self::get_nonce(env.clone(), oracle.clone()) {
+            env.storage().persistent().set(&nonce_key, &(nonce + 1));
+        }
+
         // Create confirmation data
+        let now = env.ledger().timestamp();
+        let ttl = Self::get_confirmation_ttl(env.clone());
         let confirmation = ConfirmationData {
             escrow_id: escrow_id.clone(),
             event_type,
             result: result.clone(),
             oracle: oracle.clone(),
-            timestamp: env.ledger().timestamp(),
+            timestamp: now,
             verified: true,
+            expires_at: if ttl > 0 { now + ttl } else { 0 },
+            consumed: false,
         };
 
         // Store confirmation
@@ -242,9 +378,9 @@ impl OracleAdapter {
             .persistent()
             .set(&confirmation_key, &confirmation);
 
-        // Track confirming oracles for this escrow
+        // Track confirming oracles for this escrow and event type
         confirming_oracles.push_back(oracle.clone());
-        let confirming_key = (CONFIRMING_ORACLES, escrow_id.clone());
+        let confirming_key = (CONFIRMING_ORACLES, escrow_id.clone(), event_type);
         env.storage()
             .persistent()
             .set(&confirming_key, &confirming_oracles);
@@ -273,6 +409,217 @@ impl OracleAdapter {
         }
     }
 
+    /// Retract a confirmation that no escrow has consumed yet
+    ///
+    /// The oracle may confirm the event again afterwards, e.g. with a
+    /// corrected result. The revoked confirmation is kept aside so the oracle
+    /// can still be slashed for it.
+    ///
+    /// # Arguments
+    /// * `oracle` - The oracle that made the confirmation
+    /// * `escrow_id` - Escrow the confirmation is for
+    /// * `event_type` - Event type of the confirmation
+    ///
+    /// # Events
+    /// Emits `conf_rev` event
+    pub fn revoke_confirmation(
+        env: Env,
+        oracle: Address,
+        escrow_id: Bytes,
+        event_type: u32,
+    ) -> Result<(), ContractError> {
+        oracle.require_auth();
+
+        let confirmation_key = (escrow_id.clone(), oracle.clone(), event_type);
+        let confirmation: ConfirmationData = env
+            .storage()
+            .persistent()
+            .get(&confirmation_key)
+            .ok_or(ContractError::ConfirmationNotFound)?;
+
+        if confirmation.consumed {
+            return Err(ContractError::ConfirmationConsumed);
+        }
+
+        env.storage().persistent().remove(&confirmation_key);
+        env.storage().persistent().set(
+            &(
+                CONFIRMATION_REVOKED,
+                escrow_id.clone(),
+                oracle.clone(),
+                event_type,
+            ),
+            &confirmation,
+        );
+
+        let mut confirming_oracles = Vec::new(&env);
+        for existing_oracle in Self::get_confirming_oracles(&env, &escrow_id, event_type).iter() {
+            if existing_oracle != oracle {
+                confirming_oracles.push_back(existing_oracle);
+            }
+        }
+        env.storage().persistent().set(
+            &(CONFIRMING_ORACLES, escrow_id.clone(), event_type),
+            &confirming_oracles,
+        );
+
+        env.events().publish(
+            (CONFIRMATION_REVOKED,),
+            (escrow_id, oracle, event_type, confirmation.result),
+        );
+        Ok(())
+    }
+
+    /// Mark the confirmations of an event as consumed by an escrow release
+    ///
+    /// Consumed confirmations can no longer be revoked. Only approved
+    /// consumers (escrow managers) may call this, and fails with
+    /// `AlreadyConsumed` if no confirmation of the event was left to consume.
+    ///
+    /// # Arguments
+    /// * `consumer` - Approved consumer authorizing the call
+    /// * `escrow_id` - Escrow that released funds
+    /// * `event_type` - Event type the release relied on
+    ///
+    /// # Returns
+    /// The number of confirmations marked
+    ///
+    /// # Events
+    /// Emits `conf_used` event
+    pub fn mark_consumed(
+        env: Env,
+        consumer: Address,
+        escrow_id: Bytes,
+        event_type: u32,
+    ) -> Result<u32, ContractError> {
+        consumer.require_auth();
+        if !Self::get_consumers(env.clone()).contains(&consumer) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let mut marked: u32 = 0;
+        for oracle in Self::get_confirming_oracles(&env, &escrow_id, event_type).iter() {
+            let confirmation_key = (escrow_id.clone(), oracle, event_type);
+            let confirmation: Option<ConfirmationData> =
+                env.storage().persistent().get(&confirmation_key);
+            if let Some(mut confirmation) = confirmation {
+                if !confirmation.consumed {
+                    confirmation.consumed = true;
+                    env.storage()
+                        .persistent()
+                        .set(&confirmation_key, &confirmation);
+                    marked += 1;
+                }
+            }
+        }
+        if marked == 0 {
+            return Err(ContractError::AlreadyConsumed);
+        }
+
+        env.events().publish(
+            (symbol_short!("conf_used"),),
+            (escrow_id, event_type, consumer, marked),
+        );
+        Ok(marked)
+    }
+
+    /// Set how long new confirmations count toward release (admin only)
+    ///
+    /// # Arguments
+    /// * `ttl` - Lifetime in seconds; 0 disables expiry
+    ///
+    /// # Events
+    /// Emits `conf_ttl` event
+    pub fn set_confirmation_ttl(env: Env, ttl: u64) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+        env.storage().instance().set(&CONFIRMATION_TTL, &ttl);
+        env.events().publish((CONFIRMATION_TTL,), (ttl,));
+        Ok(())
+    }
+
+    /// Get the lifetime given to new confirmations (0 = no expiry)
+    pub fn get_confirmation_ttl(env: Env) -> u64 {
+        env.storage().instance().get(&CONFIRMATION_TTL).unwrap_or(0)
+    }
+
+    /// Set the addresses allowed to mark confirmations consumed (admin only)
+    ///
+    /// Every EscrowManager reading this adapter must be listed: its releases
+    /// call `mark_consumed` and revert when it is not a consumer.
+    pub fn set_consumers(env: Env, consumers: Vec<Address>) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+        env.storage().instance().set(&CONSUMERS, &consumers);
+        env.events().publish((CONSUMERS,), (consumers,));
+        Ok(())
+    }
+
+    /// Get the addresses allowed to mark confirmations consumed
+    pub fn get_consumers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&CONSUMERS)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the governance contract allowed to upgrade the adapter
+    ///
+    /// The admin sets it once; afterwards only the current governance can
+    /// replace it.
+    ///
+    /// # Events
+    /// Emits `gov` event
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&GOVERNANCE);
+        match current {
+            Some(current) => current.require_auth(),
+            None => Self::check_admin(&env)?,
+        }
+        env.storage().instance().set(&GOVERNANCE, &governance);
+        env.events().publish((GOVERNANCE,), (governance,));
+        Ok(())
+    }
+
+    /// Swap the adapter's code for an uploaded Wasm (governance only)
+    ///
+    /// Oracle keys, nonces, bonds and recorded confirmations survive the swap,
+    /// so signatures prepared against the old code stay valid.
+    ///
+    /// # Events
+    /// Emits `upgraded` event
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        Self::check_governance(&env)?;
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((UPGRADED,), (new_wasm_hash,));
+        Ok(())
+    }
+
+    /// Record that adapter storage has been moved to `version` (governance only)
+    ///
+    /// # Arguments
+    /// * `version` - Layout the stored keys, bonds and feeds now follow; newer
+    ///   than the recorded one and no newer than this code reads
+    ///
+    /// # Events
+    /// Emits `migrated` event
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        Self::check_governance(&env)?;
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage().instance().set(&VERSION, &version);
+        env.events().publish((MIGRATED,), (current, version));
+        Ok(())
+    }
+
+    /// Storage layout version of the adapter's records
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&VERSION).unwrap_or(1)
+    }
+
     /// Check if an oracle is registered
     ///
     /// # Arguments
@@ -302,20 +649,43 @@ impl OracleAdapter {
         Self::get_contract_data(&env).ok()?.oracles.get(index)
     }
 
+    /// Get the ed25519 public key registered for an oracle
+    pub fn get_oracle_key(env: Env, oracle: Address) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&(ORACLE_KEY, oracle))
+    }
+
+    /// Get a nonce above every nonce the oracle has used, a safe pick for
+    /// its next signed confirmation
+    pub fn get_nonce(env: Env, oracle: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(ORACLE_NONCE, oracle))
+            .unwrap_or(0)
+    }
+
+    /// Check whether a confirmation carrying `nonce` was already accepted from the oracle
+    pub fn is_nonce_used(env: Env, oracle: Address, nonce: u64) -> bool {
+        env.storage().persistent().has(&(NONCE_USED, oracle, nonce))
+    }
+
     /// Get admin address
     pub fn get_admin(env: Env) -> Result<Address, ContractError> {
         let contract_data = Self::get_contract_data(&env)?;
         Ok(contract_data.admin)
     }
 
-    /// Check if consensus threshold is met for an escrow.
+    /// Check if consensus threshold is met for an event on an escrow.
     ///
-    /// Returns true if the number of unique oracle confirmations is >= threshold.
-    /// This enables multi-oracle consensus for high-value trades.
+    /// Only verified, unexpired confirmations of `event_type` from authorized
+    /// oracles are counted, and none already consumed by a release. They are
+    /// grouped by the hash of their `result`. Consensus is met when the
+    /// most-confirmed result has at least `threshold` oracles behind it and
+    /// strictly more than any competing result.
     ///
     /// # Arguments
     /// * `escrow_id` - The escrow ID to check
-    /// * `threshold` - Minimum number of unique oracle confirmations required
+    /// * `event_type` - The event type the confirmations must be for
+    /// * `threshold` - Minimum number of oracles that must agree on the result
     /// * `oracle_set` - Set of authorized oracles (empty means any registered oracle is allowed)
     ///
     /// # Returns
@@ -323,6 +693,7 @@ impl OracleAdapter {
     pub fn check_consensus(
         env: Env,
         escrow_id: Bytes,
+        event_type: u32,
         threshold: u32,
         oracle_set: Vec<Address>,
     ) -> Result<bool, ContractError> {
@@ -331,128 +702,773 @@ impl OracleAdapter {
             return Err(ContractError::InvalidThreshold);
         }
 
-        let contract_data = Self::get_contract_data(&env)?;
+        Ok(
+            Self::tally_results(&env, &escrow_id, event_type, &oracle_set)?
+                .is_some_and(|(_, votes)| votes >= threshold),
+        )
+    }
+
+    /// Get the result the authorized oracles agree on for an event.
+    ///
+    /// Returns the most-confirmed `result` payload, or None if there are no
+    /// confirmations or the leading results are tied. Applies the same
+    /// filtering as `check_consensus` but without a threshold.
+    pub fn get_consensus_result(
+        env: Env,
+        escrow_id: Bytes,
+        event_type: u32,
+        oracle_set: Vec<Address>,
+    ) -> Result<Option<Bytes>, ContractError> {
+        Ok(
+            Self::tally_results(&env, &escrow_id, event_type, &oracle_set)?
+                .map(|(result, _)| result),
+        )
+    }
 
-        // Count unique oracle confirmations for this escrow
-        let confirmations = Self::get_confirmations_for_escrow(&env, escrow_id)?;
-        let mut unique_oracle_count: u32 = 0;
+    /// Configure bonded oracle registration (admin only)
+    ///
+    /// # Arguments
+    /// * `config` - Stake token, minimum bond, unbonding delay and slash split
+    ///
+    /// # Events
+    /// Emits `bond_cfg` event
+    pub fn set_bond_config(env: Env, config: BondConfig) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
 
-        for confirmation in confirmations.iter() {
-            // Verify confirmed oracle is in the authorized set (or set is empty)
-            // Also validate against current registration state
-            let is_authorized = if oracle_set.is_empty() {
-                // If oracle_set is empty, check against all registered oracles
-                Self::is_oracle_registered(&contract_data, &confirmation.oracle)
-            } else {
-                // Check if oracle is in the specified oracle_set
-                // Additionally validate against current registration
-                let is_in_set = {
-                    let mut found = false;
-                    for authorized_oracle in oracle_set.iter() {
-                        if authorized_oracle == confirmation.oracle {
-                            found = true;
-                            break;
-                        }
-                    }
-                    found
-                };
-                is_in_set && Self::is_oracle_registered(&contract_data, &confirmation.oracle)
-            };
-
-            if is_authorized && confirmation.verified {
-                unique_oracle_count += 1;
-            }
+        if config.min_bond <= 0 || config.treasury_bps > 10_000 {
+            return Err(ContractError::InvalidAmount);
         }
 
-        Ok(unique_oracle_count >= threshold)
+        env.storage().instance().set(&BOND_CONFIG, &config);
+        env.events()
+            .publish((BOND_CONFIG,), (config.token, config.min_bond));
+        Ok(())
     }
 
-    // Helper functions
+    /// Get the bonding configuration, if bonded registration is enabled
+    pub fn get_bond_config(env: Env) -> Option<BondConfig> {
+        env.storage().instance().get(&BOND_CONFIG)
+    }
 
-    fn is_initialized(env: &Env) -> bool {
-        env.storage().instance().has(&symbol_short!("data"))
+    /// Set the addresses allowed to slash oracle bonds (admin only)
+    ///
+    /// Typically the EscrowManager (dispute rulings) and governance.
+    pub fn set_slashers(env: Env, slashers: Vec<Address>) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+        env.storage().instance().set(&SLASHERS, &slashers);
+        env.events().publish((SLASHERS,), (slashers,));
+        Ok(())
     }
 
-    fn get_contract_data(env: &Env) -> Result<ContractData, ContractError> {
+    /// Get the addresses allowed to slash oracle bonds
+    pub fn get_slashers(env: Env) -> Vec<Address> {
         env.storage()
             .instance()
-            .get(&symbol_short!("data"))
-            .ok_or(ContractError::EscrowNotFound)
+            .get(&SLASHERS)
+            .unwrap_or(Vec::new(&env))
     }
 
-    fn get_confirmations_for_escrow(
-        env: &Env,
-        escrow_id: Bytes,
-    ) -> Result<Vec<ConfirmationData>, ContractError> {
-        let contract_data = Self::get_contract_data(env)?;
-        let mut confirmations = Vec::new(env);
-
-        let confirming_key = (CONFIRMING_ORACLES, escrow_id.clone());
-        if env.storage().persistent().has(&confirming_key) {
-            let confirming_oracles = Self::get_confirming_oracles(env, &escrow_id);
-            for oracle in confirming_oracles.iter() {
-                let confirmation_key = (escrow_id.clone(), oracle.clone());
-                if let Some(confirmation) = env.storage().persistent().get(&confirmation_key) {
-                    confirmations.push_back(confirmation);
-                }
-            }
+    /// Register as an oracle by staking a bond, without admin involvement
+    ///
+    /// # Arguments
+    /// * `oracle` - The oracle address; must authorize the stake transfer
+    /// * `public_key` - ed25519 public key the oracle signs confirmations with
+    /// * `amount` - Stake to bond; at least the configured minimum
+    ///
+    /// # Events
+    /// Emits `orc_bond` and `ORACLE_ADDED` events
+    pub fn register_bonded(
+        env: Env,
+        oracle: Address,
+        public_key: BytesN<32>,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        oracle.require_auth();
 
-            return Ok(confirmations);
+        let config = Self::bond_config(&env)?;
+        if amount < config.min_bond {
+            return Err(ContractError::InsufficientBond);
         }
 
-        // Fallback for legacy data: iterate through all registered oracles
-        for oracle in contract_data.oracles.iter() {
-            let confirmation_key = (escrow_id.clone(), oracle.clone());
-            if let Some(confirmation) = env.storage().persistent().get(&confirmation_key) {
-                confirmations.push_back(confirmation);
-            }
+        let mut contract_data = Self::get_contract_data(&env)?;
+        if Self::is_oracle_registered(&contract_data, &oracle) {
+            return Err(ContractError::OracleAlreadyRegistered);
         }
 
-        Ok(confirmations)
-    }
+        token::Client::new(&env, &config.token).transfer(
+            &oracle,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        // Stake still unbonding from an earlier registration is kept as is
+        let bond_key = (BOND, oracle.clone());
+        let mut bond: Bond = env.storage().persistent().get(&bond_key).unwrap_or(Bond {
+            amount: 0,
+            unbonding: 0,
+            unbond_at: 0,
+        });
+        bond.amount += amount;
+        env.storage().persistent().set(&bond_key, &bond);
 
-    fn get_confirming_oracles(env: &Env, escrow_id: &Bytes) -> Vec<Address> {
-        let confirming_key = (CONFIRMING_ORACLES, escrow_id.clone());
+        contract_data.oracles.push_back(oracle.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("data"), &contract_data);
         env.storage()
             .persistent()
-            .get(&confirming_key)
-            .unwrap_or(Vec::new(env))
-    }
+            .set(&(ORACLE_KEY, oracle.clone()), &public_key);
 
-    fn check_admin(env: &Env) -> Result<(), ContractError> {
-        let contract_data = Self::get_contract_data(env)?;
-        contract_data.admin.require_auth();
+        env.events()
+            .publish((symbol_short!("orc_bond"),), (oracle.clone(), amount));
+        env.events().publish((ORACLE_ADDED,), (oracle,));
         Ok(())
     }
 
-    fn is_oracle_registered(contract_data: &ContractData, oracle: &Address) -> bool {
-        for registered_oracle in contract_data.oracles.iter() {
-            if registered_oracle == *oracle {
-                return true;
-            }
+    /// Queue part of an oracle's bond for withdrawal
+    ///
+    /// The amount stays slashable until `withdraw_unbonded` after the
+    /// unbonding period. Dropping below the minimum bond deregisters the
+    /// oracle. A new request restarts the delay for the whole queued amount.
+    ///
+    /// # Events
+    /// Emits `orc_unbnd` event
+    pub fn request_unbond(env: Env, oracle: Address, amount: i128) -> Result<u64, ContractError> {
+        oracle.require_auth();
+
+        let config = Self::bond_config(&env)?;
+        let bond_key = (BOND, oracle.clone());
+        let mut bond: Bond = env
+            .storage()
+            .persistent()
+            .get(&bond_key)
+            .ok_or(ContractError::NotBonded)?;
+
+        if amount <= 0 || amount > bond.amount {
+            return Err(ContractError::InvalidAmount);
         }
-        false
-    }
 
-    fn create_message(env: &Env, escrow_id: &Bytes, event_type: u32, result: &Bytes) -> BytesN<32> {
-        // Create a deterministic message hash for signature verification
-        let mut message_data = Bytes::new(env);
-        message_data.append(escrow_id);
+        bond.amount -= amount;
+        bond.unbonding += amount;
+        bond.unbond_at = env.ledger().timestamp() + config.unbonding_period;
+        env.storage().persistent().set(&bond_key, &bond);
+
+        if bond.amount < config.min_bond {
+            Self::deregister(&env, &oracle)?;
+        }
+
+        env.events().publish(
+            (symbol_short!("orc_unbnd"),),
+            (oracle, amount, bond.unbond_at),
+        );
+        Ok(bond.unbond_at)
+    }
+
+    /// Withdraw stake whose unbonding period has elapsed
+    ///
+    /// # Events
+    /// Emits `orc_wdrw` event
+    pub fn withdraw_unbonded(env: Env, oracle: Address) -> Result<i128, ContractError> {
+        oracle.require_auth();
+
+        let config = Self::bond_config(&env)?;
+        let bond_key = (BOND, oracle.clone());
+        let mut bond: Bond = env
+            .storage()
+            .persistent()
+            .get(&bond_key)
+            .ok_or(ContractError::NotBonded)?;
+
+        if bond.unbonding <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if env.ledger().timestamp() < bond.unbond_at {
+            return Err(ContractError::UnbondingLocked);
+        }
+
+        let amount = bond.unbonding;
+        bond.unbonding = 0;
+        env.storage().persistent().set(&bond_key, &bond);
+
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &oracle,
+            &amount,
+        );
+
+        env.events()
+            .publish((symbol_short!("orc_wdrw"),), (oracle, amount));
+        Ok(amount)
+    }
+
+    /// Slash an oracle whose confirmation was proven wrong
+    ///
+    /// Called by an approved slasher after a dispute ruling or governance
+    /// vote. The slash is taken from the active bond first, then from stake
+    /// still unbonding, and split between the treasury and the harmed party;
+    /// the treasury share is recorded through `ProtocolTreasury::deposit_fee`.
+    /// The confirmation is marked unverified so it no longer counts toward
+    /// consensus, and the oracle is deregistered if its bond falls below the
+    /// minimum. Each confirmation can be slashed only once.
+    ///
+    /// # Arguments
+    /// * `slasher` - Approved slasher authorizing the call
+    /// * `oracle` - The oracle to slash
+    /// * `escrow_id` - Escrow the wrong confirmation was for
+    /// * `event_type` - Event type of the wrong confirmation
+    /// * `amount` - Amount to slash; capped at the oracle's total stake
+    /// * `harmed` - Party compensated with the non-treasury share
+    ///
+    /// # Returns
+    /// The amount actually slashed
+    ///
+    /// # Events
+    /// Emits `orc_slash` event
+    #[allow(clippy::too_many_arguments)]
+    pub fn slash(
+        env: Env,
+        slasher: Address,
+        oracle: Address,
+        escrow_id: Bytes,
+        event_type: u32,
+        amount: i128,
+        harmed: Address,
+    ) -> Result<i128, ContractError> {
+        slasher.require_auth();
+        if !Self::get_slashers(env.clone()).contains(&slasher) {
+            return Err(ContractError::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let config = Self::bond_config(&env)?;
+
+        // A revoked confirmation no longer counts but remains slashable
+        let confirmation_key = (escrow_id.clone(), oracle.clone(), event_type);
+        let confirmation: Option<ConfirmationData> =
+            env.storage().persistent().get(&confirmation_key);
+        let revoked_key = (
+            CONFIRMATION_REVOKED,
+            escrow_id.clone(),
+            oracle.clone(),
+            event_type,
+        );
+        if confirmation.is_none() && !env.storage().persistent().has(&revoked_key) {
+            return Err(ContractError::ConfirmationNotFound);
+        }
+
+        let slashed_key = (SLASHED, escrow_id.clone(), oracle.clone(), event_type);
+        if env.storage().persistent().has(&slashed_key) {
+            return Err(ContractError::AlreadySlashed);
+        }
+
+        let bond_key = (BOND, oracle.clone());
+        let mut bond: Bond = env
+            .storage()
+            .persistent()
+            .get(&bond_key)
+            .ok_or(ContractError::NotBonded)?;
+
+        let bond_before = bond.amount + bond.unbonding;
+        let slashed = amount.min(bond_before);
+        let from_active = slashed.min(bond.amount);
+        bond.amount -= from_active;
+        bond.unbonding -= slashed - from_active;
+        env.storage().persistent().set(&bond_key, &bond);
+        env.storage().persistent().set(&slashed_key, &true);
+
+        // The confirmation was wrong; stop counting it toward consensus
+        if let Some(mut confirmation) = confirmation {
+            confirmation.verified = false;
+            env.storage()
+                .persistent()
+                .set(&confirmation_key, &confirmation);
+        }
+
+        let to_treasury = slashed * config.treasury_bps as i128 / 10_000;
+        let to_harmed = slashed - to_treasury;
+        let this = env.current_contract_address();
+        let token_client = token::Client::new(&env, &config.token);
+        if to_treasury > 0 {
+            // Record the share as a fee; the treasury only counts funds it holds
+            token_client.transfer(&this, &config.treasury, &to_treasury);
+            let deposit_args: Vec<Val> = Vec::from_array(
+                &env,
+                [
+                    this.into_val(&env),
+                    config.token.into_val(&env),
+                    to_treasury.into_val(&env),
+                ],
+            );
+            env.invoke_contract::<()>(
+                &config.treasury,
+                &Symbol::new(&env, "deposit_fee"),
+                deposit_args,
+            );
+        }
+        if to_harmed > 0 {
+            token_client.transfer(&this, &harmed, &to_harmed);
+        }
+
+        let slashes_key = (SLASHES, oracle.clone());
+        let mut history: Vec<SlashRecord> = env
+            .storage()
+            .persistent()
+            .get(&slashes_key)
+            .unwrap_or(Vec::new(&env));
+        history.push_back(SlashRecord {
+            escrow_id: escrow_id.clone(),
+            event_type,
+            amount: slashed,
+            bond_before,
+            harmed: harmed.clone(),
+            slashed_by: slasher,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&slashes_key, &history);
+
+        if bond.amount < config.min_bond {
+            Self::deregister(&env, &oracle)?;
+        }
+
+        env.events().publish(
+            (symbol_short!("orc_slash"),),
+            (oracle, escrow_id, event_type, slashed, bond_before, harmed),
+        );
+        Ok(slashed)
+    }
+
+    /// Get an oracle's bond, if it registered with one
+    pub fn get_bond(env: Env, oracle: Address) -> Option<Bond> {
+        env.storage().persistent().get(&(BOND, oracle))
+    }
+
+    /// Get every slash applied to an oracle, oldest first
+    pub fn get_slash_history(env: Env, oracle: Address) -> Vec<SlashRecord> {
+        env.storage()
+            .persistent()
+            .get(&(SLASHES, oracle))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Configure aggregation for a price feed (admin only)
+    ///
+    /// Feeds are identified by opaque bytes, e.g. a collateral id or a
+    /// commodity code, and can be reconfigured at any time.
+    ///
+    /// # Events
+    /// Emits `feed_cfg` event
+    pub fn set_feed_config(
+        env: Env,
+        feed_id: Bytes,
+        config: FeedConfig,
+    ) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+
+        if config.min_sources == 0 || config.max_deviation_bps > 10_000 || config.heartbeat == 0 {
+            return Err(ContractError::InvalidThreshold);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(FEED_CONFIG, feed_id.clone()), &config);
+        env.events().publish(
+            (FEED_CONFIG,),
+            (
+                feed_id,
+                config.min_sources,
+                config.max_deviation_bps,
+                config.heartbeat,
+            ),
+        );
+        Ok(())
+    }
+
+    /// Get the aggregation parameters of a price feed
+    pub fn get_feed_config(env: Env, feed_id: Bytes) -> Option<FeedConfig> {
+        env.storage().persistent().get(&(FEED_CONFIG, feed_id))
+    }
+
+    /// Submit a value for a price feed
+    ///
+    /// Each oracle holds one submission per feed; a new one replaces the
+    /// previous. After every submission the fresh values from registered
+    /// oracles are aggregated: values deviating from their median by more
+    /// than `max_deviation_bps` are discarded, and if at least `min_sources`
+    /// remain their median becomes the published price.
+    ///
+    /// # Arguments
+    /// * `oracle` - Registered oracle submitting the value
+    /// * `feed_id` - Feed to submit to
+    /// * `value` - Observed value; must be positive
+    ///
+    /// # Returns
+    /// The newly published price, or None if too few submissions agree
+    ///
+    /// # Events
+    /// Emits `price_dev` for each discarded submission and `price_upd` when a
+    /// price is published
+    pub fn submit_price(
+        env: Env,
+        oracle: Address,
+        feed_id: Bytes,
+        value: i128,
+    ) -> Result<Option<PriceData>, ContractError> {
+        oracle.require_auth();
+
+        let contract_data = Self::get_contract_data(&env)?;
+        if !Self::is_oracle_registered(&contract_data, &oracle) {
+            return Err(ContractError::OracleNotRegistered);
+        }
+        let config = Self::feed_config(&env, &feed_id)?;
+        if value <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let now = env.ledger().timestamp();
+        let submissions_key = (PRICE_SUBMISSIONS, feed_id.clone());
+        let submissions: Map<Address, PriceSubmission> = env
+            .storage()
+            .persistent()
+            .get(&submissions_key)
+            .unwrap_or(Map::new(&env));
+
+        // Drop stale submissions and those of deregistered oracles
+        let mut live = Map::new(&env);
+        for (submitter, submission) in submissions.iter() {
+            if Self::is_oracle_registered(&contract_data, &submitter)
+                && now <= submission.timestamp + config.heartbeat
+            {
+                live.set(submitter, submission);
+            }
+        }
+        live.set(
+            oracle,
+            PriceSubmission {
+                value,
+                timestamp: now,
+            },
+        );
+        env.storage().persistent().set(&submissions_key, &live);
+
+        if live.len() < config.min_sources {
+            return Ok(None);
+        }
+
+        let mut values = Vec::new(&env);
+        for (_, submission) in live.iter() {
+            values.push_back(submission.value);
+        }
+        let center = Self::median(&env, &values);
+
+        let mut accepted = Vec::new(&env);
+        for (submitter, submission) in live.iter() {
+            let deviation = (submission.value - center).abs();
+            if deviation.saturating_mul(10_000)
+                > center.saturating_mul(config.max_deviation_bps as i128)
+            {
+                env.events().publish(
+                    (symbol_short!("price_dev"),),
+                    (feed_id.clone(), submitter, submission.value, center),
+                );
+            } else {
+                accepted.push_back(submission.value);
+            }
+        }
+
+        if accepted.len() < config.min_sources {
+            return Ok(None);
+        }
+
+        let price = PriceData {
+            value: Self::median(&env, &accepted),
+            updated_at: now,
+            sources: accepted.len(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(PRICE, feed_id.clone()), &price);
+
+        env.events().publish(
+            (symbol_short!("price_upd"),),
+            (feed_id, price.value, price.sources),
+        );
+        Ok(Some(price))
+    }
+
+    /// Get the published price of a feed, refusing stale data
+    ///
+    /// # Returns
+    /// The price, or `StalePrice` if it is older than the feed heartbeat
+    pub fn get_price(env: Env, feed_id: Bytes) -> Result<PriceData, ContractError> {
+        let config = Self::feed_config(&env, &feed_id)?;
+        let price: PriceData = env
+            .storage()
+            .persistent()
+            .get(&(PRICE, feed_id))
+            .ok_or(ContractError::PriceUnavailable)?;
+
+        if env.ledger().timestamp() > price.updated_at + config.heartbeat {
+            return Err(ContractError::StalePrice);
+        }
+        Ok(price)
+    }
+
+    /// Get the last published price of a feed regardless of its age
+    pub fn get_latest_price(env: Env, feed_id: Bytes) -> Option<PriceData> {
+        env.storage().persistent().get(&(PRICE, feed_id))
+    }
+
+    // Helper functions
+
+    fn is_initialized(env: &Env) -> bool {
+        env.storage().instance().has(&symbol_short!("data"))
+    }
+
+    fn get_contract_data(env: &Env) -> Result<ContractData, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("data"))
+            .ok_or(ContractError::EscrowNotFound)
+    }
+
+    fn get_confirmations_for_escrow(
+        env: &Env,
+        escrow_id: Bytes,
+    ) -> Result<Vec<ConfirmationData>, ContractError> {
+        let mut confirmations = Vec::new(env);
+
+        for event_type in 1..=5u32 {
+            for confirmation in Self::get_confirmations_for_event(env, &escrow_id, event_type) {
+                confirmations.push_back(confirmation);
+            }
+        }
+
+        Ok(confirmations)
+    }
+
+    fn get_confirmations_for_event(
+        env: &Env,
+        escrow_id: &Bytes,
+        event_type: u32,
+    ) -> Vec<ConfirmationData> {
+        let mut confirmations = Vec::new(env);
+        for oracle in Self::get_confirming_oracles(env, escrow_id, event_type).iter() {
+            let confirmation_key = (escrow_id.clone(), oracle, event_type);
+            if let Some(confirmation) = env.storage().persistent().get(&confirmation_key) {
+                confirmations.push_back(confirmation);
+            }
+        }
+        confirmations
+    }
+
+    fn get_confirming_oracles(env: &Env, escrow_id: &Bytes, event_type: u32) -> Vec<Address> {
+        let confirming_key = (CONFIRMING_ORACLES, escrow_id.clone(), event_type);
+        env.storage()
+            .persistent()
+            .get(&confirming_key)
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Tally verified, authorized confirmations of `event_type` by result hash.
+    ///
+    /// Returns the leading result and its vote count, or None if there are no
+    /// votes or the lead is tied. Each oracle is counted at most once.
+    fn tally_results(
+        env: &Env,
+        escrow_id: &Bytes,
+        event_type: u32,
+        oracle_set: &Vec<Address>,
+    ) -> Result<Option<(Bytes, u32)>, ContractError> {
+        let contract_data = Self::get_contract_data(env)?;
+
+        let confirmations = Self::get_confirmations_for_event(env, escrow_id, event_type);
+
+        let now = env.ledger().timestamp();
+        let mut counted: Vec<Address> = Vec::new(env);
+        let mut votes: Map<BytesN<32>, (Bytes, u32)> = Map::new(env);
+
+        for confirmation in confirmations.iter() {
+            // Verify confirmed oracle is in the authorized set (or set is empty)
+            // and still registered
+            let is_authorized = (oracle_set.is_empty()
+                || oracle_set.contains(&confirmation.oracle))
+                && Self::is_oracle_registered(&contract_data, &confirmation.oracle);
+
+            let expired = confirmation.expires_at != 0 && now > confirmation.expires_at;
+
+            if !is_authorized
+                || !confirmation.verified
+                || confirmation.consumed
+                || expired
+                || counted.contains(&confirmation.oracle)
+            {
+                continue;
+            }
+            counted.push_back(confirmation.oracle.clone());
+
+            let result_hash: BytesN<32> = env.crypto().sha256(&confirmation.result).into();
+            let (result, count) = votes
+                .get(result_hash.clone())
+                .unwrap_or((confirmation.result.clone(), 0));
+            votes.set(result_hash, (result, count + 1));
+        }
+
+        let mut leader: Option<(Bytes, u32)> = None;
+        let mut runner_up: u32 = 0;
+        for (_, (result, count)) in votes.iter() {
+            match &leader {
+                Some((_, best)) if count <= *best => runner_up = runner_up.max(count),
+                Some((_, best)) => {
+                    runner_up = *best;
+                    leader = Some((result, count));
+                }
+                None => leader = Some((result, count)),
+            }
+        }
+
+        Ok(leader.filter(|(_, count)| *count > runner_up))
+    }
+
+    fn feed_config(env: &Env, feed_id: &Bytes) -> Result<FeedConfig, ContractError> {
+        env.storage()
+            .persistent()
+            .get(&(FEED_CONFIG, feed_id.clone()))
+            .ok_or(ContractError::FeedNotConfigured)
+    }
+
+    /// Median of a non-empty list; the two middle values are averaged for even lengths
+    fn median(env: &Env, values: &Vec<i128>) -> i128 {
+        let mut sorted: Vec<i128> = Vec::new(env);
+        for value in values.iter() {
+            let mut index = sorted.len();
+            while index > 0 && sorted.get_unchecked(index - 1) > value {
+                index -= 1;
+            }
+            sorted.insert(index, value);
+        }
+
+        let mid = sorted.len() / 2;
+        if sorted.len() % 2 == 1 {
+            sorted.get_unchecked(mid)
+        } else {
+            (sorted.get_unchecked(mid - 1) + sorted.get_unchecked(mid)) / 2
+        }
+    }
+
+    fn bond_config(env: &Env) -> Result<BondConfig, ContractError> {
+        env.storage()
+            .instance()
+            .get(&BOND_CONFIG)
+            .ok_or(ContractError::BondingNotConfigured)
+    }
+
+    /// Drop an oracle from the registry and forget its signing key.
+    ///
+    /// Returns false if the oracle was not registered.
+    fn deregister(env: &Env, oracle: &Address) -> Result<bool, ContractError> {
+        let mut contract_data = Self::get_contract_data(env)?;
+
+        // Find and remove oracle
+        let mut found = false;
+        let mut new_oracles = Vec::new(env);
+
+        for existing_oracle in contract_data.oracles.iter() {
+            if existing_oracle != *oracle {
+                new_oracles.push_back(existing_oracle);
+            } else {
+                found = true;
+            }
+        }
+
+        if !found {
+            return Ok(false);
+        }
+
+        contract_data.oracles = new_oracles;
+        env.storage()
+            .persistent()
+            .remove(&(ORACLE_KEY, oracle.clone()));
+
+        // Save updated data
+        env.storage()
+            .instance()
+            .set(&symbol_short!("data"), &contract_data);
+
+        // Note: Stale confirmations from removed oracles are automatically filtered
+        // by check_consensus which validates against current registration state.
+        // This prevents removed oracles from ever contributing to consensus.
+
+        // Emit event
+        env.events().publish((ORACLE_REMOVED,), (oracle.clone(),));
+
+        Ok(true)
+    }
+
+    fn check_admin(env: &Env) -> Result<(), ContractError> {
+        let contract_data = Self::get_contract_data(env)?;
+        contract_data.admin.require_auth();
+        Ok(())
+    }
+
+    fn check_governance(env: &Env) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&GOVERNANCE)
+            .ok_or(ContractError::Unauthorized)?;
+        governance.require_auth();
+        Ok(())
+    }
+
+    fn is_oracle_registered(contract_data: &ContractData, oracle: &Address) -> bool {
+        for registered_oracle in contract_data.oracles.iter() {
+            if registered_oracle == *oracle {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn create_message(
+        env: &Env,
+        escrow_id: &Bytes,
+        event_type: u32,
+        result: &Bytes,
+        nonce: u64,
+        expiry: u64,
+    ) -> BytesN<32> {
+        // Create a deterministic message hash for signature verification.
+        // Binding the adapter address keeps signatures from being replayed
+        // against another deployment; escrow_id is length-prefixed so it
+        // cannot bleed into the fixed-width fields that follow.
+        let mut message_data = env.current_contract_address().to_xdr(env);
+        message_data.append(&Bytes::from_slice(env, &escrow_id.len().to_be_bytes()));
+        message_data.append(escrow_id);
         message_data.append(&Bytes::from_slice(env, &event_type.to_be_bytes()));
+        message_data.append(&Bytes::from_slice(env, &nonce.to_be_bytes()));
+        message_data.append(&Bytes::from_slice(env, &expiry.to_be_bytes()));
         message_data.append(result);
 
         env.crypto().sha256(&message_data).into()
     }
 
     fn verify_signature(
-        _env: &Env,
-        _message: &BytesN<32>,
-        _signature: &Bytes,
+        env: &Env,
+        message: &BytesN<32>,
+        signature: &BytesN<64>,
         oracle: &Address,
     ) -> Result<(), ContractError> {
-        // In modern Soroban, we prefer require_auth()
-        // For this adapter, we'll ensure the oracle authorized the call
-        oracle.require_auth();
+        let public_key: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&(ORACLE_KEY, oracle.clone()))
+            .ok_or(ContractError::InvalidSignature)?;
+
+        // Traps on a bad signature
+        env.crypto()
+            .ed25519_verify(&public_key, &message.clone().into(), signature);
         Ok(())
     }
 }
@@ -460,11 +1476,101 @@ impl OracleAdapter {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
+    use ed25519_dalek::{Signer, SigningKey};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
     use soroban_sdk::{
         testutils::MockAuth, testutils::MockAuthInvoke, Address, Bytes, Env, IntoVal,
     };
 
+    /// ProtocolTreasury stand-in recording fee deposits per asset
+    #[contract]
+    pub struct MockTreasury;
+
+    #[contractimpl]
+    impl MockTreasury {
+        pub fn deposit_fee(env: Env, depositor: Address, asset: Address, amount: i128) {
+            depositor.require_auth();
+            let key = (symbol_short!("fees"), asset);
+            let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(current + amount));
+        }
+
+        pub fn get_total_fees(env: Env, asset: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("fees"), asset))
+                .unwrap_or(0)
+        }
+    }
+
+    /// Generate an oracle address together with its ed25519 signing key
+    fn new_oracle(env: &Env, seed: u8) -> (Address, SigningKey) {
+        (Address::generate(env), SigningKey::from_bytes(&[seed; 32]))
+    }
+
+    fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+        BytesN::from_array(env, &key.verifying_key().to_bytes())
+    }
+
+    /// Sign a confirmation with the oracle's next nonce, returning
+    /// `(nonce, expiry, signature)` ready to pass to `confirm_event`
+    fn sign(
+        env: &Env,
+        contract_id: &Address,
+        oracle: &Address,
+        key: &SigningKey,
+        escrow_id: &Bytes,
+        event_type: u32,
+        result: &Bytes,
+    ) -> (u64, u64, BytesN<64>) {
+        let nonce = OracleAdapterClient::new(env, contract_id).get_nonce(oracle);
+        sign_with_nonce(env, contract_id, key, escrow_id, event_type, result, nonce)
+    }
+
+    /// Sign a confirmation carrying a chosen nonce
+    fn sign_with_nonce(
+        env: &Env,
+        contract_id: &Address,
+        key: &SigningKey,
+        escrow_id: &Bytes,
+        event_type: u32,
+        result: &Bytes,
+        nonce: u64,
+    ) -> (u64, u64, BytesN<64>) {
+        let expiry = env.ledger().timestamp() + 3600;
+        let message = env.as_contract(contract_id, || {
+            OracleAdapter::create_message(env, escrow_id, event_type, result, nonce, expiry)
+        });
+        let signature = key.sign(&message.to_array()).to_bytes();
+        (nonce, expiry, BytesN::from_array(env, &signature))
+    }
+
+    /// Sign and submit a confirmation, returning whether it was accepted
+    fn confirm(
+        env: &Env,
+        contract_id: &Address,
+        oracle: &Address,
+        key: &SigningKey,
+        escrow_id: &Bytes,
+        event_type: u32,
+        result: &Bytes,
+    ) -> bool {
+        let client = OracleAdapterClient::new(env, contract_id);
+        let (nonce, expiry, signature) =
+            sign(env, contract_id, oracle, key, escrow_id, event_type, result);
+        client
+            .try_confirm_event(
+                oracle,
+                escrow_id,
+                &event_type,
+                result,
+                &nonce,
+                &expiry,
+                &signature,
+            )
+            .is_ok()
+    }
+
     #[test]
     fn test_initialization() {
         let env = Env::default();
@@ -494,8 +1600,10 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
+        let pk1 = public_key(&env, &key1);
+        let pk2 = public_key(&env, &key2);
         let unauthorized = Address::generate(&env);
 
         // Initialize
@@ -510,13 +1618,14 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle1.clone(),).into_val(&env),
+                args: (oracle1.clone(), pk1.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        client.add_oracle(&oracle1);
+        client.add_oracle(&oracle1, &pk1);
         assert_eq!(client.is_oracle_registered_query(&oracle1), true);
         assert_eq!(client.get_oracle_count(), 1);
+        assert_eq!(client.get_oracle_key(&oracle1), Some(pk1.clone()));
 
         // Test adding second oracle
         env.mock_auths(&[MockAuth {
@@ -524,11 +1633,11 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle2.clone(),).into_val(&env),
+                args: (oracle2.clone(), pk2.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        client.add_oracle(&oracle2);
+        client.add_oracle(&oracle2, &pk2);
         assert_eq!(client.is_oracle_registered_query(&oracle2), true);
         assert_eq!(client.get_oracle_count(), 2);
 
@@ -538,26 +1647,27 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle1.clone(),).into_val(&env),
+                args: (oracle1.clone(), pk1.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
         assert_eq!(
-            client.try_add_oracle(&oracle1),
+            client.try_add_oracle(&oracle1, &pk1),
             Err(Ok(ContractError::OracleAlreadyRegistered))
         );
 
         // Test unauthorized add fails
+        let stranger = Address::generate(&env);
         env.mock_auths(&[MockAuth {
             address: &unauthorized,
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (Address::generate(&env),).into_val(&env),
+                args: (stranger.clone(), pk1.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        assert!(client.try_add_oracle(&Address::generate(&env)).is_err());
+        assert!(client.try_add_oracle(&stranger, &pk1).is_err());
 
         // Test removing oracle
         env.mock_auths(&[MockAuth {
@@ -572,6 +1682,7 @@ mod test {
         client.remove_oracle(&oracle1);
         assert_eq!(client.is_oracle_registered_query(&oracle1), false);
         assert_eq!(client.get_oracle_count(), 1);
+        assert_eq!(client.get_oracle_key(&oracle1), None);
 
         // Test removing non-existent oracle fails
         env.mock_auths(&[MockAuth {
@@ -609,26 +1720,33 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
 
         // Initialize and add oracle
         client.initialize(&admin);
-        client.add_oracle(&oracle);
+        client.add_oracle(&oracle, &public_key(&env, &key));
 
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Test invalid event type (0)
         let escrow_id = Bytes::from_slice(&env, b"escrow_0");
+        let (nonce, expiry, signature) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_id, 0, &result);
         assert_eq!(
-            client.try_confirm_event(&oracle, &escrow_id, &0u32, &result, &signature),
+            client.try_confirm_event(
+                &oracle, &escrow_id, &0u32, &result, &nonce, &expiry, &signature
+            ),
             Err(Ok(ContractError::InvalidEventType))
         );
 
         // Test invalid event type (6)
         let escrow_id = Bytes::from_slice(&env, b"escrow_6");
+        let (nonce, expiry, signature) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_id, 6, &result);
         assert_eq!(
-            client.try_confirm_event(&oracle, &escrow_id, &6u32, &result, &signature),
+            client.try_confirm_event(
+                &oracle, &escrow_id, &6u32, &result, &nonce, &expiry, &signature
+            ),
             Err(Ok(ContractError::InvalidEventType))
         );
 
@@ -642,9 +1760,15 @@ mod test {
         ];
         for (i, event_type) in (1..=5).enumerate() {
             let escrow_id = Bytes::from_slice(&env, escrow_ids[i]);
-            let confirm_result =
-                client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature);
-            assert!(confirm_result.is_ok());
+            assert!(confirm(
+                &env,
+                &contract_id,
+                &oracle,
+                &key,
+                &escrow_id,
+                event_type,
+                &result
+            ));
         }
     }
 
@@ -656,28 +1780,262 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
 
         // Initialize and add oracle
         client.initialize(&admin);
-        client.add_oracle(&oracle);
+        client.add_oracle(&oracle, &public_key(&env, &key));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
         let event_type = 1u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // First confirmation should work
-        // Note: verify_signature is now just require_auth(), so it should pass with mock_all_auths
-        let confirm_result =
-            client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature);
-        assert!(confirm_result.is_ok());
+        let (nonce, expiry, signature) = sign(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            event_type,
+            &result,
+        );
+        client.confirm_event(
+            &oracle,
+            &escrow_id,
+            &event_type,
+            &result,
+            &nonce,
+            &expiry,
+            &signature,
+        );
+        assert_eq!(client.get_nonce(&oracle), nonce + 1);
 
-        // Second confirmation from same oracle should fail (replay attack)
+        // Resubmitting the same signed message fails
         assert_eq!(
-            client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature),
+            client.try_confirm_event(
+                &oracle,
+                &escrow_id,
+                &event_type,
+                &result,
+                &nonce,
+                &expiry,
+                &signature
+            ),
             Err(Ok(ContractError::ConfirmationAlreadyExists))
         );
+
+        // A freshly signed second confirmation from same oracle should fail
+        let (nonce, expiry, signature) = sign(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            event_type,
+            &result,
+        );
+        assert_eq!(
+            client.try_confirm_event(
+                &oracle,
+                &escrow_id,
+                &event_type,
+                &result,
+                &nonce,
+                &expiry,
+                &signature
+            ),
+            Err(Ok(ContractError::ConfirmationAlreadyExists))
+        );
+    }
+
+    #[test]
+    fn test_spent_nonce_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_a = Bytes::from_slice(&env, b"escrow_a");
+        let escrow_b = Bytes::from_slice(&env, b"escrow_b");
+        let result = Bytes::from_slice(&env, b"confirmed");
+
+        // Both messages are signed against nonce 0; only the first lands
+        let (nonce_a, expiry_a, sig_a) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_a, 1, &result);
+        let (nonce_b, expiry_b, sig_b) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_b, 1, &result);
+        client.confirm_event(
+            &oracle, &escrow_a, &1u32, &result, &nonce_a, &expiry_a, &sig_a,
+        );
+
+        assert_eq!(
+            client
+                .try_confirm_event(&oracle, &escrow_b, &1u32, &result, &nonce_b, &expiry_b, &sig_b),
+            Err(Ok(ContractError::InvalidNonce))
+        );
+    }
+
+    #[test]
+    fn test_nonces_accepted_out_of_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&Address::generate(&env));
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_a = Bytes::from_slice(&env, b"escrow_a");
+        let escrow_b = Bytes::from_slice(&env, b"escrow_b");
+        let escrow_c = Bytes::from_slice(&env, b"escrow_c");
+        let result = Bytes::from_slice(&env, b"confirmed");
+
+        // The submission signed second lands first; the stuck one still goes through
+        let (nonce_a, expiry_a, sig_a) =
+            sign_with_nonce(&env, &contract_id, &key, &escrow_a, 1, &result, 0);
+        let (nonce_b, expiry_b, sig_b) =
+            sign_with_nonce(&env, &contract_id, &key, &escrow_b, 1, &result, 1);
+        client.confirm_event(
+            &oracle, &escrow_b, &1u32, &result, &nonce_b, &expiry_b, &sig_b,
+        );
+        assert_eq!(client.get_nonce(&oracle), 2);
+        assert!(!client.is_nonce_used(&oracle, &0));
+
+        client.confirm_event(
+            &oracle, &escrow_a, &1u32, &result, &nonce_a, &expiry_a, &sig_a,
+        );
+        assert!(client.is_nonce_used(&oracle, &0));
+        assert_eq!(client.get_nonce(&oracle), 2);
+
+        // Each nonce is still accepted only once
+        let (nonce_c, expiry_c, sig_c) =
+            sign_with_nonce(&env, &contract_id, &key, &escrow_c, 1, &result, 1);
+        assert_eq!(
+            client
+                .try_confirm_event(&oracle, &escrow_c, &1u32, &result, &nonce_c, &expiry_c, &sig_c),
+            Err(Ok(ContractError::InvalidNonce))
+        );
+    }
+
+    #[test]
+    fn test_signature_not_replayable_across_escrows() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_a = Bytes::from_slice(&env, b"escrow_a");
+        let escrow_b = Bytes::from_slice(&env, b"escrow_b");
+        let result = Bytes::from_slice(&env, b"confirmed");
+
+        // Signature for escrow_a submitted against escrow_b traps in verification
+        let (nonce, expiry, signature) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_a, 1, &result);
+        assert!(client
+            .try_confirm_event(&oracle, &escrow_b, &1u32, &result, &nonce, &expiry, &signature)
+            .is_err());
+        assert_eq!(client.get_nonce(&oracle), 0);
+        assert_eq!(client.get_confirmation(&escrow_b), None);
+
+        // The genuine submission still goes through
+        client.confirm_event(
+            &oracle, &escrow_a, &1u32, &result, &nonce, &expiry, &signature,
+        );
+    }
+
+    #[test]
+    fn test_signature_from_wrong_key_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
+        let impostor = SigningKey::from_bytes(&[9; 32]);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let result = Bytes::from_slice(&env, b"confirmed");
+
+        assert!(!confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &impostor,
+            &escrow_id,
+            1,
+            &result
+        ));
+        assert_eq!(client.get_confirmation(&escrow_id), None);
+    }
+
+    #[test]
+    fn test_expired_signature_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let result = Bytes::from_slice(&env, b"confirmed");
+
+        let (nonce, expiry, signature) =
+            sign(&env, &contract_id, &oracle, &key, &escrow_id, 1, &result);
+        env.ledger().with_mut(|li| li.timestamp = expiry + 1);
+
+        assert_eq!(
+            client.try_confirm_event(
+                &oracle, &escrow_id, &1u32, &result, &nonce, &expiry, &signature
+            ),
+            Err(Ok(ContractError::SignatureExpired))
+        );
+    }
+
+    #[test]
+    fn test_relayer_submits_without_oracle_auth() {
+        let env = Env::default();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&admin);
+        env.mock_all_auths();
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        // No auths mocked: the signature alone authorizes the confirmation
+        env.mock_auths(&[]);
+        let escrow_id = Bytes::from_slice(&env, b"escrow_relayed");
+        let result = Bytes::from_slice(&env, b"confirmed");
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+        assert_eq!(client.get_confirmation(&escrow_id).unwrap().len(), 1);
     }
 
     #[test]
@@ -687,7 +2045,7 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let unauthorized_oracle = Address::generate(&env);
+        let (unauthorized_oracle, key) = new_oracle(&env, 1);
 
         // Initialize without adding the oracle
         client.initialize(&admin);
@@ -695,7 +2053,15 @@ mod test {
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
         let event_type = 1u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
+        let (nonce, expiry, signature) = sign(
+            &env,
+            &contract_id,
+            &unauthorized_oracle,
+            &key,
+            &escrow_id,
+            event_type,
+            &result,
+        );
 
         // Confirmation from unregistered oracle should fail
         assert_eq!(
@@ -704,6 +2070,8 @@ mod test {
                 &escrow_id,
                 &event_type,
                 &result,
+                &nonce,
+                &expiry,
                 &signature
             ),
             Err(Ok(ContractError::OracleNotRegistered))
@@ -735,8 +2103,8 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
 
         // Initialize
         client.initialize(&admin);
@@ -745,8 +2113,8 @@ mod test {
         assert_eq!(client.get_oracle_count(), 0);
 
         // Add oracles
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
         assert_eq!(client.get_oracle_count(), 2);
 
         // Test oracle registration queries
@@ -777,9 +2145,18 @@ mod test {
         let result = Bytes::from_slice(&env, b"confirmed");
 
         env.as_contract(&contract_id, || {
-            let message = OracleAdapter::create_message(&env, &escrow_id, event_type, &result);
+            let message =
+                OracleAdapter::create_message(&env, &escrow_id, event_type, &result, 0, 100);
             // Message should be a valid hash
             assert_eq!(message.len(), 32);
+
+            // Nonce and expiry are bound into the message
+            let next_nonce =
+                OracleAdapter::create_message(&env, &escrow_id, event_type, &result, 1, 100);
+            let later_expiry =
+                OracleAdapter::create_message(&env, &escrow_id, event_type, &result, 0, 101);
+            assert_ne!(message, next_nonce);
+            assert_ne!(message, later_expiry);
         });
     }
 
@@ -791,39 +2168,50 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
-        let oracle3 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
+        let (oracle3, key3) = new_oracle(&env, 3);
 
         // Initialize and add oracles
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
-        client.add_oracle(&oracle3);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
+        client.add_oracle(&oracle3, &public_key(&env, &key3));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_multi_1");
         let event_type = 2u32; // Delivery
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // First oracle confirms
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Second oracle confirms
-        assert!(client
-            .try_confirm_event(&oracle2, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Create oracle set with all 3 oracles
         let oracle_set = Vec::from_array(&env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
 
         // Check consensus with threshold 2 - should be met (2 confirmations)
-        assert!(client.check_consensus(&escrow_id, &2u32, &oracle_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &2u32, &oracle_set));
 
         // Check consensus with threshold 3 - should not be met (only 2 confirmations)
-        assert!(!client.check_consensus(&escrow_id, &3u32, &oracle_set));
+        assert!(!client.check_consensus(&escrow_id, &event_type, &3u32, &oracle_set));
     }
 
     #[test]
@@ -834,41 +2222,52 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
-        let oracle3 = Address::generate(&env);
-        let unauthorized_oracle = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
+        let (oracle3, key3) = new_oracle(&env, 3);
+        let (unauthorized_oracle, key4) = new_oracle(&env, 4);
 
         // Initialize and add oracles
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
-        client.add_oracle(&oracle3);
-        client.add_oracle(&unauthorized_oracle);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
+        client.add_oracle(&oracle3, &public_key(&env, &key3));
+        client.add_oracle(&unauthorized_oracle, &public_key(&env, &key4));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_multi_2");
         let event_type = 2u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Oracle 1 and 2 confirm
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
-        assert!(client
-            .try_confirm_event(&oracle2, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Unauthorized oracle also confirms
-        assert!(client
-            .try_confirm_event(
-                &unauthorized_oracle,
-                &escrow_id,
-                &event_type,
-                &result,
-                &signature
-            )
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &unauthorized_oracle,
+            &key4,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Create restricted oracle set (only oracle1, oracle2, oracle3)
         let restricted_oracle_set =
@@ -876,7 +2275,7 @@ mod test {
 
         // Check consensus with restricted set - should only count oracle1 and oracle2 (2 confirmations)
         // unauthorized_oracle is not in the set, so it shouldn't count
-        assert!(client.check_consensus(&escrow_id, &2u32, &restricted_oracle_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &2u32, &restricted_oracle_set));
 
         // Create full oracle set including unauthorized
         let full_oracle_set = Vec::from_array(
@@ -885,7 +2284,7 @@ mod test {
         );
 
         // With all oracles in set, should have 3 confirmations
-        assert!(client.check_consensus(&escrow_id, &3u32, &full_oracle_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &3u32, &full_oracle_set));
     }
 
     #[test]
@@ -896,40 +2295,51 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
 
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_collusion");
         let event_type = 2u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Oracle 1 confirms
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Only oracle1 is in the authorized set
         let restricted_set = Vec::from_array(&env, [oracle1.clone()]);
 
         // Consensus should require 1 oracle, which is met
-        assert!(client.check_consensus(&escrow_id, &1u32, &restricted_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &1u32, &restricted_set));
 
         // But if we require both oracles to confirm and they're in the set:
         let both_set = Vec::from_array(&env, [oracle1, oracle2.clone()]);
-        assert!(!client.check_consensus(&escrow_id, &2u32, &both_set));
+        assert!(!client.check_consensus(&escrow_id, &event_type, &2u32, &both_set));
 
         // Oracle 2 confirms
-        assert!(client
-            .try_confirm_event(&oracle2, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Now both oracles have confirmed
-        assert!(client.check_consensus(&escrow_id, &2u32, &both_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &2u32, &both_set));
     }
 
     #[test]
@@ -940,31 +2350,42 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
-        let oracle2 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
 
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_any");
         let event_type = 2u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Both oracles confirm
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
-        assert!(client
-            .try_confirm_event(&oracle2, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Empty oracle set means any registered oracle can confirm
         let empty_set = Vec::new(&env);
 
         // Should count both confirmations
-        assert!(client.check_consensus(&escrow_id, &2u32, &empty_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &2u32, &empty_set));
     }
 
     #[test]
@@ -975,20 +2396,25 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
 
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_verified");
         let event_type = 2u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Confirm from oracle1
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         // Get the confirmation to verify it's marked as verified
         let confirmations = client.get_confirmation(&escrow_id).unwrap();
@@ -998,7 +2424,7 @@ mod test {
         let oracle_set = Vec::from_array(&env, [oracle1]);
 
         // Check consensus - should count the verified confirmation
-        assert!(client.check_consensus(&escrow_id, &1u32, &oracle_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &1u32, &oracle_set));
     }
 
     #[test]
@@ -1009,31 +2435,696 @@ mod test {
         let client = OracleAdapterClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let oracle1 = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
 
         client.initialize(&admin);
-        client.add_oracle(&oracle1);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_zero_threshold");
         let event_type = 2u32;
         let result = Bytes::from_slice(&env, b"confirmed");
-        let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Confirm from oracle1
-        assert!(client
-            .try_confirm_event(&oracle1, &escrow_id, &event_type, &result, &signature)
-            .is_ok());
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &result
+        ));
 
         let oracle_set = Vec::from_array(&env, [oracle1]);
 
         // Check consensus with threshold 0 should fail
         assert_eq!(
-            client.try_check_consensus(&escrow_id, &0u32, &oracle_set),
+            client.try_check_consensus(&escrow_id, &event_type, &0u32, &oracle_set),
             Err(Ok(ContractError::InvalidThreshold))
         );
 
         // Threshold 1 should succeed
-        assert!(client.check_consensus(&escrow_id, &1u32, &oracle_set));
+        assert!(client.check_consensus(&escrow_id, &event_type, &1u32, &oracle_set));
+    }
+
+    #[test]
+    fn test_consensus_is_per_event_type() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
+
+        client.initialize(&admin);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_events");
+        let result = Bytes::from_slice(&env, b"confirmed");
+        let oracle_set = Vec::from_array(&env, [oracle1.clone(), oracle2.clone()]);
+
+        // Shipment confirmations do not satisfy a Delivery requirement
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            1,
+            &result
+        ));
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            1,
+            &result
+        ));
+        assert!(client.check_consensus(&escrow_id, &1u32, &2u32, &oracle_set));
+        assert!(!client.check_consensus(&escrow_id, &2u32, &1u32, &oracle_set));
+
+        // The same oracle may confirm a different event on the same escrow, once
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            2,
+            &result
+        ));
+        assert!(!confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            2,
+            &result
+        ));
+        assert!(client.check_consensus(&escrow_id, &2u32, &1u32, &oracle_set));
+        assert!(!client.check_consensus(&escrow_id, &2u32, &2u32, &oracle_set));
+
+        assert_eq!(client.get_confirmation(&escrow_id).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_consensus_requires_agreeing_results() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let (oracle1, key1) = new_oracle(&env, 1);
+        let (oracle2, key2) = new_oracle(&env, 2);
+        let (oracle3, key3) = new_oracle(&env, 3);
+
+        client.initialize(&admin);
+        client.add_oracle(&oracle1, &public_key(&env, &key1));
+        client.add_oracle(&oracle2, &public_key(&env, &key2));
+        client.add_oracle(&oracle3, &public_key(&env, &key3));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_results");
+        let event_type = 3u32; // Quality
+        let passed = Bytes::from_slice(&env, b"passed");
+        let failed = Bytes::from_slice(&env, b"failed");
+        let oracle_set = Vec::from_array(&env, [oracle1.clone(), oracle2.clone(), oracle3.clone()]);
+
+        // A 1-1 split has no majority result
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle1,
+            &key1,
+            &escrow_id,
+            event_type,
+            &passed
+        ));
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle2,
+            &key2,
+            &escrow_id,
+            event_type,
+            &failed
+        ));
+        assert!(!client.check_consensus(&escrow_id, &event_type, &1u32, &oracle_set));
+        assert_eq!(
+            client.get_consensus_result(&escrow_id, &event_type, &oracle_set),
+            None
+        );
+
+        // A third oracle breaks the tie; only the two agreeing oracles count
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle3,
+            &key3,
+            &escrow_id,
+            event_type,
+            &passed
+        ));
+        assert!(client.check_consensus(&escrow_id, &event_type, &2u32, &oracle_set));
+        assert!(!client.check_consensus(&escrow_id, &event_type, &3u32, &oracle_set));
+        assert_eq!(
+            client.get_consensus_result(&escrow_id, &event_type, &oracle_set),
+            Some(passed)
+        );
+    }
+
+    #[test]
+    fn test_revoke_and_correct_confirmation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&Address::generate(&env));
+        client.add_oracle(&oracle, &public_key(&env, &key));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_revoke");
+        let wrong = Bytes::from_slice(&env, b"failed");
+        let corrected = Bytes::from_slice(&env, b"passed");
+        let oracle_set = Vec::from_array(&env, [oracle.clone()]);
+
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            3,
+            &wrong
+        ));
+        client.revoke_confirmation(&oracle, &escrow_id, &3u32);
+
+        assert_eq!(client.get_confirmation(&escrow_id), None);
+        assert!(!client.check_consensus(&escrow_id, &3u32, &1u32, &oracle_set));
+        assert_eq!(
+            client.try_revoke_confirmation(&oracle, &escrow_id, &3u32),
+            Err(Ok(ContractError::ConfirmationNotFound))
+        );
+
+        // The oracle can confirm again with the corrected result
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            3,
+            &corrected
+        ));
+        assert_eq!(
+            client.get_consensus_result(&escrow_id, &3u32, &oracle_set),
+            Some(corrected)
+        );
+    }
+
+    #[test]
+    fn test_consumed_confirmation_cannot_be_revoked() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let (oracle, key) = new_oracle(&env, 1);
+        let escrow_manager = Address::generate(&env);
+        client.initialize(&Address::generate(&env));
+        client.add_oracle(&oracle, &public_key(&env, &key));
+        client.set_consumers(&Vec::from_array(&env, [escrow_manager.clone()]));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_consumed");
+        let result = Bytes::from_slice(&env, b"delivered");
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_mark_consumed(&stranger, &escrow_id, &2u32),
+            Err(Ok(ContractError::Unauthorized))
+        );
+
+        let oracle_set = Vec::from_array(&env, [oracle.clone()]);
+        assert!(client.check_consensus(&escrow_id, &2u32, &1u32, &oracle_set));
+        assert_eq!(client.mark_consumed(&escrow_manager, &escrow_id, &2u32), 1);
+        let confirmation = client.get_confirmation(&escrow_id).unwrap().get(0).unwrap();
+        assert!(confirmation.consumed);
+
+        // A used confirmation no longer counts toward another release
+        assert!(!client.check_consensus(&escrow_id, &2u32, &1u32, &oracle_set));
+        assert_eq!(
+            client.try_mark_consumed(&escrow_manager, &escrow_id, &2u32),
+            Err(Ok(ContractError::AlreadyConsumed))
+        );
+        assert_eq!(
+            client.try_revoke_confirmation(&oracle, &escrow_id, &2u32),
+            Err(Ok(ContractError::ConfirmationConsumed))
+        );
+    }
+
+    #[test]
+    fn test_confirmation_ttl() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let (oracle, key) = new_oracle(&env, 1);
+        client.initialize(&Address::generate(&env));
+        client.add_oracle(&oracle, &public_key(&env, &key));
+        client.set_confirmation_ttl(&600);
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_ttl");
+        let result = Bytes::from_slice(&env, b"shipped");
+        let oracle_set = Vec::from_array(&env, [oracle.clone()]);
+        assert!(confirm(
+            &env,
+            &contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            1,
+            &result
+        ));
+
+        let confirmation = client.get_confirmation(&escrow_id).unwrap().get(0).unwrap();
+        assert_eq!(confirmation.expires_at, env.ledger().timestamp() + 600);
+        assert!(client.check_consensus(&escrow_id, &1u32, &1u32, &oracle_set));
+
+        env.ledger().with_mut(|li| li.timestamp += 601);
+        assert!(!client.check_consensus(&escrow_id, &1u32, &1u32, &oracle_set));
     }
-}
 
+    struct BondedSetup {
+        env: Env,
+        contract_id: Address,
+        client: OracleAdapterClient<'static>,
+        token: Address,
+        treasury: Address,
+        slasher: Address,
+    }
+
+    fn setup_bonded() -> BondedSetup {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let client = unsafe {
+            core::mem::transmute::<OracleAdapterClient<'_>, OracleAdapterClient<'static>>(client)
+        };
+
+        let admin = Address::generate(&env);
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let treasury = env.register(MockTreasury, ());
+        let slasher = Address::generate(&env);
+
+        client.initialize(&admin);
+        client.set_bond_config(&BondConfig {
+            token: token.clone(),
+            min_bond: 1_000,
+            unbonding_period: 86_400,
+            treasury: treasury.clone(),
+            treasury_bps: 2_000,
+        });
+        client.set_slashers(&Vec::from_array(&env, [slasher.clone()]));
+
+        BondedSetup {
+            env,
+            contract_id,
+            client,
+            token,
+            treasury,
+            slasher,
+        }
+    }
+
+    fn bonded_oracle(t: &BondedSetup, seed: u8, bond: i128) -> (Address, SigningKey) {
+        let (oracle, key) = new_oracle(&t.env, seed);
+        token::StellarAssetClient::new(&t.env, &t.token).mint(&oracle, &bond);
+        t.client
+            .register_bonded(&oracle, &public_key(&t.env, &key), &bond);
+        (oracle, key)
+    }
+
+    #[test]
+    fn test_register_bonded_requires_min_bond() {
+        let t = setup_bonded();
+        let (oracle, key) = new_oracle(&t.env, 1);
+        token::StellarAssetClient::new(&t.env, &t.token).mint(&oracle, &5_000);
+
+        assert_eq!(
+            t.client
+                .try_register_bonded(&oracle, &public_key(&t.env, &key), &999),
+            Err(Ok(ContractError::InsufficientBond))
+        );
+
+        t.client
+            .register_bonded(&oracle, &public_key(&t.env, &key), &1_500);
+        assert!(t.client.is_oracle_registered_query(&oracle));
+        assert_eq!(t.client.get_bond(&oracle).unwrap().amount, 1_500);
+
+        let tok = token::Client::new(&t.env, &t.token);
+        assert_eq!(tok.balance(&oracle), 3_500);
+        assert_eq!(tok.balance(&t.contract_id), 1_500);
+    }
+
+    #[test]
+    fn test_register_bonded_requires_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+
+        let (oracle, key) = new_oracle(&env, 1);
+        assert_eq!(
+            client.try_register_bonded(&oracle, &public_key(&env, &key), &1_000),
+            Err(Ok(ContractError::BondingNotConfigured))
+        );
+    }
+
+    #[test]
+    fn test_unbonding_goes_through_delay() {
+        let t = setup_bonded();
+        let (oracle, _) = bonded_oracle(&t, 1, 3_000);
+        let tok = token::Client::new(&t.env, &t.token);
+
+        // Partial unbond keeps the oracle registered above the minimum
+        let unbond_at = t.client.request_unbond(&oracle, &1_000);
+        assert!(t.client.is_oracle_registered_query(&oracle));
+        assert_eq!(
+            t.client.get_bond(&oracle).unwrap(),
+            Bond {
+                amount: 2_000,
+                unbonding: 1_000,
+                unbond_at,
+            }
+        );
+
+        // Dropping below the minimum deregisters it
+        t.client.request_unbond(&oracle, &1_500);
+        assert!(!t.client.is_oracle_registered_query(&oracle));
+
+        assert_eq!(
+            t.client.try_withdraw_unbonded(&oracle),
+            Err(Ok(ContractError::UnbondingLocked))
+        );
+
+        t.env.ledger().with_mut(|li| li.timestamp += 86_400);
+        assert_eq!(t.client.withdraw_unbonded(&oracle), 2_500);
+        assert_eq!(tok.balance(&oracle), 2_500);
+        assert_eq!(t.client.get_bond(&oracle).unwrap().amount, 500);
+    }
+
+    #[test]
+    fn test_slash_splits_bond_and_invalidates_confirmation() {
+        let t = setup_bonded();
+        let (oracle, key) = bonded_oracle(&t, 1, 2_000);
+        let harmed = Address::generate(&t.env);
+
+        let escrow_id = Bytes::from_slice(&t.env, b"escrow_slash");
+        let result = Bytes::from_slice(&t.env, b"delivered");
+        assert!(confirm(
+            &t.env,
+            &t.contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+        let empty_set = Vec::new(&t.env);
+        assert!(t
+            .client
+            .check_consensus(&escrow_id, &2u32, &1u32, &empty_set));
+
+        let slashed = t
+            .client
+            .slash(&t.slasher, &oracle, &escrow_id, &2u32, &500, &harmed);
+        assert_eq!(slashed, 500);
+
+        // 20% to the treasury, the rest to the harmed party
+        let tok = token::Client::new(&t.env, &t.token);
+        assert_eq!(tok.balance(&t.treasury), 100);
+        assert_eq!(tok.balance(&harmed), 400);
+        assert_eq!(
+            MockTreasuryClient::new(&t.env, &t.treasury).get_total_fees(&t.token),
+            100
+        );
+        assert_eq!(t.client.get_bond(&oracle).unwrap().amount, 1_500);
+
+        // The wrong confirmation no longer counts
+        assert!(!t
+            .client
+            .check_consensus(&escrow_id, &2u32, &1u32, &empty_set));
+
+        let history = t.client.get_slash_history(&oracle);
+        assert_eq!(history.len(), 1);
+        let record = history.get(0).unwrap();
+        assert_eq!(record.amount, 500);
+        assert_eq!(record.bond_before, 2_000);
+        assert_eq!(record.harmed, harmed);
+        assert_eq!(record.slashed_by, t.slasher);
+
+        // The same confirmation cannot be slashed again
+        assert_eq!(
+            t.client
+                .try_slash(&t.slasher, &oracle, &escrow_id, &2u32, &500, &harmed),
+            Err(Ok(ContractError::AlreadySlashed))
+        );
+
+        // A slash for another wrong confirmation below the minimum bond
+        // deregisters the oracle
+        let other_escrow = Bytes::from_slice(&t.env, b"escrow_slash_2");
+        assert!(confirm(
+            &t.env,
+            &t.contract_id,
+            &oracle,
+            &key,
+            &other_escrow,
+            2,
+            &result
+        ));
+        t.client
+            .slash(&t.slasher, &oracle, &other_escrow, &2u32, &1_000, &harmed);
+        assert!(!t.client.is_oracle_registered_query(&oracle));
+        assert_eq!(t.client.get_slash_history(&oracle).len(), 2);
+    }
+
+    #[test]
+    fn test_slash_reaches_unbonding_stake() {
+        let t = setup_bonded();
+        let (oracle, key) = bonded_oracle(&t, 1, 2_000);
+        let harmed = Address::generate(&t.env);
+
+        let escrow_id = Bytes::from_slice(&t.env, b"escrow_exit");
+        let result = Bytes::from_slice(&t.env, b"delivered");
+        assert!(confirm(
+            &t.env,
+            &t.contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+
+        // Exiting does not escape a slash during the unbonding period
+        t.client.request_unbond(&oracle, &2_000);
+        let slashed = t
+            .client
+            .slash(&t.slasher, &oracle, &escrow_id, &2u32, &5_000, &harmed);
+        assert_eq!(slashed, 2_000);
+
+        t.env.ledger().with_mut(|li| li.timestamp += 86_400);
+        assert_eq!(
+            t.client.try_withdraw_unbonded(&oracle),
+            Err(Ok(ContractError::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_slash_requires_approved_slasher() {
+        let t = setup_bonded();
+        let (oracle, key) = bonded_oracle(&t, 1, 2_000);
+        let harmed = Address::generate(&t.env);
+
+        let escrow_id = Bytes::from_slice(&t.env, b"escrow_auth");
+        let result = Bytes::from_slice(&t.env, b"delivered");
+        assert!(confirm(
+            &t.env,
+            &t.contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+
+        let stranger = Address::generate(&t.env);
+        assert_eq!(
+            t.client
+                .try_slash(&stranger, &oracle, &escrow_id, &2u32, &500, &harmed),
+            Err(Ok(ContractError::Unauthorized))
+        );
+
+        // Nothing to slash without a matching confirmation
+        assert_eq!(
+            t.client
+                .try_slash(&t.slasher, &oracle, &escrow_id, &1u32, &500, &harmed),
+            Err(Ok(ContractError::ConfirmationNotFound))
+        );
+    }
+
+    fn setup_feed(sources: u32) -> (Env, OracleAdapterClient<'static>, Bytes, Vec<Address>) {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let client = unsafe {
+            core::mem::transmute::<OracleAdapterClient<'_>, OracleAdapterClient<'static>>(client)
+        };
+        client.initialize(&Address::generate(&env));
+
+        let mut oracles = Vec::new(&env);
+        for seed in 1..=sources as u8 {
+            let (oracle, key) = new_oracle(&env, seed);
+            client.add_oracle(&oracle, &public_key(&env, &key));
+            oracles.push_back(oracle);
+        }
+
+        let feed_id = Bytes::from_slice(&env, b"cocoa");
+        client.set_feed_config(
+            &feed_id,
+            &FeedConfig {
+                min_sources: 3,
+                max_deviation_bps: 500,
+                heartbeat: 3_600,
+            },
+        );
+        (env, client, feed_id, oracles)
+    }
+
+    #[test]
+    fn test_price_feed_publishes_median_without_outliers() {
+        let (env, client, feed_id, oracles) = setup_feed(4);
+
+        assert_eq!(
+            client.submit_price(&oracles.get(0).unwrap(), &feed_id, &1_000),
+            None
+        );
+        assert_eq!(
+            client.submit_price(&oracles.get(1).unwrap(), &feed_id, &1_020),
+            None
+        );
+        assert_eq!(
+            client.try_get_price(&feed_id),
+            Err(Ok(ContractError::PriceUnavailable))
+        );
+
+        // A wildly off value is discarded, leaving too few agreeing sources
+        assert_eq!(
+            client.submit_price(&oracles.get(2).unwrap(), &feed_id, &5_000),
+            None
+        );
+
+        let price = client
+            .submit_price(&oracles.get(3).unwrap(), &feed_id, &990)
+            .unwrap();
+        assert_eq!(price.value, 1_000);
+        assert_eq!(price.sources, 3);
+        assert_eq!(price.updated_at, env.ledger().timestamp());
+        assert_eq!(client.get_price(&feed_id), price);
+    }
+
+    #[test]
+    fn test_price_feed_refuses_stale_price() {
+        let (env, client, feed_id, oracles) = setup_feed(3);
+        for (oracle, value) in oracles.iter().zip([1_000i128, 1_010, 1_030]) {
+            client.submit_price(&oracle, &feed_id, &value);
+        }
+        assert_eq!(client.get_price(&feed_id).value, 1_010);
+
+        env.ledger().with_mut(|li| li.timestamp += 3_601);
+        assert_eq!(
+            client.try_get_price(&feed_id),
+            Err(Ok(ContractError::StalePrice))
+        );
+        assert_eq!(client.get_latest_price(&feed_id).unwrap().value, 1_010);
+
+        // Old submissions no longer count toward a new round
+        assert_eq!(
+            client.submit_price(&oracles.get(0).unwrap(), &feed_id, &1_200),
+            None
+        );
+        assert_eq!(
+            client.try_get_price(&feed_id),
+            Err(Ok(ContractError::StalePrice))
+        );
+    }
+
+    #[test]
+    fn test_price_feed_requires_registered_oracle_and_config() {
+        let (env, client, feed_id, oracles) = setup_feed(3);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_submit_price(&stranger, &feed_id, &1_000),
+            Err(Ok(ContractError::OracleNotRegistered))
+        );
+
+        let unknown_feed = Bytes::from_slice(&env, b"coffee");
+        assert_eq!(
+            client.try_submit_price(&oracles.get(0).unwrap(), &unknown_feed, &1_000),
+            Err(Ok(ContractError::FeedNotConfigured))
+        );
+        assert_eq!(
+            client.try_submit_price(&oracles.get(0).unwrap(), &feed_id, &0),
+            Err(Ok(ContractError::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_revoked_confirmation_remains_slashable() {
+        let t = setup_bonded();
+        let (oracle, key) = bonded_oracle(&t, 1, 2_000);
+        let harmed = Address::generate(&t.env);
+
+        let escrow_id = Bytes::from_slice(&t.env, b"escrow_revoked");
+        let result = Bytes::from_slice(&t.env, b"delivered");
+        assert!(confirm(
+            &t.env,
+            &t.contract_id,
+            &oracle,
+            &key,
+            &escrow_id,
+            2,
+            &result
+        ));
+        t.client.revoke_confirmation(&oracle, &escrow_id, &2u32);
+
+        assert_eq!(
+            t.client
+                .slash(&t.slasher, &oracle, &escrow_id, &2u32, &500, &harmed),
+            500
+        );
+    }
+}
@@ -5,11 +5,16 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+};
 
 /// Default protocol fee in basis points (50 = 0.5%)
 const DEFAULT_FEE_BPS: u32 = 50;
 
+/// Newest fee and share storage layout this code supports
+const CONTRACT_VERSION: u32 = 1;
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -23,6 +28,10 @@ pub enum ContractError {
     NoFeesAvailable = 4,
     InvalidFee = 5,
     ZeroAmount = 6,
+    DepositorNotAuthorized = 7,
+    FeeNotReceived = 8,
+    InsufficientBalance = 9,
+    InvalidVersion = 10,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -117,21 +126,79 @@ impl ProtocolTreasury {
             .unwrap_or(DEFAULT_FEE_BPS)
     }
 
-    /// Record a fee deposit. Called by other contracts after transferring
-    /// tokens to the treasury address.
-    pub fn deposit_fee(env: Env, asset: Address, amount: i128) -> Result<(), ContractError> {
+    /// Authorize or revoke a contract allowed to deposit fees (admin only).
+    pub fn set_depositor(
+        env: Env,
+        depositor: Address,
+        authorized: bool,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        let key = (symbol_short!("depositor"), depositor.clone());
+        if authorized {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events()
+            .publish((symbol_short!("dep_set"),), (depositor, authorized));
+
+        Ok(())
+    }
+
+    /// Check whether a contract is allowed to deposit fees.
+    pub fn is_depositor(env: Env, depositor: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("depositor"), depositor))
+            .unwrap_or(false)
+    }
+
+    /// Record a fee deposit. Called by authorized contracts after transferring
+    /// `amount` of `asset` to the treasury address.
+    ///
+    /// The treasury's token balance must cover every fee recorded so far plus
+    /// this deposit, so fees that were never funded cannot be recorded.
+    pub fn deposit_fee(
+        env: Env,
+        depositor: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        depositor.require_auth();
+
+        if !Self::is_depositor(env.clone(), depositor.clone()) {
+            return Err(ContractError::DepositorNotAuthorized);
+        }
+
         if amount <= 0 {
             return Err(ContractError::ZeroAmount);
         }
 
+        // Fees recorded but not yet claimed must all be backed by the balance
+        let held_key = (symbol_short!("held"), asset.clone());
+        let held: i128 = env.storage().persistent().get(&held_key).unwrap_or(0);
+        let balance = token::Client::new(&env, &asset).balance(&env.current_contract_address());
+        if balance - held < amount {
+            return Err(ContractError::FeeNotReceived);
+        }
+
         let key = (symbol_short!("fees"), asset.clone());
         let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
         env.storage()
             .persistent()
             .set(&key, &(current + amount));
+        env.storage().persistent().set(&held_key, &(held + amount));
 
         env.events()
-            .publish((symbol_short!("fee_dep"),), (asset, amount));
+            .publish((symbol_short!("fee_dep"),), (depositor, asset, amount));
 
         Ok(())
     }
@@ -265,15 +332,26 @@ impl ProtocolTreasury {
             return Err(ContractError::NoFeesAvailable);
         }
 
-        // Transfer tokens to contributor
+        // Never pay out more than the treasury actually holds
         let token_client = token::Client::new(&env, &asset);
+        if token_client.balance(&env.current_contract_address()) < claimable {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        // Transfer tokens to contributor
         token_client.transfer(&env.current_contract_address(), &contributor, &claimable);
 
-        // Update claimed amount
+        // Update claimed amount and release it from the held balance
         env.storage()
             .persistent()
             .set(&claim_key, &(already_claimed + claimable));
 
+        let held_key = (symbol_short!("held"), asset.clone());
+        let held: i128 = env.storage().persistent().get(&held_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&held_key, &(held - claimable).max(0));
+
         env.events()
             .publish((symbol_short!("claimed"),), (contributor, asset, claimable));
 
@@ -299,6 +377,86 @@ impl ProtocolTreasury {
             .get(&symbol_short!("tot_wt"))
             .unwrap_or(0)
     }
+
+    /// Set the governance contract allowed to upgrade the treasury.
+    ///
+    /// The admin sets it once; afterwards only the current governance can replace it.
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&symbol_short!("gov"));
+        match current {
+            Some(current) => current.require_auth(),
+            None => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("admin"))
+                    .ok_or(ContractError::Unauthorized)?;
+                admin.require_auth();
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
+        env.events()
+            .publish((symbol_short!("gov_set"),), (governance,));
+
+        Ok(())
+    }
+
+    /// Swap the treasury's code for an uploaded Wasm (governance only).
+    /// Collected fees and contributor shares stay in place and remain claimable.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    /// Record that fee and share storage has been moved to `version` (governance only).
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("version"), &version);
+
+        env.events()
+            .publish((symbol_short!("migrated"),), (current, version));
+
+        Ok(())
+    }
+
+    /// Query the storage layout version of the treasury's records.
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -308,13 +466,17 @@ impl ProtocolTreasury {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, token, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, MockAuth, MockAuthInvoke},
+        token, Env, IntoVal,
+    };
 
     struct TestEnv<'a> {
         env: Env,
         client: ProtocolTreasuryClient<'a>,
         treasury_addr: Address,
         admin: Address,
+        depositor: Address,
         token_addr: Address,
     }
 
@@ -333,6 +495,10 @@ mod test {
 
         client.initialize(&admin);
 
+        // Stand-in for an escrow/loan contract allowed to deposit fees
+        let depositor = Address::generate(&env);
+        client.set_depositor(&depositor, &true);
+
         let client = unsafe {
             core::mem::transmute::<ProtocolTreasuryClient<'_>, ProtocolTreasuryClient<'static>>(
                 client,
@@ -344,6 +510,7 @@ mod test {
             client,
             treasury_addr,
             admin,
+            depositor,
             token_addr,
         }
     }
@@ -353,6 +520,12 @@ mod test {
         token_admin_client.mint(&t.treasury_addr, &amount);
     }
 
+    /// Fund the treasury and record the fee, as a depositing contract would.
+    fn deposit(t: &TestEnv, amount: i128) {
+        mint_to_treasury(t, amount);
+        t.client.deposit_fee(&t.depositor, &t.token_addr, &amount);
+    }
+
     #[test]
     fn test_initialize() {
         let t = setup();
@@ -400,10 +573,10 @@ mod test {
     #[test]
     fn test_deposit_fee() {
         let t = setup();
-        t.client.deposit_fee(&t.token_addr, &500);
+        deposit(&t, 500);
         assert_eq!(t.client.get_total_fees(&t.token_addr), 500);
 
-        t.client.deposit_fee(&t.token_addr, &300);
+        deposit(&t, 300);
         assert_eq!(t.client.get_total_fees(&t.token_addr), 800);
     }
 
@@ -411,7 +584,41 @@ mod test {
     #[should_panic(expected = "HostError: Error(Contract, #6)")]
     fn test_deposit_fee_zero() {
         let t = setup();
-        t.client.deposit_fee(&t.token_addr, &0);
+        t.client.deposit_fee(&t.depositor, &t.token_addr, &0);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #7)")]
+    fn test_deposit_fee_unauthorized_depositor() {
+        let t = setup();
+        let stranger = Address::generate(&t.env);
+        mint_to_treasury(&t, 500);
+        t.client.deposit_fee(&stranger, &t.token_addr, &500);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #8)")]
+    fn test_deposit_fee_not_funded() {
+        let t = setup();
+        t.client.deposit_fee(&t.depositor, &t.token_addr, &500);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #8)")]
+    fn test_deposit_fee_cannot_reuse_balance() {
+        let t = setup();
+        deposit(&t, 500);
+        // The 500 already backs the first deposit
+        t.client.deposit_fee(&t.depositor, &t.token_addr, &500);
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #7)")]
+    fn test_revoked_depositor() {
+        let t = setup();
+        t.client.set_depositor(&t.depositor, &false);
+        assert!(!t.client.is_depositor(&t.depositor));
+        deposit(&t, 500);
     }
 
     #[test]
@@ -470,8 +677,7 @@ mod test {
         t.client.register_contributor(&contributor, &100);
 
         // Deposit fees and mint tokens to treasury
-        t.client.deposit_fee(&t.token_addr, &1000);
-        mint_to_treasury(&t, 1000);
+        deposit(&t, 1000);
 
         // Claim share (100% since sole contributor)
         let claimed = t.client.claim_share(&contributor, &t.token_addr);
@@ -492,8 +698,7 @@ mod test {
         t.client.register_contributor(&c1, &75);
         t.client.register_contributor(&c2, &25);
 
-        t.client.deposit_fee(&t.token_addr, &1000);
-        mint_to_treasury(&t, 1000);
+        deposit(&t, 1000);
 
         let claimed1 = t.client.claim_share(&c1, &t.token_addr);
         assert_eq!(claimed1, 750);
@@ -514,14 +719,12 @@ mod test {
         t.client.register_contributor(&contributor, &100);
 
         // First deposit
-        t.client.deposit_fee(&t.token_addr, &500);
-        mint_to_treasury(&t, 500);
+        deposit(&t, 500);
         let claimed1 = t.client.claim_share(&contributor, &t.token_addr);
         assert_eq!(claimed1, 500);
 
         // Second deposit
-        t.client.deposit_fee(&t.token_addr, &300);
-        mint_to_treasury(&t, 300);
+        deposit(&t, 300);
         let claimed2 = t.client.claim_share(&contributor, &t.token_addr);
         assert_eq!(claimed2, 300);
 
@@ -554,8 +757,7 @@ mod test {
         let contributor = Address::generate(&t.env);
 
         t.client.register_contributor(&contributor, &100);
-        t.client.deposit_fee(&t.token_addr, &500);
-        mint_to_treasury(&t, 500);
+        deposit(&t, 500);
 
         t.client.claim_share(&contributor, &t.token_addr);
         // Second claim should fail - nothing new to claim
@@ -573,4 +775,33 @@ mod test {
         let t = setup();
         assert_eq!(t.client.get_fee_bps(), DEFAULT_FEE_BPS);
     }
+
+    #[test]
+    fn test_governance_replaced_only_by_current_governance() {
+        let t = setup();
+        let governance = Address::generate(&t.env);
+        let successor = Address::generate(&t.env);
+        t.client.set_governance(&governance);
+
+        let args = (successor.clone(),).into_val(&t.env);
+        let invoke = MockAuthInvoke {
+            contract: &t.treasury_addr,
+            fn_name: "set_governance",
+            args,
+            sub_invokes: &[],
+        };
+
+        // The admin cannot take the role back once governance holds it
+        t.env.mock_auths(&[MockAuth {
+            address: &t.admin,
+            invoke: &invoke,
+        }]);
+        assert!(t.client.try_set_governance(&successor).is_err());
+
+        t.env.mock_auths(&[MockAuth {
+            address: &governance,
+            invoke: &invoke,
+        }]);
+        t.client.set_governance(&successor);
+    }
 }
@@ -5,7 +5,10 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env};
+
+/// Profile storage layout assumed by this code
+const CONTRACT_VERSION: u32 = 1;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -18,6 +21,7 @@ pub enum ContractError {
     AlreadyInitialized = 2,
     ProfileNotFound = 3,
     InvalidValue = 4,
+    InvalidVersion = 5,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -336,6 +340,87 @@ impl ReputationRegistry {
 
         Ok(())
     }
+
+    /// Set the governance contract allowed to upgrade the registry
+    ///
+    /// The admin sets it once; afterwards only the current governance can
+    /// replace it.
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current: Option<Address> = env.storage().instance().get(&symbol_short!("gov"));
+        match current {
+            Some(current) => current.require_auth(),
+            None => {
+                let admin: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("admin"))
+                    .ok_or(ContractError::Unauthorized)?;
+                admin.require_auth();
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
+        env.events()
+            .publish((symbol_short!("gov_set"),), (governance,));
+
+        Ok(())
+    }
+
+    /// Swap the reputation registry's code for an uploaded Wasm (governance only)
+    /// Recorded SME scores carry over unchanged
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((symbol_short!("upgraded"),), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    /// Record that SME profiles have been moved to `version` (governance only)
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("version"), &version);
+
+        env.events()
+            .publish((symbol_short!("migrated"),), (current, version));
+
+        Ok(())
+    }
+
+    /// Storage layout version SME profiles are kept in
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
 }
 
 // ---------------------------------------------------------------------------
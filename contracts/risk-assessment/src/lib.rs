@@ -7,7 +7,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol,
+    contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, IntoVal,
+    Symbol, Val, Vec,
 };
 
 // ============================================================================
@@ -67,6 +68,9 @@ pub enum ContractError {
     AuctionNotActive = 28,
     BidBelowDebtFloor = 29,
     AuctionNotExpired = 30,
+
+    // Upgrade errors
+    InvalidVersion = 31,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -145,15 +149,15 @@ pub enum LoanStatus {
     Liquidated = 3,
 }
 
-/// Escrow status (mirrors StelloVault)
+/// Escrow status (mirrors EscrowManager)
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EscrowStatus {
-    Pending = 0,
-    Active = 1,
-    Released = 2,
-    Cancelled = 3,
-    Disputed = 4,
+    Active = 0,
+    Released = 1,
+    Refunded = 2,
+    Disputed = 3,
+    Resolved = 4,
 }
 
 /// Aggregated position data for risk calculation
@@ -232,7 +236,7 @@ pub enum AuctionStatus {
 
 /// State of a Dutch Auction for one defaulted loan
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AuctionState {
     pub loan_id: u64,
     pub collateral_value: i128,
@@ -255,7 +259,9 @@ pub struct AuctionState {
 // External Contract Data Structures (for cross-contract calls)
 // ============================================================================
 
-/// Loan data structure (from LoanManagement)
+/// Loan view used by the risk engine.
+///
+/// `amount` is the outstanding principal (principal repaid is already netted out).
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Loan {
@@ -269,7 +275,7 @@ pub struct Loan {
     pub status: LoanStatus,
 }
 
-/// Collateral data structure (from CollateralRegistry)
+/// Collateral view used by the risk engine
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Collateral {
@@ -283,7 +289,7 @@ pub struct Collateral {
     pub locked: bool,
 }
 
-/// Trade escrow data structure (from StelloVault)
+/// Trade escrow view used by the risk engine
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct TradeEscrow {
@@ -294,12 +300,110 @@ pub struct TradeEscrow {
     pub amount: i128,
     pub asset: Address,
     pub status: EscrowStatus,
-    pub oracle_address: Address,
-    pub release_conditions: Symbol,
     pub expiry_ts: u64,
     pub created_at: u64,
 }
 
+/// `Loan` exactly as returned by `LoanManagement::get_loan`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LoanRecord {
+    pub id: u64,
+    pub escrow_id: u64,
+    pub borrower: Address,
+    pub lender: Address,
+    pub amount: i128,
+    pub interest_rate: u32,
+    pub deadline: u64,
+    pub status: LoanStatus,
+    pub principal_repaid: i128,
+    pub interest_repaid: i128,
+    pub last_repayment_ts: u64,
+}
+
+/// `Collateral` exactly as returned by `CollateralRegistry::get_collateral`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CollateralRecord {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub collateral_type: u32,
+}
+
+/// `Escrow` exactly as returned by `EscrowManager::get_escrow`
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowRecord {
+    pub id: u64,
+    pub buyer: Address,
+    pub seller: Address,
+    pub lender: Address,
+    pub collateral_id: u64,
+    pub amount: i128,
+    pub asset: Address,
+    pub required_confirmation: u32,
+    pub status: EscrowStatus,
+    pub expiry_ts: u64,
+    pub created_at: u64,
+    pub destination_asset: Address,
+    pub min_destination_amount: i128,
+    pub required_confirmations: u32,
+    pub oracle_set: Vec<Address>,
+}
+
+impl From<LoanRecord> for Loan {
+    fn from(record: LoanRecord) -> Self {
+        Loan {
+            id: record.id,
+            escrow_id: record.escrow_id,
+            borrower: record.borrower,
+            lender: record.lender,
+            amount: record.amount - record.principal_repaid,
+            interest_rate: record.interest_rate,
+            deadline: record.deadline,
+            status: record.status,
+        }
+    }
+}
+
+impl From<CollateralRecord> for Collateral {
+    fn from(record: CollateralRecord) -> Self {
+        Collateral {
+            id: record.id,
+            owner: record.owner,
+            face_value: record.face_value,
+            realized_value: record.realized_value,
+            expiry_ts: record.expiry_ts,
+            registered_at: record.registered_at,
+            last_valuation_ts: record.last_valuation_ts,
+            locked: record.locked,
+        }
+    }
+}
+
+impl From<EscrowRecord> for TradeEscrow {
+    fn from(record: EscrowRecord) -> Self {
+        TradeEscrow {
+            buyer: record.buyer,
+            seller: record.seller,
+            lender: record.lender,
+            collateral_token_id: record.collateral_id,
+            amount: record.amount,
+            asset: record.asset,
+            status: record.status,
+            expiry_ts: record.expiry_ts,
+            created_at: record.created_at,
+        }
+    }
+}
+
 // ============================================================================
 // Event Symbols
 // ============================================================================
@@ -317,6 +421,11 @@ const EVT_AUC_START: Symbol = symbol_short!("auc_start");
 const EVT_AUC_BID: Symbol = symbol_short!("auc_bid");
 const EVT_AUC_SETL: Symbol = symbol_short!("auc_setl");
 const EVT_AUC_EXP: Symbol = symbol_short!("auc_exp");
+const EVT_UPGRADED: Symbol = symbol_short!("upgraded");
+const EVT_MIGRATED: Symbol = symbol_short!("migrated");
+
+/// Parameter and auction layout this code reads; the recorded one lags until `migrate`
+const CONTRACT_VERSION: u32 = 1;
 
 // ============================================================================
 // Contract Definition
@@ -406,6 +515,63 @@ impl RiskAssessment {
             .unwrap()
     }
 
+    // ========================================================================
+    // Upgrades
+    // ========================================================================
+
+    /// Swap the risk engine's code for an uploaded Wasm (governance only)
+    /// Uses the governance address fixed at initialization; running auctions
+    /// keep their bids and deadlines across the swap
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish((EVT_UPGRADED,), (new_wasm_hash,));
+
+        Ok(())
+    }
+
+    /// Record that parameters and auctions have been moved to `version` (governance only)
+    /// Bid and deadline records written by older code must be converted first
+    pub fn migrate(env: Env, version: u32) -> Result<(), ContractError> {
+        let governance: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        let current = Self::version(env.clone());
+        if version <= current || version > CONTRACT_VERSION {
+            return Err(ContractError::InvalidVersion);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("version"), &version);
+
+        env.events().publish((EVT_MIGRATED,), (current, version));
+
+        Ok(())
+    }
+
+    /// Storage layout version parameters and auctions are kept in
+    pub fn version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("version"))
+            .unwrap_or(1)
+    }
+
     // ========================================================================
     // Health Factor Calculation
     // ========================================================================
@@ -415,6 +581,10 @@ impl RiskAssessment {
     /// Formula: (Collateral Value * Liquidation Threshold) / Total Debt
     /// Returns basis points (10000 = 1.0 healthy, <10000 = at risk)
     ///
+    /// Collateral bound to a price feed is valued at the aggregated oracle
+    /// price; the calculation fails with CollateralRegistry's valuation error
+    /// while that price is stale.
+    ///
     /// # Arguments
     /// * `position_id` - The escrow ID representing the position
     pub fn calculate_health_factor(env: Env, position_id: u64) -> Result<u32, ContractError> {
@@ -562,17 +732,13 @@ impl RiskAssessment {
         // has any existing positions and assigns risk based on that
         
         // Try to get borrower's risk history from storage (for testing/simulation)
-        let borrower_risk_key = (symbol_short!("borrower_risk"), borrower.clone());
+        let borrower_risk_key = (symbol_short!("brw_risk"), borrower);
         if let Some(stored_risk) = env.storage().persistent().get::<_, u32>(&borrower_risk_key) {
             return Ok(stored_risk);
         }
         
-        // If no stored risk data, use a simple heuristic based on address
-        // In production, this would be replaced with actual position analysis
-        let address_bytes = borrower.to_fixed_bytes();
-        let risk_score = (address_bytes[31] % 4) as u32; // 0-3 range
-        
-        Ok(risk_score)
+        // If no stored risk data, default to Warning (matches LoanManagement's fallback)
+        Ok(1)
     }
 
     /// Set borrower risk factor (for testing purposes only)
@@ -582,7 +748,7 @@ impl RiskAssessment {
             return Err(ContractError::InvalidHealthFactor);
         }
         
-        let borrower_risk_key = (symbol_short!("borrower_risk"), borrower);
+        let borrower_risk_key = (symbol_short!("brw_risk"), borrower);
         env.storage().persistent().set(&borrower_risk_key, &risk_factor);
         Ok(())
     }
@@ -1045,33 +1211,177 @@ impl RiskAssessment {
     }
 
     /// Fetch position data from external contracts
-    /// In production, this would use cross-contract calls
-    /// For now, we use storage simulation for testing
+    ///
+    /// A position is identified by its escrow ID. The loan is read from
+    /// LoanManagement via its escrow mapping, the escrow from the vault
+    /// (EscrowManager), and the collateral from CollateralRegistry.
     fn fetch_position_data(
         env: &Env,
         position_id: u64,
     ) -> Result<(Loan, Collateral, TradeEscrow), ContractError> {
-        // Try to get from test storage first (for unit tests)
+        // Positions seeded via set_test_position take precedence in unit tests
+        #[cfg(any(test, feature = "testutils"))]
+        if let Some(position) = Self::fetch_test_position(env, position_id) {
+            return Ok(position);
+        }
+
+        let loan_mgr: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("loan_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+        let position_args: Vec<Val> = Vec::from_array(env, [position_id.into_val(env)]);
+        let loan_id: u64 = env
+            .invoke_contract::<Option<u64>>(
+                &loan_mgr,
+                &Symbol::new(env, "get_loan_id_by_escrow"),
+                position_args,
+            )
+            .ok_or(ContractError::LoanNotFound)?;
+        let loan_args: Vec<Val> = Vec::from_array(env, [loan_id.into_val(env)]);
+        let loan: LoanRecord = env
+            .invoke_contract::<Option<LoanRecord>>(
+                &loan_mgr,
+                &Symbol::new(env, "get_loan"),
+                loan_args,
+            )
+            .ok_or(ContractError::LoanNotFound)?;
+
+        let (collateral, escrow) = Self::fetch_collateral_data(env, position_id)?;
+
+        Ok((loan.into(), collateral, escrow))
+    }
+
+    /// Fetch the escrow for a position from the vault and the collateral it
+    /// locks from CollateralRegistry
+    fn fetch_collateral_data(
+        env: &Env,
+        position_id: u64,
+    ) -> Result<(Collateral, TradeEscrow), ContractError> {
+        let coll_reg: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+        let vault: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("vault"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let escrow_args: Vec<Val> = Vec::from_array(env, [position_id.into_val(env)]);
+        let escrow: EscrowRecord = env
+            .invoke_contract::<Option<EscrowRecord>>(
+                &vault,
+                &Symbol::new(env, "get_escrow"),
+                escrow_args,
+            )
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        let coll_args: Vec<Val> = Vec::from_array(env, [escrow.collateral_id.into_val(env)]);
+        let collateral: CollateralRecord = env
+            .invoke_contract::<Option<CollateralRecord>>(
+                &coll_reg,
+                &Symbol::new(env, "get_collateral"),
+                coll_args,
+            )
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        // Collateral bound to a price feed is valued at the aggregated
+        // oracle price; the registry's own error (e.g. a stale price) aborts
+        let mut collateral: Collateral = collateral.into();
+        let value_args: Vec<Val> = Vec::from_array(env, [escrow.collateral_id.into_val(env)]);
+        collateral.realized_value = env.invoke_contract::<i128>(
+            &coll_reg,
+            &Symbol::new(env, "get_valuation"),
+            value_args,
+        );
+
+        Ok((collateral, escrow.into()))
+    }
+
+    #[cfg(any(test, feature = "testutils"))]
+    fn fetch_test_position(env: &Env, position_id: u64) -> Option<(Loan, Collateral, TradeEscrow)> {
         let loan_key = (symbol_short!("test_loan"), position_id);
         let coll_key = (symbol_short!("test_coll"), position_id);
         let escrow_key = (symbol_short!("test_escr"), position_id);
 
-        let loan: Loan = env.storage()
-            .persistent()
-            .get(&loan_key)
-            .ok_or(ContractError::LoanNotFound)?;
+        let loan: Loan = env.storage().persistent().get(&loan_key)?;
+        let collateral: Collateral = env.storage().persistent().get(&coll_key)?;
+        let escrow: TradeEscrow = env.storage().persistent().get(&escrow_key)?;
 
-        let collateral: Collateral = env.storage()
-            .persistent()
-            .get(&coll_key)
-            .ok_or(ContractError::CollateralNotFound)?;
+        Some((loan, collateral, escrow))
+    }
+
+    /// Persist a fresh Active auction for a position and emit `auc_start`
+    fn open_auction(
+        env: &Env,
+        loan_id: u64,
+        collateral_value: i128,
+        debt_floor: i128,
+    ) -> Result<AuctionState, ContractError> {
+        let cfg = Self::get_auction_config(env.clone());
+        let now = env.ledger().timestamp();
+        let ends_at = now.checked_add(cfg.duration).ok_or(ContractError::MathOverflow)?;
+
+        let state = AuctionState {
+            loan_id,
+            collateral_value,
+            debt_floor,
+            started_at: now,
+            ends_at,
+            status: AuctionStatus::Active,
+            winner: None,
+            winning_bid: 0,
+            debt_covered: 0,
+            borrower_surplus: 0,
+            auction_fee: 0,
+        };
 
-        let escrow: TradeEscrow = env.storage()
+        env.storage()
             .persistent()
-            .get(&escrow_key)
-            .ok_or(ContractError::EscrowNotFound)?;
+            .set(&(symbol_short!("auction"), loan_id), &state);
+
+        env.events().publish(
+            (EVT_AUC_START,),
+            (loan_id, collateral_value, debt_floor, ends_at),
+        );
+
+        Ok(state)
+    }
 
-        Ok((loan, collateral, escrow))
+    /// Transfer settlement of a won auction to LoanManagement and CollateralRegistry
+    ///
+    /// The loan is closed as liquidated with `recovered` paid to its lender,
+    /// and the collateral changes hands to the winning bidder, releasing the
+    /// lien of the loan's escrow.
+    fn settle_auction(
+        env: &Env,
+        loan_id: u64,
+        escrow_id: u64,
+        collateral_id: u64,
+        winner: &Address,
+        recovered: i128,
+    ) -> Result<(), ContractError> {
+        let loan_mgr: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("loan_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+        let coll_reg: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        let liq_args: Vec<Val> = Vec::from_array(
+            env,
+            [loan_id.into_val(env), winner.into_val(env), recovered.into_val(env)],
+        );
+        env.invoke_contract::<()>(&loan_mgr, &Symbol::new(env, "mark_liquidated"), liq_args);
+
+        let seize_args: Vec<Val> = Vec::from_array(
+            env,
+            [collateral_id.into_val(env), escrow_id.into_val(env), winner.into_val(env)],
+        );
+        env.invoke_contract::<()>(&coll_reg, &Symbol::new(env, "seize_collateral"), seize_args);
+
+        Ok(())
     }
 
     /// Set test data for a position (for testing only)
@@ -1123,9 +1433,10 @@ impl RiskAssessment {
     // Dutch Auction — Core Functions
     // ========================================================================
 
-    /// Start a Dutch Auction for a defaulted / undercollateralised loan.
+    /// Start a Dutch Auction for an undercollateralised loan.
     ///
-    /// Anyone may trigger this once the loan is liquidatable. The starting price
+    /// Anyone may trigger this once the loan is liquidatable. Defaulted loans
+    /// are auctioned through `start_default_auction` instead. The starting price
     /// equals the full collateral value and decays linearly toward the debt floor
     /// over the configured auction duration.
     pub fn start_auction(
@@ -1171,32 +1482,45 @@ impl RiskAssessment {
             .checked_add(interest)
             .ok_or(ContractError::MathOverflow)?;
 
-        let cfg = Self::get_auction_config(env.clone());
-        let now = env.ledger().timestamp();
-        let ends_at = now.checked_add(cfg.duration).ok_or(ContractError::MathOverflow)?;
+        Self::open_auction(&env, loan_id, collateral.realized_value, debt_floor)
+    }
 
-        let state = AuctionState {
-            loan_id,
-            collateral_value: collateral.realized_value,
-            debt_floor,
-            started_at: now,
-            ends_at,
-            status: AuctionStatus::Active,
-            winner: None,
-            winning_bid: 0,
-            debt_covered: 0,
-            borrower_surplus: 0,
-            auction_fee: 0,
-        };
+    /// Start a Dutch Auction for a loan LoanManagement has just marked defaulted.
+    ///
+    /// Only callable by the LoanManagement contract. A defaulted loan is
+    /// auctioned regardless of its health factor; `debt` is the outstanding
+    /// amount (principal + accrued interest) at the time of default and becomes
+    /// the auction's debt floor. The loan itself is not read back here since
+    /// LoanManagement is still on the call stack.
+    pub fn start_default_auction(
+        env: Env,
+        position_id: u64,
+        debt: i128,
+    ) -> Result<AuctionState, ContractError> {
+        let loan_mgr: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("loan_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+        loan_mgr.require_auth();
 
-        env.storage().persistent().set(&auc_key, &state);
+        let paused: bool = env.storage()
+            .instance()
+            .get(&symbol_short!("paused"))
+            .unwrap_or(false);
+        if paused {
+            return Err(ContractError::LiquidationsPaused);
+        }
 
-        env.events().publish(
-            (EVT_AUC_START,),
-            (loan_id, collateral.realized_value, debt_floor, ends_at),
-        );
+        let auc_key = (symbol_short!("auction"), position_id);
+        if let Some(existing) = env.storage().persistent().get::<_, AuctionState>(&auc_key) {
+            if existing.status == AuctionStatus::Active {
+                return Err(ContractError::AuctionAlreadyActive);
+            }
+        }
 
-        Ok(state)
+        let (collateral, _escrow) = Self::fetch_collateral_data(&env, position_id)?;
+
+        Self::open_auction(&env, position_id, collateral.realized_value, debt)
     }
 
     /// Compute the current Dutch-Auction price for a loan.
@@ -1244,6 +1568,8 @@ impl RiskAssessment {
     /// immediately and triggers settlement:
     ///  - `debt_floor` goes to the lender.
     ///  - surplus above `debt_floor` minus auction fee goes to borrower.
+    ///  - the loan is marked liquidated in LoanManagement.
+    ///  - the collateral is transferred to the bidder in CollateralRegistry.
     pub fn bid_auction(
         env: Env,
         loan_id: u64,
@@ -1285,9 +1611,8 @@ impl RiskAssessment {
             return Err(ContractError::BidBelowDebtFloor);
         }
 
-        // Fetch escrow asset for token transfers
-        let (_loan, _collateral, escrow) = Self::fetch_position_data(&env, loan_id)?;
-        let (loan, _collateral2, _escrow2) = Self::fetch_position_data(&env, loan_id)?;
+        // Fetch parties and escrow asset for token transfers
+        let (loan, collateral, escrow) = Self::fetch_position_data(&env, loan_id)?;
 
         // Verify bid is >= current auction price
         let current_price = Self::get_auction_price(env.clone(), loan_id)?;
@@ -1330,6 +1655,16 @@ impl RiskAssessment {
 
         env.storage().persistent().set(&auc_key, &state);
 
+        // Close the loan and hand the collateral to the winner
+        Self::settle_auction(
+            &env,
+            loan.id,
+            loan.escrow_id,
+            collateral.id,
+            &bidder,
+            debt_covered,
+        )?;
+
         env.events().publish(
             (EVT_AUC_BID,),
             (loan_id, bidder.clone(), payment_amount),
@@ -1381,7 +1716,119 @@ impl RiskAssessment {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Env};
+    use soroban_sdk::{
+        contract, contracterror, testutils::Address as _, testutils::Ledger as _, Env,
+    };
+
+    /// LoanManagement stand-in with no loans on record
+    #[contract]
+    pub struct EmptyLoanManagement;
+
+    #[contractimpl]
+    impl EmptyLoanManagement {
+        pub fn get_loan_id_by_escrow(_env: Env, _escrow_id: u64) -> Option<u64> {
+            None
+        }
+    }
+
+    /// Contracts backing a single priced position; kept apart because their
+    /// entry points clash with the stand-ins above
+    mod priced_position {
+        use super::*;
+
+        #[contracterror]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub enum MockValuationError {
+            StaleValuation = 8,
+        }
+
+        /// LoanManagement stand-in holding a single 1000 loan at 0% for escrow 1
+        #[contract]
+        pub struct SingleLoanManagement;
+
+        #[contractimpl]
+        impl SingleLoanManagement {
+            pub fn get_loan_id_by_escrow(_env: Env, _escrow_id: u64) -> Option<u64> {
+                Some(1)
+            }
+
+            pub fn get_loan(env: Env, _loan_id: u64) -> Option<LoanRecord> {
+                Some(LoanRecord {
+                    id: 1,
+                    escrow_id: 1,
+                    borrower: env.current_contract_address(),
+                    lender: env.current_contract_address(),
+                    amount: 1000,
+                    interest_rate: 0,
+                    deadline: env.ledger().timestamp() + 86400,
+                    status: LoanStatus::Active,
+                    principal_repaid: 0,
+                    interest_repaid: 0,
+                    last_repayment_ts: 0,
+                })
+            }
+        }
+
+        /// EscrowManager stand-in with escrow 1 locking collateral 1
+        #[contract]
+        pub struct SingleEscrowVault;
+
+        #[contractimpl]
+        impl SingleEscrowVault {
+            pub fn get_escrow(env: Env, _escrow_id: u64) -> Option<EscrowRecord> {
+                let party = env.current_contract_address();
+                Some(EscrowRecord {
+                    id: 1,
+                    buyer: party.clone(),
+                    seller: party.clone(),
+                    lender: party.clone(),
+                    collateral_id: 1,
+                    amount: 1000,
+                    asset: party.clone(),
+                    required_confirmation: 2,
+                    status: EscrowStatus::Active,
+                    expiry_ts: env.ledger().timestamp() + 86400,
+                    created_at: 0,
+                    destination_asset: party,
+                    min_destination_amount: 0,
+                    required_confirmations: 1,
+                    oracle_set: Vec::new(&env),
+                })
+            }
+        }
+
+        /// CollateralRegistry stand-in storing 1000 but valuing collateral at
+        /// 2000 through a price feed that goes stale after an hour
+        #[contract]
+        pub struct PricedCollateralRegistry;
+
+        #[contractimpl]
+        impl PricedCollateralRegistry {
+            pub fn get_collateral(env: Env, id: u64) -> Option<CollateralRecord> {
+                Some(CollateralRecord {
+                    id,
+                    owner: env.current_contract_address(),
+                    face_value: 1000,
+                    realized_value: 1000,
+                    expiry_ts: env.ledger().timestamp() + 86400,
+                    metadata_hash: BytesN::from_array(&env, &[1; 32]),
+                    registered_at: 0,
+                    last_valuation_ts: 0,
+                    locked: true,
+                    collateral_type: 0,
+                })
+            }
+
+            pub fn get_valuation(env: Env, _id: u64) -> Result<i128, MockValuationError> {
+                if env.ledger().timestamp() > 3_600 {
+                    return Err(MockValuationError::StaleValuation);
+                }
+                Ok(2000)
+            }
+        }
+    }
+
+    use priced_position::{PricedCollateralRegistry, SingleEscrowVault, SingleLoanManagement};
 
     fn setup_env() -> (Env, Address, Address, Address, Address, Address) {
         let env = Env::default();
@@ -1429,8 +1876,6 @@ mod test {
             amount,
             asset: Address::generate(env),
             status: EscrowStatus::Active,
-            oracle_address: Address::generate(env),
-            release_conditions: symbol_short!("delivery"),
             expiry_ts: env.ledger().timestamp() + 86400,
             created_at: env.ledger().timestamp(),
         }
@@ -2168,8 +2613,9 @@ mod test {
 
     #[test]
     fn test_position_not_found() {
-        let (env, admin, governance, coll_reg, loan_mgr, vault) = setup_env();
+        let (env, admin, governance, coll_reg, _loan_mgr, vault) = setup_env();
         let contract_id = env.register(RiskAssessment, ());
+        let loan_mgr = env.register(EmptyLoanManagement, ());
 
         env.mock_all_auths();
 
@@ -2189,6 +2635,59 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_health_factor_uses_price_feed_valuation() {
+        let (env, admin, governance, _coll_reg, _loan_mgr, _vault) = setup_env();
+        let contract_id = env.register(RiskAssessment, ());
+        let coll_reg = env.register(PricedCollateralRegistry, ());
+        let loan_mgr = env.register(SingleLoanManagement, ());
+        let vault = env.register(SingleEscrowVault, ());
+
+        env.mock_all_auths();
+
+        env.as_contract(&contract_id, || {
+            RiskAssessment::initialize(
+                env.clone(),
+                admin.clone(),
+                governance.clone(),
+                coll_reg.clone(),
+                loan_mgr.clone(),
+                vault.clone(),
+            ).unwrap();
+
+            // 2000 aggregated value * 80% / 1000 debt, not the stored 1000
+            let hf = RiskAssessment::calculate_health_factor(env.clone(), 1).unwrap();
+            assert_eq!(hf, 16000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "HostError: Error(Contract, #8)")]
+    fn test_health_factor_passes_through_stale_valuation() {
+        let (env, admin, governance, _coll_reg, _loan_mgr, _vault) = setup_env();
+        let contract_id = env.register(RiskAssessment, ());
+        let coll_reg = env.register(PricedCollateralRegistry, ());
+        let loan_mgr = env.register(SingleLoanManagement, ());
+        let vault = env.register(SingleEscrowVault, ());
+
+        env.mock_all_auths();
+
+        env.as_contract(&contract_id, || {
+            RiskAssessment::initialize(
+                env.clone(),
+                admin.clone(),
+                governance.clone(),
+                coll_reg.clone(),
+                loan_mgr.clone(),
+                vault.clone(),
+            ).unwrap();
+
+            // The registry's StaleValuation surfaces unchanged
+            env.ledger().with_mut(|li| li.timestamp = 3_601);
+            let _ = RiskAssessment::calculate_health_factor(env.clone(), 1);
+        });
+    }
+
     #[test]
     fn test_no_pending_update() {
         let (env, admin, governance, coll_reg, loan_mgr, vault) = setup_env();